@@ -0,0 +1,56 @@
+use std::fmt;
+use std::path::PathBuf;
+
+
+use crate::objects::parsers::parse_error::ParseError;
+use crate::primitives::texture_error::TextureError;
+
+/// Errors from loading an asset by path.
+#[derive(Debug)]
+pub enum AssetError {
+    /// The file extension doesn't name a format that can be loaded as the requested asset.
+    UnsupportedFormat(PathBuf),
+    Parse(ParseError),
+    Image(image::ImageError),
+    Texture(TextureError),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetError::UnsupportedFormat(path) => write!(f, "unsupported file format: {}", path.display()),
+            AssetError::Parse(error) => write!(f, "failed to parse file: {}", error),
+            AssetError::Image(error) => write!(f, "failed to decode image: {}", error),
+            AssetError::Texture(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AssetError::UnsupportedFormat(_) => None,
+            AssetError::Parse(error) => Some(error),
+            AssetError::Image(error) => Some(error),
+            AssetError::Texture(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for AssetError {
+    fn from(error: ParseError) -> Self {
+        AssetError::Parse(error)
+    }
+}
+
+impl From<image::ImageError> for AssetError {
+    fn from(error: image::ImageError) -> Self {
+        AssetError::Image(error)
+    }
+}
+
+impl From<TextureError> for AssetError {
+    fn from(error: TextureError) -> Self {
+        AssetError::Texture(error)
+    }
+}
@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use glium::Display;
+use glium::glutin::surface::WindowSurface;
+use glium::texture::RawImage2d;
+
+use crate::assets::asset_error::AssetError;
+use crate::assets::file_watcher::FileWatcher;
+use crate::assets::handle::{Asset, AssetStorage, Handle};
+use crate::assets::worker_pool::WorkerPool;
+use crate::objects::intermediaries::mesh_cache::MeshCache;
+use crate::objects::intermediaries::ply_object::PlyObject;
+use crate::objects::intermediaries::stl_object::StlObject;
+use crate::primitives::mesh::Mesh;
+use crate::primitives::texture::{Texture, TextureOptions};
+use crate::storages::mesh_storage::MeshStorage;
+use crate::storages::texture_storage::{TextureId, TextureStorage};
+
+/// The most worker threads the asset server starts, loading is mostly bound by the disk.
+const MAX_WORKERS: usize = 4;
+/// Shown in place of textures that are still loading or failed to load.
+const PLACEHOLDER_COLOR: [u8; 4] = [128, 128, 128, 255];
+
+/// Loads meshes and textures by path into their storages and hands out counted handles to them.
+/// Loading a path that is already loaded returns the same asset, and assets whose handles are
+/// all dropped are freed by `unload_unused`.
+///
+/// The `_async` loads read and decode files on worker threads. Their ids are usable right away
+/// and show a placeholder until `update` puts the finished asset in the storage.
+pub struct AssetServer {
+    meshes: TrackedAssets<Mesh>,
+    textures: TrackedAssets<Texture>,
+    /// How each texture loaded from a file is created, again when it is reloaded.
+    texture_options: HashMap<TextureId, TextureOptions>,
+    workers: WorkerPool,
+    watcher: Option<FileWatcher>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadState {
+    /// Still being read, the storage holds a placeholder or the previous version.
+    Loading,
+    Loaded,
+    /// Reading the file failed, the placeholder or previous version stays.
+    Failed(Arc<AssetError>),
+}
+
+/// The assets of one type, with the path each was loaded from.
+struct TrackedAssets<T: Asset> {
+    entries: HashMap<T::Id, TrackedAsset<T>>,
+    paths: HashMap<PathBuf, T::Id>,
+    sender: Sender<LoadResult<T>>,
+    receiver: Receiver<LoadResult<T>>,
+}
+
+/// What a worker sends back for the asset with the id.
+type LoadResult<T> = (<T as Asset>::Id, Result<<T as Asset>::Data, AssetError>);
+
+struct TrackedAsset<T: Asset> {
+    path: Option<PathBuf>,
+    handle: Weak<T::Id>,
+    state: LoadState,
+}
+
+impl<T: Asset> TrackedAssets<T> {
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        TrackedAssets {
+            entries: HashMap::new(),
+            paths: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// A new handle to the asset loaded from `path`, if it is still alive.
+    fn get(&self, path: &Path) -> Option<Handle<T>> {
+        let id = self.paths.get(path)?;
+        Handle::upgrade(&self.entries.get(id)?.handle)
+    }
+
+    fn track(&mut self, id: T::Id, path: Option<PathBuf>, state: LoadState) -> Handle<T> {
+        let handle = Handle::new(id);
+        if let Some(path) = &path {
+            self.paths.insert(path.clone(), id);
+        }
+        self.entries.insert(id, TrackedAsset { path, handle: handle.downgrade(), state });
+        handle
+    }
+
+    /// Tracks the placeholder behind `id` as loading and reads the file on a worker thread.
+    fn load_async(&mut self, workers: &WorkerPool, id: T::Id, path: PathBuf, read: fn(&Path) -> Result<T::Data, AssetError>) -> Handle<T> {
+        self.read_async(workers, id, path.clone(), read);
+        self.track(id, Some(path), LoadState::Loading)
+    }
+
+    /// Reads the file of a loaded asset again, the current version stays until it is done.
+    /// Returns `false` if nothing is loaded from `path`.
+    fn reload(&mut self, workers: &WorkerPool, path: &Path, read: fn(&Path) -> Result<T::Data, AssetError>) -> bool {
+        let Some(&id) = self.paths.get(path) else {
+            return false;
+        };
+        let Some(asset) = self.entries.get_mut(&id) else {
+            return false;
+        };
+        asset.state = LoadState::Loading;
+        self.read_async(workers, id, path.to_path_buf(), read);
+        true
+    }
+
+    fn read_async(&self, workers: &WorkerPool, id: T::Id, path: PathBuf, read: fn(&Path) -> Result<T::Data, AssetError>) {
+        let sender = self.sender.clone();
+        workers.execute(move || {
+            // The server may be gone by now, then nobody wants the result.
+            let _ = sender.send((id, read(&path)));
+        });
+    }
+
+    /// Takes the results the workers have finished so far, leaving out those of assets that were
+    /// unloaded in the meantime. The others are marked as loaded or failed.
+    fn finished(&mut self) -> Vec<(T::Id, T::Data)> {
+        let mut finished = Vec::new();
+        for (id, result) in self.receiver.try_iter() {
+            let Some(asset) = self.entries.get_mut(&id) else {
+                continue;
+            };
+            match result {
+                Ok(data) => {
+                    asset.state = LoadState::Loaded;
+                    finished.push((id, data));
+                }
+                Err(error) => asset.state = LoadState::Failed(Arc::new(error)),
+            }
+        }
+        finished
+    }
+
+    fn fail(&mut self, id: T::Id, error: AssetError) {
+        if let Some(asset) = self.entries.get_mut(&id) {
+            asset.state = LoadState::Failed(Arc::new(error));
+        }
+    }
+
+    fn state(&self, handle: &Handle<T>) -> Option<LoadState> {
+        Some(self.entries.get(&handle.id())?.state.clone())
+    }
+
+    fn path(&self, handle: &Handle<T>) -> Option<&Path> {
+        self.entries.get(&handle.id())?.path.as_deref()
+    }
+
+    /// Returns the path of each removed asset, `None` for added ones and for those whose path was
+    /// loaded again since, which now belongs to the new asset.
+    fn unload_unused<S: AssetStorage<T>>(&mut self, storage: &mut S) -> Vec<Option<PathBuf>> {
+        let unused: Vec<T::Id> = self
+            .entries
+            .iter()
+            .filter(|(_, asset)| asset.handle.strong_count() == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        let mut paths = Vec::with_capacity(unused.len());
+        for id in unused {
+            let path = self
+                .entries
+                .remove(&id)
+                .and_then(|asset| asset.path)
+                .filter(|path| self.paths.get(path) == Some(&id));
+            if let Some(path) = &path {
+                self.paths.remove(path);
+            }
+            storage.remove_asset(id);
+            paths.push(path);
+        }
+        paths
+    }
+}
+
+impl AssetServer {
+    pub fn new() -> Self {
+        AssetServer {
+            meshes: TrackedAssets::new(),
+            textures: TrackedAssets::new(),
+            texture_options: HashMap::new(),
+            workers: WorkerPool::with_max_size(MAX_WORKERS),
+            watcher: None,
+        }
+    }
+
+    /// Loads an OBJ (through its binary cache), `.gmesh`, STL or PLY file, or returns the mesh
+    /// already loaded from it.
+    pub fn load_mesh(&mut self, path: &Path, mesh_storage: &mut MeshStorage) -> Result<Handle<Mesh>, AssetError> {
+        let path = asset_path(path);
+        if let Some(handle) = self.meshes.get(&path) {
+            return Ok(handle);
+        }
+        let mesh = read_mesh(&path)?;
+        self.watch(&path);
+        Ok(self.meshes.track(mesh_storage.add_mesh(mesh), Some(path), LoadState::Loaded))
+    }
+
+    /// Like `load_mesh`, but reads the file on a worker thread. Until then the mesh is a unit
+    /// cube.
+    pub fn load_mesh_async(&mut self, path: &Path, mesh_storage: &mut MeshStorage) -> Handle<Mesh> {
+        let path = asset_path(path);
+        if let Some(handle) = self.meshes.get(&path) {
+            return handle;
+        }
+        let placeholder = mesh_storage.add_mesh(Mesh::cube(1.0));
+        self.watch(&path);
+        self.meshes.load_async(&self.workers, placeholder, path, read_mesh)
+    }
+
+    /// Adds a mesh that wasn't loaded from a file, e.g. a generated one, so it is unloaded like
+    /// the others.
+    pub fn add_mesh(&mut self, mesh: Mesh, mesh_storage: &mut MeshStorage) -> Handle<Mesh> {
+        self.meshes.track(mesh_storage.add_mesh(mesh), None, LoadState::Loaded)
+    }
+
+    /// Loads any image format the `image` crate reads as a 2D texture, or returns the texture
+    /// already loaded from it. A texture keeps the options it was first loaded with.
+    pub fn load_texture(
+        &mut self,
+        path: &Path,
+        options: TextureOptions,
+        display: &Display<WindowSurface>,
+        texture_storage: &mut TextureStorage,
+    ) -> Result<Handle<Texture>, AssetError> {
+        let path = asset_path(path);
+        if let Some(handle) = self.textures.get(&path) {
+            return Ok(handle);
+        }
+        let texture = Texture::new(display, read_image(&path)?, options)?;
+        let texture_id = texture_storage.add_texture(texture);
+        self.texture_options.insert(texture_id, options);
+        self.watch(&path);
+        Ok(self.textures.track(texture_id, Some(path), LoadState::Loaded))
+    }
+
+    /// Like `load_texture`, but decodes the image on a worker thread. Until then the texture is
+    /// plain grey.
+    pub fn load_texture_async(
+        &mut self,
+        path: &Path,
+        options: TextureOptions,
+        display: &Display<WindowSurface>,
+        texture_storage: &mut TextureStorage,
+    ) -> Result<Handle<Texture>, AssetError> {
+        let path = asset_path(path);
+        if let Some(handle) = self.textures.get(&path) {
+            return Ok(handle);
+        }
+        let placeholder = texture_storage.add_texture(Texture::solid(display, PLACEHOLDER_COLOR, options)?);
+        self.texture_options.insert(placeholder, options);
+        self.watch(&path);
+        Ok(self.textures.load_async(&self.workers, placeholder, path, read_image))
+    }
+
+    /// Adds a texture that wasn't loaded from a file, like an array or cube map.
+    pub fn add_texture(&mut self, texture: Texture, texture_storage: &mut TextureStorage) -> Handle<Texture> {
+        self.textures.track(texture_storage.add_texture(texture), None, LoadState::Loaded)
+    }
+
+    /// Puts the assets finished on worker threads into their storages, replacing the
+    /// placeholders. Meant for the render thread, since textures are created on the GPU here.
+    /// Also starts reloading changed files, see `watch_for_changes`.
+    pub fn update(&mut self, display: &Display<WindowSurface>, mesh_storage: &mut MeshStorage, texture_storage: &mut TextureStorage) {
+        self.reload_changed();
+        self.update_meshes(mesh_storage);
+        for (id, image) in self.textures.finished() {
+            let options = self.texture_options.get(&id).copied().unwrap_or_default();
+            match Texture::new(display, image, options) {
+                Ok(texture) => {
+                    texture_storage.replace_texture(id, texture);
+                }
+                Err(error) => self.textures.fail(id, error.into()),
+            }
+        }
+    }
+
+    fn reload_changed(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        for path in watcher.poll() {
+            if !self.meshes.reload(&self.workers, &path, read_mesh) {
+                self.textures.reload(&self.workers, &path, read_image);
+            }
+        }
+    }
+
+    fn update_meshes(&mut self, mesh_storage: &mut MeshStorage) {
+        for (id, mesh) in self.meshes.finished() {
+            mesh_storage.replace_mesh(id, mesh);
+        }
+    }
+
+    /// `None` for handles of another server.
+    pub fn mesh_state(&self, handle: &Handle<Mesh>) -> Option<LoadState> {
+        self.meshes.state(handle)
+    }
+
+    pub fn texture_state(&self, handle: &Handle<Texture>) -> Option<LoadState> {
+        self.textures.state(handle)
+    }
+
+    /// The file a mesh was loaded from, `None` for added ones.
+    pub fn mesh_path(&self, handle: &Handle<Mesh>) -> Option<&Path> {
+        self.meshes.path(handle)
+    }
+
+    pub fn texture_path(&self, handle: &Handle<Texture>) -> Option<&Path> {
+        self.textures.path(handle)
+    }
+
+    /// Removes every asset without handles from its storage, returning how many were removed.
+    /// Loading one of their paths again reads the file again. Components only hold ids, so an
+    /// entity can still refer to a removed asset; the storages then return `None` for it.
+    ///
+    /// The files of removed assets are no longer watched, unless a live asset was loaded from the
+    /// same path since.
+    pub fn unload_unused(&mut self, mesh_storage: &mut MeshStorage, texture_storage: &mut TextureStorage) -> usize {
+        let mut paths = self.meshes.unload_unused(mesh_storage);
+        paths.extend(self.textures.unload_unused(texture_storage));
+        let textures = &self.textures.entries;
+        self.texture_options.retain(|texture_id, _| textures.contains_key(texture_id));
+        if let Some(watcher) = &mut self.watcher {
+            paths.iter().flatten().for_each(|path| watcher.unwatch(path));
+        }
+        paths.len()
+    }
+
+    /// Reloads assets in place when their files change, checking at most once per interval.
+    /// Changes are picked up by `update`, until then the previous version stays.
+    pub fn watch_for_changes(&mut self, interval: Duration) {
+        let mut watcher = FileWatcher::new(interval);
+        self.meshes.paths.keys().chain(self.textures.paths.keys()).for_each(|path| watcher.watch(path));
+        self.watcher = Some(watcher);
+    }
+
+    fn watch(&mut self, path: &Path) {
+        if let Some(watcher) = &mut self.watcher {
+            watcher.watch(path);
+        }
+    }
+
+    pub fn mesh_count(&self) -> usize {
+        self.meshes.entries.len()
+    }
+
+    pub fn texture_count(&self) -> usize {
+        self.textures.entries.len()
+    }
+}
+
+/// The same file reached through different relative paths should be loaded once.
+fn asset_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_ascii_lowercase()
+}
+
+/// Reads a mesh file, picking the format by its extension.
+pub fn read_mesh(path: &Path) -> Result<Mesh, AssetError> {
+    match extension(path).as_str() {
+        "obj" => Ok(MeshCache::load_wavefront(path)?.mesh),
+        "gmesh" => Ok(MeshCache::parse(path)?.mesh),
+        "stl" => Ok(Mesh::from_stl(&StlObject::parse(path)?)),
+        "ply" => Ok(Mesh::from_ply(&PlyObject::parse(path)?)),
+        _ => Err(AssetError::UnsupportedFormat(path.to_path_buf())),
+    }
+}
+
+/// Decodes an image file into RGBA pixels, bottom row first like textures store them.
+pub fn read_image(path: &Path) -> Result<RawImage2d<'static, u8>, AssetError> {
+    let image = image::open(path)?.to_rgba8();
+    let dimensions = image.dimensions();
+    Ok(RawImage2d::from_raw_rgba_reversed(&image.into_raw(), dimensions))
+}
+
+#[cfg(test)]
+mod tests {
+    use gust_hierarchy::world::World;
+
+    use crate::components::mesh_component::MeshComponent;
+    use crate::components::transform_component::TransformComponent;
+
+    use super::*;
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("gust_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn test_meshes_are_shared_by_path() {
+        let directory = temp_directory("asset_server_meshes");
+        std::fs::write(directory.join("triangle.obj"), TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+
+        let first = server.load_mesh(&directory.join("triangle.obj"), &mut meshes).unwrap();
+        let second = server.load_mesh(&directory.join("../").join(directory.file_name().unwrap()).join("triangle.obj"), &mut meshes).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.count(), 2);
+        assert_eq!(meshes.mesh_count(), 1);
+        assert_eq!(server.mesh_path(&first), Some(directory.join("triangle.obj").canonicalize().unwrap().as_path()));
+
+        let cube = server.add_mesh(Mesh::cube(1.0), &mut meshes);
+        assert_ne!(cube, first);
+        assert_eq!(server.mesh_path(&cube), None);
+        assert_eq!(server.mesh_count(), 2);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unused_assets_are_unloaded() {
+        let directory = temp_directory("asset_server_unload");
+        let path = directory.join("triangle.obj");
+        std::fs::write(&path, TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+        let mut textures = TextureStorage::new();
+
+        let handle = server.load_mesh(&path, &mut meshes).unwrap();
+        let copy = handle.clone();
+        let cube = server.add_mesh(Mesh::cube(1.0), &mut meshes);
+        let (mesh_id, cube_id) = (handle.id(), cube.id());
+
+        drop(handle);
+        drop(cube);
+        assert_eq!(server.unload_unused(&mut meshes, &mut textures), 1);
+        assert!(meshes.has_mesh(mesh_id));
+        assert!(!meshes.has_mesh(cube_id));
+
+        drop(copy);
+        assert_eq!(server.unload_unused(&mut meshes, &mut textures), 1);
+        assert_eq!(meshes.mesh_count(), 0);
+        assert_eq!(server.mesh_count(), 0);
+
+        // The file is read again once it was unloaded
+        let handle = server.load_mesh(&path, &mut meshes).unwrap();
+        assert_ne!(handle.id(), mesh_id);
+        assert_eq!(meshes.get_mesh(handle.id()).unwrap().triangle_count(), 1);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_reloaded_before_unloading() {
+        let directory = temp_directory("asset_server_reload_before_unload");
+        let path = directory.join("triangle.obj");
+        std::fs::write(&path, TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+
+        let old_id = server.load_mesh(&path, &mut meshes).unwrap().id();
+        let handle = server.load_mesh(&path, &mut meshes).unwrap();
+        assert_ne!(handle.id(), old_id);
+
+        // Unloading the old mesh leaves the path to the new one
+        assert_eq!(server.unload_unused(&mut meshes, &mut TextureStorage::new()), 1);
+        assert!(!meshes.has_mesh(old_id));
+        assert_eq!(server.load_mesh(&path, &mut meshes).unwrap(), handle);
+        assert_eq!(meshes.mesh_count(), 1);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unloaded_while_an_entity_refers_to_it() {
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+        let handle = server.add_mesh(Mesh::cube(1.0), &mut meshes);
+        let mut world = World::new();
+        let entity = world.spawn();
+        world.add_component(entity, TransformComponent::default());
+        world.add_component(entity, MeshComponent(handle.id()));
+
+        drop(handle);
+        assert_eq!(server.unload_unused(&mut meshes, &mut TextureStorage::new()), 1);
+        let mesh_id = world.get_component::<MeshComponent>(entity).unwrap().0;
+        assert!(meshes.get_mesh(mesh_id).is_none());
+        assert!(meshes.get_gpu_mesh(mesh_id).is_none());
+        assert_eq!(meshes.world_bounds(&world, entity), None);
+    }
+
+    /// Applies finished loads until the mesh is no longer loading.
+    fn wait_for_mesh(server: &mut AssetServer, handle: &Handle<Mesh>, meshes: &mut MeshStorage) -> LoadState {
+        for _ in 0..1000 {
+            server.update_meshes(meshes);
+            match server.mesh_state(handle).unwrap() {
+                LoadState::Loading => std::thread::sleep(std::time::Duration::from_millis(5)),
+                state => return state,
+            }
+        }
+        panic!("mesh did not finish loading");
+    }
+
+    #[test]
+    fn test_async_loading() {
+        let directory = temp_directory("asset_server_async");
+        let path = directory.join("triangle.obj");
+        std::fs::write(&path, TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+
+        let handle = server.load_mesh_async(&path, &mut meshes);
+        assert_eq!(server.load_mesh_async(&path, &mut meshes), handle);
+        assert_eq!(server.load_mesh(&path, &mut meshes).unwrap(), handle);
+        assert!(meshes.has_mesh(handle.id()));
+
+        assert!(matches!(wait_for_mesh(&mut server, &handle, &mut meshes), LoadState::Loaded));
+        assert_eq!(meshes.get_mesh(handle.id()).unwrap().triangle_count(), 1);
+        assert!(meshes.needs_upload(handle.id()));
+
+        // A failed load keeps the placeholder
+        let missing = server.load_mesh_async(&directory.join("missing.ply"), &mut meshes);
+        match wait_for_mesh(&mut server, &missing, &mut meshes) {
+            LoadState::Failed(error) => assert!(matches!(*error, AssetError::Parse(_))),
+            other => panic!("unexpected state {:?}", other),
+        }
+        assert_eq!(meshes.get_mesh(missing.id()), Some(&Mesh::cube(1.0)));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unloaded_while_loading() {
+        let directory = temp_directory("asset_server_async_unload");
+        let path = directory.join("triangle.obj");
+        std::fs::write(&path, TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+
+        let id = server.load_mesh_async(&path, &mut meshes).id();
+        server.unload_unused(&mut meshes, &mut TextureStorage::new());
+        // Dropping the pool waits for the queued load, whose result must not come back.
+        let AssetServer { meshes: mut tracked, workers, .. } = server;
+        drop(workers);
+        assert!(tracked.finished().is_empty());
+        assert!(!meshes.has_mesh(id));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_hot_reload() {
+        let directory = temp_directory("asset_server_hot_reload");
+        let path = directory.join("triangle.obj");
+        std::fs::write(&path, TRIANGLE).unwrap();
+        let mut server = AssetServer::new();
+        let mut meshes = MeshStorage::new();
+        let handle = server.load_mesh(&path, &mut meshes).unwrap();
+        server.watch_for_changes(Duration::ZERO);
+
+        // Written a little in the future so the change shows on coarse file systems too
+        let touch = |contents: String, seconds: u64| {
+            std::fs::write(&path, contents).unwrap();
+            let time = std::time::SystemTime::now() + Duration::from_secs(seconds);
+            std::fs::File::options().write(true).open(&path).unwrap().set_modified(time).unwrap();
+        };
+        touch(format!("{}v 0 0 1\nf 1 3 4\n", TRIANGLE), 10);
+        server.reload_changed();
+        assert!(matches!(wait_for_mesh(&mut server, &handle, &mut meshes), LoadState::Loaded));
+        assert_eq!(meshes.get_mesh(handle.id()).unwrap().triangle_count(), 2);
+
+        // A broken file keeps the previous version
+        touch("f 1 2 3\n".to_string(), 20);
+        server.reload_changed();
+        assert!(matches!(wait_for_mesh(&mut server, &handle, &mut meshes), LoadState::Failed(_)));
+        assert_eq!(meshes.get_mesh(handle.id()).unwrap().triangle_count(), 2);
+
+        // A file loaded again before the old mesh is unloaded stays watched
+        drop(handle);
+        touch(TRIANGLE.to_string(), 30);
+        let handle = server.load_mesh(&path, &mut meshes).unwrap();
+        assert_eq!(server.unload_unused(&mut meshes, &mut TextureStorage::new()), 1);
+        assert!(server.watcher.as_ref().unwrap().is_watching(&path.canonicalize().unwrap()));
+
+        // Unloaded files are no longer watched
+        drop(handle);
+        server.unload_unused(&mut meshes, &mut TextureStorage::new());
+        assert!(!server.watcher.as_ref().unwrap().is_watching(&path.canonicalize().unwrap()));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let mut server = AssetServer::new();
+        match server.load_mesh(Path::new("model.fbx"), &mut MeshStorage::new()) {
+            Err(AssetError::UnsupportedFormat(path)) => assert_eq!(path, Path::new("model.fbx")),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(matches!(read_mesh(Path::new("missing.stl")), Err(AssetError::Parse(_))));
+        assert!(matches!(read_image(Path::new("missing.png")), Err(AssetError::Image(_))));
+    }
+
+    #[test]
+    fn test_read_image() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/green.png");
+        let image = read_image(&path).unwrap();
+        assert_eq!(image.data.len(), (image.width * image.height * 4) as usize);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often assets and shaders are checked for changes while the game runs.
+pub const HOT_RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices changed files by comparing their modification times, at most once per interval.
+pub struct FileWatcher {
+    files: HashMap<PathBuf, Option<SystemTime>>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl FileWatcher {
+    pub fn new(interval: Duration) -> Self {
+        FileWatcher {
+            files: HashMap::new(),
+            interval,
+            last_poll: None,
+        }
+    }
+
+    /// Starts watching the file as it is now. A missing file counts as changed once it appears.
+    pub fn watch(&mut self, path: &Path) {
+        self.files.insert(path.to_path_buf(), modified(path));
+    }
+
+    pub fn unwatch(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// The files that changed since the last poll, or nothing if the interval hasn't passed yet.
+    /// Deleting a file is not a change, editors often delete and write again when saving.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        if self.last_poll.is_some_and(|last_poll| now.duration_since(last_poll) < self.interval) {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+
+        let mut changed = Vec::new();
+        for (path, last_modified) in &mut self.files {
+            let modified = modified(path);
+            if modified.is_some() && modified != *last_modified {
+                *last_modified = modified;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_poll() {
+        let directory = std::env::temp_dir().join(format!("gust_file_watcher_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let (first, second) = (directory.join("first.txt"), directory.join("second.txt"));
+        std::fs::write(&first, "first").unwrap();
+
+        let mut watcher = FileWatcher::new(Duration::ZERO);
+        watcher.watch(&first);
+        watcher.watch(&second);
+        assert!(watcher.poll().is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(&first).unwrap().set_modified(later).unwrap();
+        std::fs::write(&second, "second").unwrap();
+        let mut changed = watcher.poll();
+        changed.sort();
+        assert_eq!(changed, vec![first.clone(), second.clone()]);
+        assert!(watcher.poll().is_empty());
+
+        std::fs::remove_file(&first).unwrap();
+        assert!(watcher.poll().is_empty());
+
+        // Polls in between the interval see nothing
+        let mut watcher = FileWatcher::new(Duration::from_secs(3600));
+        watcher.watch(&second);
+        assert!(watcher.poll().is_empty());
+        File::options().write(true).open(&second).unwrap().set_modified(later).unwrap();
+        assert!(watcher.poll().is_empty());
+        watcher.unwatch(&second);
+        assert!(!watcher.is_watching(&second));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
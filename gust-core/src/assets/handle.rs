@@ -0,0 +1,103 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
+
+use glium::texture::RawImage2d;
+
+use crate::primitives::mesh::Mesh;
+use crate::primitives::texture::Texture;
+use crate::storages::mesh_storage::{MeshId, MeshStorage};
+use crate::storages::texture_storage::{TextureId, TextureStorage};
+
+/// Something the `AssetServer` keeps in a storage, under the storage's id type.
+pub trait Asset: 'static {
+    type Id: Copy + Eq + Hash + fmt::Debug + Send + Sync;
+    /// What reading the file produces on a worker thread, before anything touches the GPU.
+    type Data: Send + 'static;
+}
+
+impl Asset for Mesh {
+    type Id = MeshId;
+    type Data = Mesh;
+}
+
+impl Asset for Texture {
+    type Id = TextureId;
+    type Data = RawImage2d<'static, u8>;
+}
+
+/// A counted reference to a loaded asset. The asset stays in its storage while any clone of the
+/// handle is alive, and is unloaded by `AssetServer::unload_unused` once the last one is dropped.
+///
+/// Components refer to assets by their plain id, so whoever spawns them keeps the handle.
+pub struct Handle<T: Asset> {
+    id: Arc<T::Id>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset> Handle<T> {
+    pub(crate) fn new(id: T::Id) -> Self {
+        Handle { id: Arc::new(id), marker: PhantomData }
+    }
+
+    pub(crate) fn upgrade(weak: &Weak<T::Id>) -> Option<Self> {
+        weak.upgrade().map(|id| Handle { id, marker: PhantomData })
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<T::Id> {
+        Arc::downgrade(&self.id)
+    }
+
+    pub fn id(&self) -> T::Id {
+        *self.id
+    }
+
+    /// The number of handles to the asset, this one included.
+    pub fn count(&self) -> usize {
+        Arc::strong_count(&self.id)
+    }
+}
+
+impl<T: Asset> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle { id: self.id.clone(), marker: PhantomData }
+    }
+}
+
+impl<T: Asset> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Handle").field(&*self.id).finish()
+    }
+}
+
+impl<T: Asset> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: Asset> Eq for Handle<T> {}
+
+impl<T: Asset> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Lets the `AssetServer` free assets without knowing which storage they live in.
+pub trait AssetStorage<T: Asset> {
+    fn remove_asset(&mut self, id: T::Id);
+}
+
+impl AssetStorage<Mesh> for MeshStorage {
+    fn remove_asset(&mut self, id: MeshId) {
+        self.remove_mesh(id);
+    }
+}
+
+impl AssetStorage<Texture> for TextureStorage {
+    fn remove_asset(&mut self, id: TextureId) {
+        self.remove_texture(id);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod handle;
+pub mod asset_server;
+pub mod asset_error;
+pub mod worker_pool;
+pub mod file_watcher;
\ No newline at end of file
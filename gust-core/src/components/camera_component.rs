@@ -1,5 +1,7 @@
 use gust_math::vectors::vect3::Vect3;
 
+use crate::primitives::bounds::BoundingSphere;
+
 pub struct CameraComponent {
     pub fov: f32,
     pub z_near: f32,
@@ -29,8 +31,8 @@ impl CameraComponent {
 
     pub fn view_matrix(&self, position: Vect3, forward: Vect3, up: Vect3) -> [[f32; 4]; 4] {
         let f = forward;
-        let s = up.cross(&f).normalize();
-        let u = f.cross(&s).normalize();
+        let s = up.cross(&f).normalized();
+        let u = f.cross(&s).normalized();
 
         let p = [
             -position.dot(&s),
@@ -45,4 +47,30 @@ impl CameraComponent {
             [p[0], p[1], p[2], 1.0],
         ]
     }
-}
\ No newline at end of file
+    /// The fraction of the screen height a sphere covers seen from `position`. It is 1 when the
+    /// sphere just fills the screen, and infinite when the camera is inside it.
+    pub fn screen_size(&self, position: Vect3, sphere: &BoundingSphere) -> f32 {
+        let distance = position.distance(&sphere.center);
+        if distance <= sphere.radius {
+            return f32::INFINITY;
+        }
+        sphere.radius / ((self.fov / 2.0).tan() * distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn test_screen_size() {
+        // With a 90 degree field of view the screen is as high as the distance, twice over.
+        let camera = CameraComponent::new(FRAC_PI_2, 0.1, 100.0, 1.0);
+        let sphere = BoundingSphere::new(Vect3::new(10.0, 0.0, 0.0), 1.0);
+        assert!((camera.screen_size(Vect3::zeros(), &sphere) - 0.1).abs() < 1e-6);
+        assert!((camera.screen_size(Vect3::new(5.0, 0.0, 0.0), &sphere) - 0.2).abs() < 1e-6);
+        assert_eq!(camera.screen_size(Vect3::new(10.5, 0.0, 0.0), &sphere), f32::INFINITY);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::primitives::mesh::Mesh;
+use crate::storages::mesh_storage::{MeshId, MeshStorage};
+
+/// Draws simpler meshes in place of the entity's `MeshComponent` as it gets smaller on screen.
+/// The `MeshComponent` should hold the most detailed mesh, its bounds measure the size.
+pub struct LodComponent {
+    /// From the most to the least detailed.
+    pub levels: Vec<LodLevel>,
+}
+
+pub struct LodLevel {
+    pub mesh_id: MeshId,
+    /// The fraction of the screen height the entity has to cover for this level to be used.
+    pub min_screen_size: f32,
+}
+
+impl LodComponent {
+    pub fn new(levels: Vec<LodLevel>) -> Self {
+        LodComponent { levels }
+    }
+
+    /// Adds the meshes of a chain like `Mesh::lod_chain` to the storage. The first mesh is used
+    /// down to `screen_size`, every further one down to half the size of the one before, and
+    /// the last for anything smaller.
+    pub fn from_chain(mesh_storage: &mut MeshStorage, chain: Vec<Mesh>, screen_size: f32) -> Self {
+        let last = chain.len().saturating_sub(1);
+        let levels = chain
+            .into_iter()
+            .enumerate()
+            .map(|(level, mesh)| LodLevel {
+                mesh_id: mesh_storage.add_mesh(mesh),
+                min_screen_size: match level == last {
+                    true => 0.0,
+                    false => screen_size / 2.0f32.powi(level as i32),
+                },
+            })
+            .collect();
+        LodComponent { levels }
+    }
+
+    /// The most detailed level the screen size allows, or the least detailed one if it is
+    /// below every threshold.
+    pub fn select(&self, screen_size: f32) -> Option<MeshId> {
+        self.levels
+            .iter()
+            .find(|level| screen_size >= level.min_screen_size)
+            .or(self.levels.last())
+            .map(|level| level.mesh_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_by_screen_size() {
+        let mut storage = MeshStorage::new();
+        let lod = LodComponent::from_chain(&mut storage, Mesh::icosphere(1.0, 2).lod_chain(3), 0.5);
+        let ids: Vec<MeshId> = lod.levels.iter().map(|level| level.mesh_id).collect();
+        assert_eq!(storage.mesh_count(), 3);
+        assert_eq!(lod.levels.iter().map(|level| level.min_screen_size).collect::<Vec<_>>(), vec![0.5, 0.25, 0.0]);
+
+        assert_eq!(lod.select(2.0), Some(ids[0]));
+        assert_eq!(lod.select(0.5), Some(ids[0]));
+        assert_eq!(lod.select(0.3), Some(ids[1]));
+        assert_eq!(lod.select(0.01), Some(ids[2]));
+        assert_eq!(LodComponent::new(Vec::new()).select(1.0), None);
+
+        // Thresholds set by hand may leave small sizes uncovered.
+        let lod = LodComponent::new(vec![LodLevel { mesh_id: ids[0], min_screen_size: 0.5 }, LodLevel { mesh_id: ids[1], min_screen_size: 0.2 }]);
+        assert_eq!(lod.select(0.1), Some(ids[1]));
+    }
+}
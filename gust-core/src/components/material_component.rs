@@ -0,0 +1,3 @@
+use crate::storages::material_storage::MaterialId;
+
+pub struct MaterialComponent(pub MaterialId);
\ No newline at end of file
@@ -2,5 +2,6 @@ pub mod transform_component;
 pub mod player_component;
 pub mod velocity_component;
 pub mod mesh_component;
-pub mod texture_component;
-pub mod camera_component;
\ No newline at end of file
+pub mod material_component;
+pub mod camera_component;
+pub mod lod_component;
\ No newline at end of file
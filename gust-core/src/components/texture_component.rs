@@ -1,3 +0,0 @@
-use crate::storages::texture_storage::TextureId;
-
-pub struct TextureComponent(pub TextureId);
\ No newline at end of file
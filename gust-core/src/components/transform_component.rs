@@ -1,3 +1,5 @@
+use gust_hierarchy::entity::Entity;
+use gust_hierarchy::world::World;
 use gust_math::matrices::mat4::Mat4;
 use gust_math::vectors::vect3::Vect3;
 
@@ -44,4 +46,16 @@ impl TransformComponent {
             .scale(self.scale)
             .rotate_with_dir_and_up(self.forward, self.up)
     }
+
+    /// The transform of `entity` combined with those of all its parents, as it is drawn. `None`
+    /// if the entity or one of its parents has no transform.
+    pub fn world_matrix(world: &World, entity: Entity) -> Option<Mat4> {
+        let mut matrix = world.get_component::<TransformComponent>(entity)?.get_transform_matrix();
+        let mut current = entity;
+        while let Some(parent) = world.get_parent(current) {
+            matrix = world.get_component::<TransformComponent>(parent)?.get_transform_matrix() * matrix;
+            current = parent;
+        }
+        Some(matrix)
+    }
 }
\ No newline at end of file
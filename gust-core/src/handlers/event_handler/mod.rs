@@ -8,6 +8,7 @@ use winit::event::Event::WindowEvent;
 use winit::event::KeyEvent;
 use winit::window::{CursorGrabMode, Window};
 
+use crate::primitives::color::Color;
 use crate::primitives::lights_block::LightsBlock;
 use crate::systems::game::Game;
 use crate::systems::render_system::RenderSystem;
@@ -30,7 +31,7 @@ impl EventHandler {
         }, display)
     }
 
-    pub fn run(self, game: &mut Game, render_system: RenderSystem) {
+    pub fn run(self, game: &mut Game, mut render_system: RenderSystem) {
         self.initialize_window();
 
         let buffer = self.construct_light_block(&render_system.display);
@@ -61,7 +62,7 @@ impl EventHandler {
                         render_system.display.resize(window_size.into());
                     }
                     winit::event::WindowEvent::RedrawRequested => {
-                        EventHandler::handle_redraw_request(mouse_position, game, &render_system, &buffer);
+                        EventHandler::handle_redraw_request(mouse_position, game, &mut render_system, &buffer);
                     }
                     _ => (),
                 },
@@ -82,8 +83,9 @@ impl EventHandler {
             [-10.0, -10.0, 5.0],
         ];
 
+        // Lights are authored in sRGB but the shader works in linear space.
         let light_colors: [[f32; 3]; 5] = [
-            [1.0, 1.0, 1.0]; 5
+            Color::WHITE.to_linear().to_rgb_array(); 5
         ];
 
         UniformBuffer::new(display, LightsBlock {
@@ -101,7 +103,7 @@ impl EventHandler {
         self.window.set_cursor_visible(false);
     }
 
-    fn handle_redraw_request(mouse_position: PhysicalPosition<f64>, game: &mut Game, render_system: &RenderSystem, buffer: &UniformBuffer<LightsBlock>) {
+    fn handle_redraw_request(mouse_position: PhysicalPosition<f64>, game: &mut Game, render_system: &mut RenderSystem, buffer: &UniformBuffer<LightsBlock>) {
         let now = Instant::now();
         let elapsed = now.duration_since(game.last_frame_time);
 
@@ -111,6 +113,9 @@ impl EventHandler {
 
         game.input_handler.handle_mouse_input(mouse_position);
         game.update();
+        if let Err(error) = render_system.reload_changed_shaders() {
+            eprintln!("Keeping the previous shaders: {}", error);
+        }
         render_system.render(game, buffer);
     }
 }
@@ -4,3 +4,4 @@ pub mod objects;
 pub mod primitives;
 pub mod components;
 pub mod storages;
+pub mod assets;
@@ -0,0 +1,57 @@
+use crate::primitives::material::Material;
+use crate::primitives::mesh::Mesh;
+
+/// The contents of a glTF or GLB file, converted to our own types but not yet added to a world
+/// or uploaded to the GPU.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GltfScene {
+    /// Every node of the file, in file order.
+    pub nodes: Vec<GltfNode>,
+    /// Indices into `nodes` of the top level nodes of the displayed scene.
+    pub roots: Vec<usize>,
+    /// The primitives of each glTF mesh. Nodes may share a mesh.
+    pub meshes: Vec<Vec<GltfPrimitive>>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<GltfImage>,
+}
+
+/// A node with its local transform. glTF matrices are decomposed when parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfNode {
+    pub name: Option<String>,
+    pub translation: [f32; 3],
+    /// A unit quaternion as `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+    /// An index into `GltfScene::meshes`.
+    pub mesh: Option<usize>,
+    /// Indices into `GltfScene::nodes`.
+    pub children: Vec<usize>,
+}
+
+/// One draw call worth of a glTF mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfPrimitive {
+    pub mesh: Mesh,
+    /// An index into `GltfScene::materials`, `None` for the glTF default material.
+    pub material: Option<usize>,
+}
+
+/// A material approximated for our Blinn-Phong shading. Its textures are left unset and refer
+/// to `GltfScene::images` instead, until the images are uploaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: Option<String>,
+    pub material: Material,
+    /// The base color texture.
+    pub diffuse_image: Option<usize>,
+    pub normal_image: Option<usize>,
+}
+
+/// A decoded image as 8 bit RGBA, with rows from top to bottom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
@@ -0,0 +1,38 @@
+use std::ops::Range;
+
+use crate::primitives::bounds::MeshBounds;
+use crate::primitives::mesh::Mesh;
+
+/// A mesh in the binary `.gmesh` format, which loads without any parsing of text.
+///
+/// All values are little endian:
+/// - the magic `GMSH` and the format version as `u32`
+/// - the vertex, index and sub-mesh counts as `u32`
+/// - the bounds: box minimum and maximum, sphere center and radius as `f32`
+/// - per vertex its position, normal, texture coordinates and tangent as `f32`
+/// - the indices as `u32`
+/// - per sub-mesh its object, groups and material as strings, and its index range as `u32`
+/// - a CRC-32 of everything before it
+///
+/// Strings are a `u32` byte length and UTF-8 text. A missing string has length `u32::MAX`, a
+/// list of strings is a `u32` count and the strings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshCache {
+    pub mesh: Mesh,
+    pub bounds: MeshBounds,
+    pub sub_meshes: Vec<SubMesh>,
+}
+
+/// A run of triangles sharing a name and material, like a face group of an OBJ file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SubMesh {
+    pub object: Option<String>,
+    pub groups: Vec<String>,
+    pub material: Option<String>,
+    /// The range of `Mesh::indices`, always whole triangles.
+    pub indices: Range<u32>,
+}
+
+pub(crate) const MAGIC: &[u8; 4] = b"GMSH";
+/// Bumped whenever the layout changes, so older caches are converted again.
+pub(crate) const VERSION: u32 = 1;
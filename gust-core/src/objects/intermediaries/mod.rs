@@ -1 +1,7 @@
-pub mod wavefront_object;
\ No newline at end of file
+pub mod wavefront_object;
+pub mod wavefront_material;
+pub mod gltf_scene;
+pub mod stl_object;
+pub mod ply_object;
+pub mod mesh_cache;
+pub mod sprite_sheet;
\ No newline at end of file
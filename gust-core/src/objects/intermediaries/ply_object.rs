@@ -0,0 +1,22 @@
+/// The vertices and faces of a PLY file. Other elements and properties are skipped when
+/// parsing.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlyObject {
+    pub vertices: Vec<[f32; 3]>,
+    /// One per vertex, or empty if the file has no `nx`, `ny` and `nz` properties.
+    pub normals: Vec<[f32; 3]>,
+    /// One per vertex, or empty if the file has no texture coordinates.
+    pub tex_coords: Vec<[f32; 2]>,
+    /// Counter-clockwise polygons as zero based indices into `vertices`.
+    pub faces: Vec<Vec<u32>>,
+    /// The `comment` lines of the header.
+    pub comments: Vec<String>,
+}
+
+/// How the body of a PLY file is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
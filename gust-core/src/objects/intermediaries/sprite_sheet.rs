@@ -0,0 +1,149 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use image::{GenericImageView, RgbaImage};
+
+/// The frames of a sprite animation, all the same size, with how long each is shown and named
+/// runs of them like "walk" or "jump".
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub frames: Vec<SpriteFrame>,
+    pub tags: Vec<FrameTag>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteFrame {
+    pub image: RgbaImage,
+    pub duration: Duration,
+}
+
+/// A named animation made of the frames in `frames`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameTag {
+    pub name: String,
+    pub frames: RangeInclusive<usize>,
+    pub direction: LoopDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopDirection {
+    Forward,
+    Reverse,
+    /// Forward then back, without showing the first and last frames twice.
+    PingPong,
+    /// Back then forward.
+    PingPongReverse,
+}
+
+impl SpriteSheet {
+    /// Cuts an image into frames of the given size, left to right and then top to bottom. Rows
+    /// and columns that don't fit a whole frame are left out.
+    pub fn from_grid(image: &RgbaImage, frame_width: u32, frame_height: u32, duration: Duration) -> SpriteSheet {
+        let columns = image.width().checked_div(frame_width).unwrap_or(0);
+        let rows = image.height().checked_div(frame_height).unwrap_or(0);
+        let frames = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| SpriteFrame {
+                image: image.view(column * frame_width, row * frame_height, frame_width, frame_height).to_image(),
+                duration,
+            })
+            .collect();
+
+        SpriteSheet { frame_width, frame_height, frames, tags: Vec::new() }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&FrameTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// The frame shown after `elapsed` time of looping an animation, or of all frames when no tag
+    /// is given.
+    pub fn frame_at(&self, tag: Option<&FrameTag>, elapsed: Duration) -> usize {
+        let last = self.frames.len().saturating_sub(1);
+        let (range, direction) = match tag {
+            Some(tag) => (*tag.frames.start().min(&last)..=*tag.frames.end().min(&last), tag.direction),
+            None => (0..=last, LoopDirection::Forward),
+        };
+
+        let forward: Vec<usize> = range.clone().collect();
+        let backward: Vec<usize> = range.rev().collect();
+        let inner = forward.len().saturating_sub(2);
+        let sequence: Vec<usize> = match direction {
+            LoopDirection::Forward => forward,
+            LoopDirection::Reverse => backward,
+            LoopDirection::PingPong => forward.iter().chain(backward.iter().skip(1).take(inner)).copied().collect(),
+            LoopDirection::PingPongReverse => backward.iter().chain(forward.iter().skip(1).take(inner)).copied().collect(),
+        };
+
+        let duration = |frame: &usize| self.frames.get(*frame).map_or(Duration::ZERO, |frame| frame.duration);
+        let total: Duration = sequence.iter().map(duration).sum();
+        if total.is_zero() {
+            return sequence.first().copied().unwrap_or(0);
+        }
+
+        let mut time = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+        for frame in &sequence {
+            if time < duration(frame) {
+                return *frame;
+            }
+            time -= duration(frame);
+        }
+        *sequence.last().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(durations: &[u64]) -> SpriteSheet {
+        SpriteSheet {
+            frame_width: 1,
+            frame_height: 1,
+            frames: durations
+                .iter()
+                .map(|&millis| SpriteFrame { image: RgbaImage::new(1, 1), duration: Duration::from_millis(millis) })
+                .collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let image = RgbaImage::from_fn(5, 4, |x, y| image::Rgba([x as u8, y as u8, 0, 255]));
+        let sheet = SpriteSheet::from_grid(&image, 2, 2, Duration::from_millis(100));
+        assert_eq!(sheet.frames.len(), 4);
+        assert_eq!(sheet.frames[1].image.get_pixel(0, 0), &image::Rgba([2, 0, 0, 255]));
+        assert_eq!(sheet.frames[2].image.get_pixel(1, 1), &image::Rgba([1, 3, 0, 255]));
+        assert!(SpriteSheet::from_grid(&image, 0, 2, Duration::ZERO).frames.is_empty());
+    }
+
+    #[test]
+    fn test_frame_at() {
+        let mut sheet = sheet(&[100, 200, 100, 100]);
+        let at = |sheet: &SpriteSheet, tag: Option<&str>, millis: u64| {
+            sheet.frame_at(tag.and_then(|name| sheet.tag(name)), Duration::from_millis(millis))
+        };
+        assert_eq!(at(&sheet, None, 0), 0);
+        assert_eq!(at(&sheet, None, 250), 1);
+        assert_eq!(at(&sheet, None, 350), 2);
+        assert_eq!(at(&sheet, None, 550), 0);
+
+        sheet.tags = vec![
+            FrameTag { name: "walk".to_string(), frames: 1..=3, direction: LoopDirection::PingPong },
+            FrameTag { name: "back".to_string(), frames: 0..=1, direction: LoopDirection::Reverse },
+        ];
+        // 1, 2, 3, 2 and around again.
+        assert_eq!(at(&sheet, Some("walk"), 150), 1);
+        assert_eq!(at(&sheet, Some("walk"), 250), 2);
+        assert_eq!(at(&sheet, Some("walk"), 350), 3);
+        assert_eq!(at(&sheet, Some("walk"), 450), 2);
+        assert_eq!(at(&sheet, Some("walk"), 550), 1);
+        assert_eq!(at(&sheet, Some("back"), 50), 1);
+        assert_eq!(at(&sheet, Some("back"), 250), 0);
+
+        assert_eq!(SpriteSheet { frames: Vec::new(), ..sheet }.frame_at(None, Duration::from_secs(1)), 0);
+    }
+}
@@ -0,0 +1,15 @@
+/// The triangles of an ASCII or binary STL file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StlObject {
+    /// The name after `solid`, or the 80 byte header of a binary file without its padding.
+    pub name: String,
+    pub triangles: Vec<StlTriangle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StlTriangle {
+    /// The normal as written in the file. Many exporters leave it zero.
+    pub normal: [f32; 3],
+    /// Counter-clockwise when seen from the side the normal points to.
+    pub vertices: [[f32; 3]; 3],
+}
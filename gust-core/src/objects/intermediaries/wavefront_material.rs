@@ -0,0 +1,105 @@
+/// The materials of one `.mtl` file, in file order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WavefrontMaterialLibrary {
+    pub materials: Vec<WavefrontMaterial>,
+}
+
+impl WavefrontMaterialLibrary {
+    /// Looks up a material by the name used in `newmtl` and `usemtl`.
+    pub fn get(&self, name: &str) -> Option<&WavefrontMaterial> {
+        self.materials.iter().find(|material| material.name == name)
+    }
+}
+
+/// A material as written in an MTL file. Colors are used as is, texture paths are as written
+/// and usually relative to the MTL file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavefrontMaterial {
+    pub name: String,
+    /// `Ka`
+    pub ambient: [f32; 3],
+    /// `Kd`
+    pub diffuse: [f32; 3],
+    /// `Ks`
+    pub specular: [f32; 3],
+    /// `Ke`
+    pub emissive: [f32; 3],
+    /// `Ns`, the specular exponent.
+    pub shininess: f32,
+    /// `Ni`, the index of refraction.
+    pub optical_density: f32,
+    /// `d`, or `1 - Tr`. 1 is fully opaque.
+    pub dissolve: f32,
+    /// `illum`
+    pub illumination_model: u32,
+    /// `map_Ka`
+    pub ambient_map: Option<TextureMap>,
+    /// `map_Kd`
+    pub diffuse_map: Option<TextureMap>,
+    /// `map_Ks`
+    pub specular_map: Option<TextureMap>,
+    /// `map_Ke`
+    pub emissive_map: Option<TextureMap>,
+    /// `map_Ns`
+    pub shininess_map: Option<TextureMap>,
+    /// `map_d`
+    pub dissolve_map: Option<TextureMap>,
+    /// `map_Bump` or `bump`
+    pub bump_map: Option<TextureMap>,
+    /// `norm`
+    pub normal_map: Option<TextureMap>,
+    /// `disp`
+    pub displacement_map: Option<TextureMap>,
+}
+
+impl WavefrontMaterial {
+    /// A material with the defaults from the MTL specification.
+    pub fn new(name: &str) -> Self {
+        WavefrontMaterial {
+            name: name.to_string(),
+            ambient: [0.2; 3],
+            diffuse: [0.8; 3],
+            specular: [1.0; 3],
+            emissive: [0.0; 3],
+            shininess: 0.0,
+            optical_density: 1.0,
+            dissolve: 1.0,
+            illumination_model: 2,
+            ambient_map: None,
+            diffuse_map: None,
+            specular_map: None,
+            emissive_map: None,
+            shininess_map: None,
+            dissolve_map: None,
+            bump_map: None,
+            normal_map: None,
+            displacement_map: None,
+        }
+    }
+}
+
+/// A texture reference with the options we support. Other options are skipped when parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMap {
+    pub path: String,
+    /// `-o`
+    pub offset: [f32; 3],
+    /// `-s`
+    pub scale: [f32; 3],
+    /// `-clamp`
+    pub clamp: bool,
+    /// `-bm`, only meaningful for bump maps.
+    pub bump_multiplier: f32,
+}
+
+impl TextureMap {
+    pub fn new(path: &str) -> Self {
+        TextureMap {
+            path: path.to_string(),
+            offset: [0.0; 3],
+            scale: [1.0; 3],
+            clamp: false,
+            bump_multiplier: 1.0,
+        }
+    }
+}
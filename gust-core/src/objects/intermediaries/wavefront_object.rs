@@ -1,10 +1,39 @@
+use std::ops::Range;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct WavefrontObject {
     pub vertices: Vec<[f32; 3]>,
     pub tex_coords: Vec<[f32; 2]>,
     pub normals: Vec<[f32; 3]>,
-    pub faces: Vec<Vec<[u32; 3]>>,
+    pub faces: Vec<Vec<FaceVertex>>,
+    /// Consecutive runs of `faces` sharing the same `o`, `g`, `s` and `usemtl` state, in file
+    /// order. Together they cover every face exactly once.
+    pub face_groups: Vec<FaceGroup>,
+    /// The files named by `mtllib` statements.
+    pub material_libraries: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FaceGroup {
+    /// The name from the last `o` statement.
+    pub object: Option<String>,
+    /// The names from the last `g` statement. A face can belong to several groups at once.
+    pub groups: Vec<String>,
+    /// The name from the last `usemtl` statement.
+    pub material: Option<String>,
+    /// The smoothing group from the last `s` statement, 0 when smoothing is off.
+    pub smoothing_group: u32,
+    /// The range of indices into `WavefrontObject::faces`.
+    pub faces: Range<usize>,
+}
+
+/// One corner of a face. Indices are zero based and already resolved, so negative (relative)
+/// indices from the file never reach this point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FaceVertex {
+    pub position: u32,
+    pub tex_coord: Option<u32>,
+    pub normal: Option<u32>,
 }
 
 // Face can have multiple forms
@@ -15,4 +44,5 @@ pub struct WavefrontObject {
 // 3. a list of vertex, texture, and normal indices
 //   This has the form v1/vt1/vn1 v2/vt2/vn2 v3/vt3/vn3
 // 4. a list of vertex and normal indices
-//   This has the form v1//vn1 v2//vn2 v3//vn3
\ No newline at end of file
+//   This has the form v1//vn1 v2//vn2 v3//vn3
+// Any index may be negative, in which case it counts back from the most recent element.
@@ -0,0 +1,285 @@
+use glium::Display;
+use glium::glutin::surface::WindowSurface;
+use glium::texture::RawImage2d;
+
+use gust_hierarchy::entity::Entity;
+use gust_hierarchy::world::World;
+use gust_math::vectors::vect3::Vect3;
+
+use crate::components::material_component::MaterialComponent;
+use crate::components::mesh_component::MeshComponent;
+use crate::components::transform_component::TransformComponent;
+use crate::objects::intermediaries::gltf_scene::{GltfNode, GltfScene};
+use crate::primitives::material::Material;
+use crate::primitives::texture::{Texture, TextureOptions};
+use crate::primitives::texture_error::TextureError;
+use crate::storages::material_storage::MaterialStorage;
+use crate::storages::mesh_storage::{MeshId, MeshStorage};
+use crate::storages::texture_storage::{TextureId, TextureStorage};
+
+impl GltfScene {
+    /// Uploads the images and spawns the scene, see `spawn`.
+    pub fn instantiate(
+        &self,
+        display: &Display<WindowSurface>,
+        world: &mut World,
+        mesh_storage: &mut MeshStorage,
+        texture_storage: &mut TextureStorage,
+        material_storage: &mut MaterialStorage,
+    ) -> Result<Entity, TextureError> {
+        let textures = self.upload_images(display, texture_storage)?;
+        Ok(self.spawn(world, mesh_storage, material_storage, &textures))
+    }
+
+    /// Uploads every image, in order, so the result can be indexed like `images`. Normal maps
+    /// are linear, every other image holds sRGB colors.
+    pub fn upload_images(
+        &self,
+        display: &Display<WindowSurface>,
+        texture_storage: &mut TextureStorage,
+    ) -> Result<Vec<TextureId>, TextureError> {
+        self.images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                // Textures are stored bottom row first
+                let raw = RawImage2d::from_raw_rgba_reversed(&image.pixels, (image.width, image.height));
+                let options = match self.materials.iter().any(|material| material.normal_image == Some(index)) {
+                    true => TextureOptions::linear(),
+                    false => TextureOptions::default(),
+                };
+                Ok(texture_storage.add_texture(Texture::new(display, raw, options)?))
+            })
+            .collect()
+    }
+
+    /// Adds the meshes and materials to the storages and spawns an entity for every node of the
+    /// displayed scene, parented like the node tree. `textures` holds the `TextureId` of each
+    /// image, as returned by `upload_images`; images past its end leave materials untextured.
+    ///
+    /// Returns a new root entity that turns the glTF axes (+Y up, front facing +Z) into ours
+    /// (+Z up, front facing +X). A node with a single primitive draws it itself, a node with
+    /// several gets a child entity per primitive.
+    pub fn spawn(
+        &self,
+        world: &mut World,
+        mesh_storage: &mut MeshStorage,
+        material_storage: &mut MaterialStorage,
+        textures: &[TextureId],
+    ) -> Entity {
+        let material_ids: Vec<_> = self
+            .materials
+            .iter()
+            .map(|material| {
+                material_storage.add_material(Material {
+                    diffuse_texture: material.diffuse_image.and_then(|image| textures.get(image).copied()),
+                    normal_texture: material.normal_image.and_then(|image| textures.get(image).copied()),
+                    ..material.material.clone()
+                })
+            })
+            .collect();
+        let default_material = self
+            .meshes
+            .iter()
+            .flatten()
+            .any(|primitive| primitive.material.is_none())
+            .then(|| material_storage.add_material(Material::new()));
+        let mesh_ids: Vec<Vec<(MeshId, _)>> = self
+            .meshes
+            .iter()
+            .map(|primitives| {
+                primitives
+                    .iter()
+                    .map(|primitive| {
+                        let material = primitive.material.map(|index| material_ids[index]).or(default_material);
+                        (mesh_storage.add_mesh(primitive.mesh.clone()), material.unwrap())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let root = world.spawn();
+        let axes = TransformComponent::default()
+            .with_forward([0.0, 1.0, 0.0].into())
+            .with_up([1.0, 0.0, 0.0].into());
+        world.add_component(root, axes);
+
+        // Nodes reached a second time, through a cycle or a second parent, are skipped.
+        let mut visited = vec![false; self.nodes.len()];
+        let mut pending: Vec<(Entity, usize)> = self.roots.iter().rev().map(|&node| (root, node)).collect();
+        while let Some((parent, index)) = pending.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            let node = &self.nodes[index];
+            let entity = world.spawn();
+            world.add_component(entity, GltfScene::node_transform(node));
+            world.set_parent(parent, entity);
+
+            if let Some(mesh) = node.mesh {
+                match mesh_ids[mesh].as_slice() {
+                    [(mesh_id, material_id)] => {
+                        world.add_component(entity, MeshComponent(*mesh_id));
+                        world.add_component(entity, MaterialComponent(*material_id));
+                    }
+                    primitives => {
+                        for (mesh_id, material_id) in primitives {
+                            let primitive = world.spawn();
+                            world.add_component(primitive, TransformComponent::default());
+                            world.add_component(primitive, MeshComponent(*mesh_id));
+                            world.add_component(primitive, MaterialComponent(*material_id));
+                            world.set_parent(entity, primitive);
+                        }
+                    }
+                }
+            }
+
+            pending.extend(node.children.iter().rev().map(|&child| (entity, child)));
+        }
+
+        root
+    }
+
+    /// Our transforms face `forward` with `up` on top, which is +X and +Z unrotated, so both are
+    /// rotated by the node's quaternion.
+    fn node_transform(node: &GltfNode) -> TransformComponent {
+        TransformComponent::default()
+            .with_position(node.translation.into())
+            .with_forward(rotate(node.rotation, [1.0, 0.0, 0.0]))
+            .with_up(rotate(node.rotation, [0.0, 0.0, 1.0]))
+            .with_scale(node.scale.into())
+    }
+}
+
+/// Rotates `vector` by the unit quaternion `[x, y, z, w]`.
+fn rotate([x, y, z, w]: [f32; 4], vector: [f32; 3]) -> Vect3 {
+    let axis = Vect3::new(x, y, z);
+    let vector = Vect3::from(vector);
+    let twice_cross = axis.cross(&vector) * 2.0;
+    vector + twice_cross * w + axis.cross(&twice_cross)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::objects::intermediaries::gltf_scene::GltfMaterial;
+
+    /// A red triangle node with a child holding a two primitive mesh, the second primitive
+    /// without a material, and a second top level node.
+    const SCENE: &str = r#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{"byteLength": 44, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="}],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}, {"buffer": 0, "byteOffset": 36, "byteLength": 6}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}
+        ],
+        "meshes": [
+            {"primitives": [{"attributes": {"POSITION": 0}, "indices": 1, "material": 0}]},
+            {"primitives": [{"attributes": {"POSITION": 0}, "indices": 1, "material": 0}, {"attributes": {"POSITION": 0}}]}
+        ],
+        "materials": [{"pbrMetallicRoughness": {"baseColorFactor": [1, 0, 0, 1], "baseColorTexture": {"index": 0}}}],
+        "textures": [{"source": 0}],
+        "images": [{"uri": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAACCAIAAAAW4yFwAAAADUlEQVR4nGP4zwAC/wEIAAH/2ZC7NQAAAABJRU5ErkJggg=="}],
+        "nodes": [
+            {"name": "body", "mesh": 0, "translation": [1, 2, 3], "children": [1]},
+            {"name": "arm", "mesh": 1, "rotation": [0, 0, 0.70710677, 0.70710677], "scale": [2, 2, 2]},
+            {"name": "light"}
+        ],
+        "scenes": [{"nodes": [0, 2]}]
+    }"#;
+
+    fn assert_near(actual: Vect3, expected: [f32; 3]) {
+        let difference = actual - Vect3::from(expected);
+        assert!(difference.norm() < 1e-6, "{:?} != {:?}", actual, expected);
+    }
+
+    fn children(world: &World, entity: Entity) -> Vec<Entity> {
+        world.get_children(entity).cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_spawn_matches_node_tree() {
+        let scene = GltfScene::from_slice(SCENE.as_bytes(), Path::new(".")).unwrap();
+        let mut world = World::new();
+        let mut mesh_storage = MeshStorage::new();
+        let mut material_storage = MaterialStorage::new();
+
+        let root = scene.spawn(&mut world, &mut mesh_storage, &mut material_storage, &[TextureId(7)]);
+
+        // Three primitives, one textured material and the default material.
+        assert_eq!(mesh_storage.mesh_count(), 3);
+        assert_eq!(material_storage.material_count(), 2);
+
+        let root_transform = world.get_component::<TransformComponent>(root).unwrap();
+        assert_eq!((root_transform.forward, root_transform.up), ([0.0, 1.0, 0.0].into(), [1.0, 0.0, 0.0].into()));
+        assert!(!world.has_component::<MeshComponent>(root));
+
+        let top_level = children(&world, root);
+        assert_eq!(top_level.len(), 2);
+        let (body, light) = (top_level[0], top_level[1]);
+        assert_eq!(world.get_parent(body), Some(root));
+        assert_eq!(world.get_component::<TransformComponent>(body).unwrap().position, [1.0, 2.0, 3.0].into());
+        assert!(!world.has_component::<MeshComponent>(light));
+        assert!(children(&world, light).is_empty());
+
+        let material_id = world.get_component::<MaterialComponent>(body).unwrap().0;
+        let material = material_storage.get_material(material_id).unwrap();
+        assert_eq!(material.diffuse_texture, Some(TextureId(7)));
+        let mesh_id = world.get_component::<MeshComponent>(body).unwrap().0;
+        assert_eq!(mesh_storage.get_mesh(mesh_id).unwrap().triangle_count(), 1);
+
+        // The arm is turned a quarter around +Z and has one child per primitive.
+        let arm = children(&world, body)[0];
+        let arm_transform = world.get_component::<TransformComponent>(arm).unwrap();
+        assert_near(arm_transform.forward, [0.0, 1.0, 0.0]);
+        assert_near(arm_transform.up, [0.0, 0.0, 1.0]);
+        assert_eq!(arm_transform.scale, [2.0, 2.0, 2.0].into());
+        assert!(!world.has_component::<MeshComponent>(arm));
+
+        let primitives = children(&world, arm);
+        assert_eq!(primitives.len(), 2);
+        let materials: Vec<_> = primitives.iter().map(|&entity| world.get_component::<MaterialComponent>(entity).unwrap().0).collect();
+        assert_eq!(materials[0], material_id);
+        assert_eq!(material_storage.get_material(materials[1]), Some(&Material::new()));
+    }
+
+    #[test]
+    fn test_spawn_cyclic_nodes_and_missing_textures() {
+        let node = |children| GltfNode {
+            name: None,
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+            mesh: None,
+            children,
+        };
+        let scene = GltfScene {
+            nodes: vec![node(vec![1]), node(vec![0])],
+            roots: vec![0],
+            materials: vec![GltfMaterial { name: None, material: Material::new(), diffuse_image: Some(3), normal_image: None }],
+            ..GltfScene::default()
+        };
+        let mut world = World::new();
+        let mut material_storage = MaterialStorage::new();
+
+        let root = scene.spawn(&mut world, &mut MeshStorage::new(), &mut material_storage, &[]);
+
+        let first = children(&world, root);
+        assert_eq!(first.len(), 1);
+        let second = children(&world, first[0]);
+        assert_eq!(second.len(), 1);
+        assert!(children(&world, second[0]).is_empty());
+        assert_eq!(material_storage.material_count(), 1);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_near(rotate([0.0, 0.0, 0.0, 1.0], [1.0, 2.0, 3.0]), [1.0, 2.0, 3.0]);
+        assert_near(rotate([0.0, half, 0.0, half], [1.0, 0.0, 0.0]), [0.0, 0.0, -1.0]);
+        assert_near(rotate([1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0]), [0.0, -1.0, 0.0]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use crate::objects::intermediaries::mesh_cache::MeshCache;
+use crate::objects::intermediaries::wavefront_object::WavefrontObject;
+use crate::objects::parsers::parse_error::ParseError;
+
+impl MeshCache {
+    /// Where the cache of a source file lives: next to it, with `.gmesh` appended.
+    pub fn cache_path(source_path: &Path) -> PathBuf {
+        let mut path = source_path.as_os_str().to_owned();
+        path.push(".gmesh");
+        PathBuf::from(path)
+    }
+
+    /// Loads an OBJ file through its cache. The cache is used when it is at least as new as the
+    /// source, or when the source is gone. Otherwise the source is parsed and the cache written
+    /// again; failing to write it is not an error, the next load just parses again.
+    pub fn load_wavefront(source_path: &Path) -> Result<MeshCache, ParseError> {
+        let cache_path = MeshCache::cache_path(source_path);
+        if is_fresh(source_path, &cache_path) {
+            // A cache from another version or a damaged one is replaced below.
+            if let Ok(cache) = MeshCache::parse(&cache_path) {
+                return Ok(cache);
+            }
+        }
+
+        let cache = MeshCache::from_wavefront(&WavefrontObject::parse(source_path)?);
+        let _ = cache.save(&cache_path);
+        Ok(cache)
+    }
+}
+
+fn is_fresh(source_path: &Path, cache_path: &Path) -> bool {
+    let modified = |path: &Path| path.metadata().and_then(|metadata| metadata.modified());
+    match (modified(source_path), modified(cache_path)) {
+        (Ok(source), Ok(cache)) => cache > source,
+        (Err(_), Ok(_)) => true,
+        (_, Err(_)) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn test_load_wavefront() {
+        let directory = std::env::temp_dir().join(format!("gust_mesh_cache_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("triangle.obj");
+        let cache_path = MeshCache::cache_path(&source);
+        assert_eq!(cache_path, directory.join("triangle.obj.gmesh"));
+        std::fs::write(&source, TRIANGLE).unwrap();
+
+        // The first load converts the source
+        let cache = MeshCache::load_wavefront(&source).unwrap();
+        assert_eq!(cache.mesh.triangle_count(), 1);
+        assert_eq!(MeshCache::parse(&cache_path).unwrap(), cache);
+
+        // A newer cache is preferred, even if the source changed
+        std::fs::write(&source, format!("{}v 0 0 1\nf 1 3 4\n", TRIANGLE)).unwrap();
+        let now = SystemTime::now();
+        set_modified(&source, now - Duration::from_secs(60));
+        set_modified(&cache_path, now);
+        assert_eq!(MeshCache::load_wavefront(&source).unwrap().mesh.triangle_count(), 1);
+
+        // An older one is converted again
+        set_modified(&cache_path, now - Duration::from_secs(120));
+        assert_eq!(MeshCache::load_wavefront(&source).unwrap().mesh.triangle_count(), 2);
+        assert_eq!(MeshCache::parse(&cache_path).unwrap().mesh.triangle_count(), 2);
+
+        // Without the source only the cache is left
+        std::fs::remove_file(&source).unwrap();
+        assert_eq!(MeshCache::load_wavefront(&source).unwrap().mesh.triangle_count(), 2);
+
+        // A damaged cache is not trusted
+        std::fs::write(&cache_path, b"GMSH").unwrap();
+        assert!(MeshCache::load_wavefront(&source).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
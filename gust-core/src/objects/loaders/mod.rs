@@ -0,0 +1,2 @@
+pub mod gltf_loader;
+pub mod mesh_cache_loader;
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod intermediaries;
-pub mod parsers;
\ No newline at end of file
+pub mod parsers;
+pub mod loaders;
+pub mod writers;
\ No newline at end of file
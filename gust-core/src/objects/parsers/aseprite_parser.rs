@@ -0,0 +1,382 @@
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use flate2::read::ZlibDecoder;
+use image::RgbaImage;
+
+use crate::objects::intermediaries::sprite_sheet::{FrameTag, LoopDirection, SpriteFrame, SpriteSheet};
+use crate::objects::parsers::binary_reader::BinaryReader;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const HEADER_SIZE: usize = 128;
+
+const LAYER_CHUNK: u16 = 0x2004;
+const CEL_CHUNK: u16 = 0x2005;
+const TAGS_CHUNK: u16 = 0x2018;
+const PALETTE_CHUNK: u16 = 0x2019;
+
+const LAYER_VISIBLE: u16 = 1;
+const LAYER_GROUP: u16 = 1;
+/// Set in the header flags when layer opacity was saved, older files leave it out.
+const LAYER_OPACITY_VALID: u32 = 1;
+
+const RAW_CEL: u16 = 0;
+const LINKED_CEL: u16 = 1;
+const COMPRESSED_CEL: u16 = 2;
+
+struct Layer {
+    /// Includes the visibility of the groups the layer is in.
+    visible: bool,
+    opacity: u8,
+}
+
+struct Cel {
+    layer: usize,
+    x: i32,
+    y: i32,
+    opacity: u8,
+    z_index: i32,
+    image: RgbaImage,
+}
+
+/// How the pixels of cels are stored.
+#[derive(Clone, Copy)]
+enum ColorDepth {
+    Rgba,
+    Grayscale,
+    Indexed { transparent: u8 },
+}
+
+impl SpriteSheet {
+    pub fn parse_aseprite(file_path: &Path) -> Result<SpriteSheet, ParseError> {
+        SpriteSheet::from_aseprite_bytes(&std::fs::read(file_path)?)
+    }
+
+    pub fn from_aseprite_reader<R: Read>(mut reader: R) -> Result<SpriteSheet, ParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        SpriteSheet::from_aseprite_bytes(&bytes)
+    }
+
+    /// Reads an Aseprite `.aseprite` or `.ase` file, flattening the visible layers of each frame
+    /// into one image. Every blend mode is drawn as normal, and tilemap layers are left out.
+    pub fn from_aseprite_bytes(bytes: &[u8]) -> Result<SpriteSheet, ParseError> {
+        let mut reader = BinaryReader::new(bytes);
+        reader.u32("file size")?;
+        if reader.u16("magic")? != FILE_MAGIC {
+            return Err(ParseError::Binary { offset: 4, kind: ParseErrorKind::InvalidMagic });
+        }
+        let frame_count = reader.u16("frame count")?;
+        let width = reader.u16("width")? as u32;
+        let height = reader.u16("height")? as u32;
+        let depth = match reader.u16("color depth")? {
+            32 => ColorDepth::Rgba,
+            16 => ColorDepth::Grayscale,
+            8 => {
+                reader.offset = 28;
+                ColorDepth::Indexed { transparent: reader.u8("transparent index")? }
+            }
+            depth => return Err(ParseError::Binary { offset: 12, kind: ParseErrorKind::UnsupportedColorDepth(depth) }),
+        };
+        reader.offset = 14;
+        let flags = reader.u32("flags")?;
+        reader.offset = HEADER_SIZE;
+
+        let mut layers: Vec<Layer> = Vec::new();
+        // The visibility of the innermost group at each nesting level.
+        let mut groups: Vec<bool> = Vec::new();
+        let mut palette: Vec<[u8; 4]> = Vec::new();
+        let mut tags = Vec::new();
+        let mut frame_cels: Vec<Vec<Cel>> = Vec::with_capacity(frame_count as usize);
+        let mut durations = Vec::with_capacity(frame_count as usize);
+
+        for _ in 0..frame_count {
+            let frame_start = reader.offset;
+            let frame_size = reader.u32("frame size")? as usize;
+            if reader.u16("frame magic")? != FRAME_MAGIC {
+                return Err(ParseError::Binary { offset: frame_start + 4, kind: ParseErrorKind::InvalidMagic });
+            }
+            let old_chunk_count = reader.u16("chunk count")?;
+            durations.push(Duration::from_millis(reader.u16("frame duration")? as u64));
+            reader.take(2, "frame header")?;
+            let chunk_count = match reader.u32("chunk count")? {
+                0 => old_chunk_count as u32,
+                count => count,
+            };
+
+            let mut cels = Vec::new();
+            for _ in 0..chunk_count {
+                let chunk_start = reader.offset;
+                let chunk_size = reader.u32("chunk size")? as usize;
+                let chunk_type = reader.u16("chunk type")?;
+                if chunk_size < 6 {
+                    return Err(reader.error(ParseErrorKind::UnexpectedEnd("chunk")));
+                }
+                let mut chunk = BinaryReader { bytes: reader.take(chunk_size - 6, "chunk")?, offset: 0 };
+                let chunk_error = |error: ParseError| match error {
+                    ParseError::Binary { offset, kind } => ParseError::Binary { offset: chunk_start + 6 + offset, kind },
+                    error => error,
+                };
+
+                match chunk_type {
+                    LAYER_CHUNK => layers.push(parse_layer(&mut chunk, &mut groups, flags).map_err(chunk_error)?),
+                    CEL_CHUNK => {
+                        if let Some(cel) = parse_cel(&mut chunk, depth, &palette, &frame_cels).map_err(chunk_error)? {
+                            cels.push(cel);
+                        }
+                    }
+                    TAGS_CHUNK => tags = parse_tags(&mut chunk).map_err(chunk_error)?,
+                    PALETTE_CHUNK => parse_palette(&mut chunk, &mut palette).map_err(chunk_error)?,
+                    _ => {}
+                }
+            }
+
+            if reader.offset - frame_start != frame_size {
+                return Err(reader.error(ParseErrorKind::UnexpectedToken { expected: "end of frame", found: "more chunks".to_string() }));
+            }
+            frame_cels.push(cels);
+        }
+
+        let frames = frame_cels
+            .iter()
+            .zip(durations)
+            .map(|(cels, duration)| SpriteFrame { image: flatten(width, height, cels, &layers), duration })
+            .collect();
+
+        Ok(SpriteSheet { frame_width: width, frame_height: height, frames, tags })
+    }
+}
+
+fn parse_layer(chunk: &mut BinaryReader, groups: &mut Vec<bool>, flags: u32) -> Result<Layer, ParseError> {
+    let layer_flags = chunk.u16("layer flags")?;
+    let layer_type = chunk.u16("layer type")?;
+    let level = chunk.u16("layer child level")? as usize;
+    chunk.take(6, "layer size and blend mode")?;
+    let opacity = chunk.u8("layer opacity")?;
+
+    groups.truncate(level);
+    let visible = layer_flags & LAYER_VISIBLE != 0 && groups.iter().all(|&visible| visible);
+    if layer_type == LAYER_GROUP {
+        groups.push(visible);
+    }
+
+    Ok(Layer {
+        // Groups only hide or show their layers, and tilemaps aren't supported.
+        visible: visible && layer_type == 0,
+        opacity: match flags & LAYER_OPACITY_VALID {
+            0 => 255,
+            _ => opacity,
+        },
+    })
+}
+
+fn parse_cel(chunk: &mut BinaryReader, depth: ColorDepth, palette: &[[u8; 4]], frames: &[Vec<Cel>]) -> Result<Option<Cel>, ParseError> {
+    let layer = chunk.u16("cel layer")? as usize;
+    let x = chunk.i16("cel x")? as i32;
+    let y = chunk.i16("cel y")? as i32;
+    let opacity = chunk.u8("cel opacity")?;
+    let cel_type = chunk.u16("cel type")?;
+    let z_index = chunk.i16("cel z-index")? as i32;
+    chunk.take(5, "cel header")?;
+
+    let image = match cel_type {
+        RAW_CEL | COMPRESSED_CEL => {
+            let width = chunk.u16("cel width")? as u32;
+            let height = chunk.u16("cel height")? as u32;
+            let size = width as usize * height as usize * depth.bytes_per_pixel();
+            let pixels = match cel_type {
+                RAW_CEL => chunk.take(size, "cel pixels")?.to_vec(),
+                _ => {
+                    let offset = chunk.offset;
+                    // The size comes from the file, so it only limits the preallocation by the
+                    // compressed data that is there.
+                    let mut pixels = Vec::with_capacity(size.min(chunk.bytes.len() - offset));
+                    ZlibDecoder::new(&chunk.bytes[offset..])
+                        .take(size as u64)
+                        .read_to_end(&mut pixels)
+                        .map_err(|_| ParseError::Binary { offset, kind: ParseErrorKind::InvalidCompressedData })?;
+                    if pixels.len() != size {
+                        return Err(ParseError::Binary { offset, kind: ParseErrorKind::UnexpectedEnd("cel pixels") });
+                    }
+                    pixels
+                }
+            };
+            let rgba = pixels.chunks_exact(depth.bytes_per_pixel()).flat_map(|pixel| depth.to_rgba(pixel, palette)).collect();
+            RgbaImage::from_raw(width, height, rgba).unwrap()
+        }
+        LINKED_CEL => {
+            let frame = chunk.u16("linked frame")? as usize;
+            let linked = frames.get(frame).and_then(|cels| cels.iter().find(|cel| cel.layer == layer));
+            match linked {
+                Some(cel) => cel.image.clone(),
+                None => return Err(chunk.error(ParseErrorKind::IndexOutOfRange { element: "frame", index: frame as i64, count: frames.len() })),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(Cel { layer, x, y, opacity, z_index, image }))
+}
+
+fn parse_tags(chunk: &mut BinaryReader) -> Result<Vec<FrameTag>, ParseError> {
+    let count = chunk.u16("tag count")?;
+    chunk.take(8, "tags header")?;
+    (0..count)
+        .map(|_| {
+            let from = chunk.u16("tag start")? as usize;
+            let to = chunk.u16("tag end")? as usize;
+            let direction = match chunk.u8("tag direction")? {
+                1 => LoopDirection::Reverse,
+                2 => LoopDirection::PingPong,
+                3 => LoopDirection::PingPongReverse,
+                _ => LoopDirection::Forward,
+            };
+            chunk.take(12, "tag")?;
+            let length = chunk.u16("tag name")? as usize;
+            let name = chunk.utf8(length, "tag name")?;
+            Ok(FrameTag { name, frames: from..=to, direction })
+        })
+        .collect()
+}
+
+fn parse_palette(chunk: &mut BinaryReader, palette: &mut Vec<[u8; 4]>) -> Result<(), ParseError> {
+    let size = chunk.u32("palette size")? as usize;
+    let first = chunk.u32("first color")? as usize;
+    let last = chunk.u32("last color")? as usize;
+    chunk.take(8, "palette header")?;
+    palette.resize(size.min(256), [0; 4]);
+    for index in first..=last {
+        let flags = chunk.u16("color flags")?;
+        let color: [u8; 4] = chunk.take(4, "color")?.try_into().unwrap();
+        if flags & 1 != 0 {
+            let length = chunk.u16("color name")? as usize;
+            chunk.take(length, "color name")?;
+        }
+        if let Some(entry) = palette.get_mut(index) {
+            *entry = color;
+        }
+    }
+    Ok(())
+}
+
+/// Draws the cels of a frame over each other, lowest layer first.
+fn flatten(width: u32, height: u32, cels: &[Cel], layers: &[Layer]) -> RgbaImage {
+    let mut image = RgbaImage::new(width, height);
+    let mut cels: Vec<&Cel> = cels.iter().collect();
+    cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index, cel.z_index));
+
+    for cel in cels {
+        let Some(layer) = layers.get(cel.layer).filter(|layer| layer.visible) else {
+            continue;
+        };
+        let opacity = (cel.opacity as u32 * layer.opacity as u32 / 255) as u8;
+        for (x, y, pixel) in cel.image.enumerate_pixels() {
+            let (x, y) = (cel.x + x as i32, cel.y + y as i32);
+            if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                blend(image.get_pixel_mut(x as u32, y as u32), pixel.0, opacity);
+            }
+        }
+    }
+    image
+}
+
+/// Draws a color over another, both with unpremultiplied alpha.
+fn blend(target: &mut image::Rgba<u8>, color: [u8; 4], opacity: u8) {
+    let source_alpha = color[3] as f32 / 255.0 * opacity as f32 / 255.0;
+    let target_alpha = target.0[3] as f32 / 255.0;
+    let alpha = source_alpha + target_alpha * (1.0 - source_alpha);
+    if alpha <= 0.0 {
+        return;
+    }
+    for (target, &color) in target.0.iter_mut().zip(&color).take(3) {
+        let mixed = color as f32 * source_alpha + *target as f32 * target_alpha * (1.0 - source_alpha);
+        *target = (mixed / alpha).round() as u8;
+    }
+    target.0[3] = (alpha * 255.0).round() as u8;
+}
+
+impl ColorDepth {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            ColorDepth::Rgba => 4,
+            ColorDepth::Grayscale => 2,
+            ColorDepth::Indexed { .. } => 1,
+        }
+    }
+
+    fn to_rgba(self, pixel: &[u8], palette: &[[u8; 4]]) -> [u8; 4] {
+        match self {
+            ColorDepth::Rgba => pixel.try_into().unwrap(),
+            ColorDepth::Grayscale => [pixel[0], pixel[0], pixel[0], pixel[1]],
+            ColorDepth::Indexed { transparent } if pixel[0] == transparent => [0; 4],
+            ColorDepth::Indexed { .. } => palette.get(pixel[0] as usize).copied().unwrap_or([0; 4]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_astro() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/astro.aseprite");
+        let sheet = SpriteSheet::parse_aseprite(&path).unwrap();
+        assert_eq!((sheet.frame_width, sheet.frame_height), (16, 32));
+        assert_eq!(sheet.frames.len(), 3);
+        assert!(sheet.frames.iter().all(|frame| frame.duration == Duration::from_millis(100)));
+        assert!(sheet.tags.is_empty());
+
+        // Only the walking layer is visible, the hidden flying one would cover the bottom half.
+        let frame = &sheet.frames[0].image;
+        assert!(frame.pixels().any(|pixel| pixel.0[3] == 255));
+        assert!((16..32).all(|y| (0..16).all(|x| frame.get_pixel(x, y).0[3] == 0)));
+        assert_ne!(sheet.frames[0].image, sheet.frames[1].image);
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut pixel = image::Rgba([0, 0, 255, 255]);
+        blend(&mut pixel, [255, 0, 0, 255], 128);
+        assert_eq!(pixel.0, [128, 0, 127, 255]);
+
+        let mut pixel = image::Rgba([0, 0, 0, 0]);
+        blend(&mut pixel, [10, 20, 30, 40], 255);
+        assert_eq!(pixel.0, [10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_errors() {
+        match SpriteSheet::from_aseprite_bytes(&[0; 128]) {
+            Err(ParseError::Binary { offset: 4, kind }) => assert_eq!(kind, ParseErrorKind::InvalidMagic),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/astro.aseprite");
+        let bytes = std::fs::read(path).unwrap();
+        let mut damaged = bytes.clone();
+        damaged[12..14].copy_from_slice(&24u16.to_le_bytes());
+        match SpriteSheet::from_aseprite_bytes(&damaged) {
+            Err(ParseError::Binary { offset: 12, kind }) => assert_eq!(kind, ParseErrorKind::UnsupportedColorDepth(24)),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        // The first cel of the walking layer, claiming to be as large as a cel can be.
+        let mut huge = bytes.clone();
+        let cel = 128 + 16 + 22 + 140 + 67 + 36 + 37;
+        assert_eq!(u16::from_le_bytes([huge[cel + 4], huge[cel + 5]]), CEL_CHUNK);
+        huge[cel + 22..cel + 26].copy_from_slice(&[0xFF; 4]);
+        match SpriteSheet::from_aseprite_bytes(&huge) {
+            Err(ParseError::Binary { kind: ParseErrorKind::UnexpectedEnd("cel pixels"), .. }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        match SpriteSheet::from_aseprite_bytes(&bytes[..600]) {
+            Err(ParseError::Binary { kind: ParseErrorKind::UnexpectedEnd(_), .. }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
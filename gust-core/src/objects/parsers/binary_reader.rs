@@ -0,0 +1,54 @@
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+/// Reads little endian values from the front of the data, keeping track of the offset for errors.
+pub(crate) struct BinaryReader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) offset: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        BinaryReader { bytes, offset: 0 }
+    }
+
+    pub(crate) fn take(&mut self, size: usize, expected: &'static str) -> Result<&'a [u8], ParseError> {
+        let data = self.bytes.get(self.offset..self.offset.saturating_add(size)).ok_or(ParseError::Binary {
+            offset: self.bytes.len(),
+            kind: ParseErrorKind::UnexpectedEnd(expected),
+        })?;
+        self.offset += size;
+        Ok(data)
+    }
+
+    pub(crate) fn u8(&mut self, expected: &'static str) -> Result<u8, ParseError> {
+        Ok(self.take(1, expected)?[0])
+    }
+
+    pub(crate) fn u16(&mut self, expected: &'static str) -> Result<u16, ParseError> {
+        Ok(u16::from_le_bytes(self.take(2, expected)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn i16(&mut self, expected: &'static str) -> Result<i16, ParseError> {
+        Ok(i16::from_le_bytes(self.take(2, expected)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self, expected: &'static str) -> Result<u32, ParseError> {
+        Ok(u32::from_le_bytes(self.take(4, expected)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn f32s<const N: usize>(&mut self, expected: &'static str) -> Result<[f32; N], ParseError> {
+        let data = self.take(N * 4, expected)?;
+        Ok(std::array::from_fn(|index| f32::from_le_bytes(data[index * 4..index * 4 + 4].try_into().unwrap())))
+    }
+
+    pub(crate) fn utf8(&mut self, length: usize, expected: &'static str) -> Result<String, ParseError> {
+        let offset = self.offset;
+        let data = self.take(length, expected)?;
+        let string = std::str::from_utf8(data).map_err(|_| ParseError::Binary { offset, kind: ParseErrorKind::InvalidString })?;
+        Ok(string.to_string())
+    }
+
+    pub(crate) fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::Binary { offset: self.offset, kind }
+    }
+}
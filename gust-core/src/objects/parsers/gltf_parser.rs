@@ -0,0 +1,442 @@
+use std::fmt;
+use std::path::Path;
+
+use gltf::image::Format;
+use gltf::mesh::Mode;
+
+use crate::objects::intermediaries::gltf_scene::{GltfImage, GltfMaterial, GltfNode, GltfPrimitive, GltfScene};
+use crate::primitives::color::Color;
+use crate::primitives::material::Material;
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// Errors from reading glTF and GLB files.
+#[derive(Debug)]
+pub enum GltfError {
+    /// Reading, decoding or validating the file, its buffers or its images failed.
+    Gltf(gltf::Error),
+    MissingPositions { mesh: usize, primitive: usize },
+    IndexOutOfRange { mesh: usize, primitive: usize, index: u32, count: usize },
+    /// A vertex attribute has a different number of values than there are positions.
+    AttributeCountMismatch { mesh: usize, primitive: usize, attribute: &'static str, count: usize, expected: usize },
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GltfError::Gltf(error) => write!(f, "failed to load glTF: {}", error),
+            GltfError::MissingPositions { mesh, primitive } => {
+                write!(f, "primitive {} of mesh {} has no positions", primitive, mesh)
+            }
+            GltfError::IndexOutOfRange { mesh, primitive, index, count } => write!(
+                f,
+                "primitive {} of mesh {} uses vertex {}, but only has {}",
+                primitive, mesh, index, count
+            ),
+            GltfError::AttributeCountMismatch { mesh, primitive, attribute, count, expected } => write!(
+                f,
+                "primitive {} of mesh {} has {} {} values for {} positions",
+                primitive, mesh, count, attribute, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GltfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GltfError::Gltf(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<gltf::Error> for GltfError {
+    fn from(error: gltf::Error) -> Self {
+        GltfError::Gltf(error)
+    }
+}
+
+impl GltfScene {
+    /// Loads a `.gltf` or `.glb` file. External buffers and images are resolved relative to it.
+    pub fn parse(file_path: &Path) -> Result<GltfScene, GltfError> {
+        let (document, buffers, images) = gltf::import(file_path)?;
+        GltfScene::from_document(&document, &buffers, images)
+    }
+
+    /// Loads a `.gltf` or `.glb` file from memory. External buffers and images are resolved
+    /// relative to `base`.
+    pub fn from_slice(bytes: &[u8], base: &Path) -> Result<GltfScene, GltfError> {
+        // `gltf::import_slice` refuses embedded images without a base, so import step by step
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(bytes)?;
+        let buffers = gltf::import_buffers(&document, Some(base), blob)?;
+        let images = gltf::import_images(&document, Some(base), &buffers)?;
+        GltfScene::from_document(&document, &buffers, images)
+    }
+
+    fn from_document(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: Vec<gltf::image::Data>,
+    ) -> Result<GltfScene, GltfError> {
+        let mut scene = GltfScene {
+            images: images.into_iter().map(convert_image).collect(),
+            materials: document.materials().map(|material| convert_material(&material)).collect(),
+            ..GltfScene::default()
+        };
+
+        for mesh in document.meshes() {
+            let mut primitives = Vec::new();
+            for primitive in mesh.primitives() {
+                if let Some(primitive) = read_primitive(&mesh, &primitive, buffers)? {
+                    primitives.push(primitive);
+                }
+            }
+            scene.meshes.push(primitives);
+        }
+
+        for node in document.nodes() {
+            let (translation, rotation, scale) = node.transform().decomposed();
+            scene.nodes.push(GltfNode {
+                name: node.name().map(str::to_string),
+                translation,
+                rotation,
+                scale,
+                mesh: node.mesh().map(|mesh| mesh.index()),
+                children: node.children().map(|child| child.index()).collect(),
+            });
+        }
+
+        // Files without scenes are libraries of nodes, so every node without a parent is shown.
+        scene.roots = match document.default_scene().or_else(|| document.scenes().next()) {
+            Some(displayed) => displayed.nodes().map(|node| node.index()).collect(),
+            None => (0..scene.nodes.len())
+                .filter(|&index| scene.nodes.iter().all(|node| !node.children.contains(&index)))
+                .collect(),
+        };
+
+        Ok(scene)
+    }
+}
+
+/// Converts a primitive to a mesh. Points and lines can't be drawn by us and are skipped.
+fn read_primitive(
+    mesh: &gltf::Mesh,
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Option<GltfPrimitive>, GltfError> {
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+    let positions: Vec<[f32; 3]> = reader
+        .read_positions()
+        .ok_or(GltfError::MissingPositions { mesh: mesh.index(), primitive: primitive.index() })?
+        .collect();
+    let normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(|normals| normals.collect());
+    // Both glTF and we point the bitangent towards the top of the texture, so the sign is kept.
+    let tangents: Option<Vec<[f32; 4]>> = reader.read_tangents().map(|tangents| tangents.collect());
+    // glTF puts the origin of texture coordinates at the top left, we put it at the bottom left.
+    let tex_coords: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+        Some(tex_coords) => tex_coords.into_f32().map(|[u, v]| [u, 1.0 - v]).collect(),
+        None => vec![[0.0, 0.0]; positions.len()],
+    };
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let counts = [
+        ("NORMAL", normals.as_ref().map(Vec::len)),
+        ("TANGENT", tangents.as_ref().map(Vec::len)),
+        ("TEXCOORD_0", Some(tex_coords.len())),
+    ];
+    if let Some((attribute, Some(count))) = counts.into_iter().find(|&(_, count)| count.is_some_and(|count| count != positions.len())) {
+        return Err(GltfError::AttributeCountMismatch {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            attribute,
+            count,
+            expected: positions.len(),
+        });
+    }
+
+    if let Some(&index) = indices.iter().find(|&&index| index as usize >= positions.len()) {
+        return Err(GltfError::IndexOutOfRange {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            index,
+            count: positions.len(),
+        });
+    }
+
+    let triangles: Vec<[u32; 3]> = match primitive.mode() {
+        Mode::Triangles => indices.chunks_exact(3).map(|triangle| [triangle[0], triangle[1], triangle[2]]).collect(),
+        Mode::TriangleStrip => (0..indices.len().saturating_sub(2))
+            .map(|i| match i % 2 {
+                0 => [indices[i], indices[i + 1], indices[i + 2]],
+                _ => [indices[i + 1], indices[i], indices[i + 2]],
+            })
+            .collect(),
+        Mode::TriangleFan => (1..indices.len().saturating_sub(1))
+            .map(|i| [indices[0], indices[i], indices[i + 1]])
+            .collect(),
+        Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => return Ok(None),
+    };
+
+    // glTF winds front faces counter-clockwise, like OBJ, so the winding is reversed the same
+    // way as in `Mesh::from_wavefront`.
+    let mesh = match normals {
+        Some(normals) => Mesh {
+            vertices: (0..positions.len())
+                .map(|i| Vertex {
+                    tangent: tangents.as_ref().map_or([0.0; 4], |tangents| tangents[i]),
+                    ..Vertex::new(positions[i], normals[i], tex_coords[i])
+                })
+                .collect(),
+            indices: triangles.iter().flat_map(|&[a, b, c]| [a, c, b]).collect(),
+        },
+        // Without normals the spec asks for flat shading.
+        None => {
+            let mut mesh = Mesh {
+                vertices: (0..positions.len()).map(|i| Vertex::new(positions[i], [0.0; 3], tex_coords[i])).collect(),
+                indices: triangles.iter().flat_map(|&[a, b, c]| [a, c, b]).collect(),
+            };
+            mesh.compute_flat_normals();
+            mesh
+        }
+    };
+
+    Ok(Some(GltfPrimitive {
+        mesh,
+        material: primitive.material().index(),
+    }))
+}
+
+/// Approximates a metallic-roughness material: metals tint their highlights with the base
+/// color, and the roughness is mapped to the Blinn-Phong exponent with the same highlight width.
+fn convert_material(material: &gltf::Material) -> GltfMaterial {
+    let pbr = material.pbr_metallic_roughness();
+    let [r, g, b, a] = pbr.base_color_factor();
+    let diffuse = Color::new(r, g, b, a);
+    let metallic = pbr.metallic_factor();
+    let roughness = pbr.roughness_factor().max(0.01);
+    let alpha = roughness * roughness;
+    let [er, eg, eb] = material.emissive_factor();
+
+    GltfMaterial {
+        name: material.name().map(str::to_string),
+        material: Material {
+            diffuse,
+            specular: Color::new(0.04, 0.04, 0.04, 1.0).lerp(&diffuse.with_alpha(1.0), metallic),
+            emissive: Color::new(er, eg, eb, 1.0),
+            shininess: (2.0 / (alpha * alpha) - 2.0).clamp(1.0, 1024.0),
+            ..Material::new()
+        },
+        diffuse_image: pbr.base_color_texture().map(|info| info.texture().source().index()),
+        normal_image: material.normal_texture().map(|normal| normal.texture().source().index()),
+    }
+}
+
+/// Expands any decoded image to 8 bit RGBA. One and two channel images are gray and gray-alpha.
+fn convert_image(image: gltf::image::Data) -> GltfImage {
+    let (channels, channel_size) = match image.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+
+    let values: Vec<u8> = image
+        .pixels
+        .chunks_exact(channel_size)
+        .map(|bytes| match *bytes {
+            [value] => value,
+            [low, high] => (u16::from_ne_bytes([low, high]) >> 8) as u8,
+            [b0, b1, b2, b3] => (f32::from_ne_bytes([b0, b1, b2, b3]).clamp(0.0, 1.0) * 255.0).round() as u8,
+            _ => unreachable!("channels are 1, 2 or 4 bytes"),
+        })
+        .collect();
+
+    let pixels = values
+        .chunks_exact(channels)
+        .flat_map(|pixel| match *pixel {
+            [gray] => [gray, gray, gray, 255],
+            [gray, alpha] => [gray, gray, gray, alpha],
+            [r, g, b] => [r, g, b, 255],
+            [r, g, b, a] => [r, g, b, a],
+            _ => unreachable!("images have 1 to 4 channels"),
+        })
+        .collect();
+
+    GltfImage {
+        width: image.width,
+        height: image.height,
+        pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A triangle without normals and with `u16` indices.
+    const TRIANGLE: &str = r#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{"byteLength": 44, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="}],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}, {"buffer": 0, "byteOffset": 36, "byteLength": 6}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "indices": 1}]}],
+        "nodes": [{"mesh": 0}]
+    }"#;
+
+    /// A textured quad drawn as a triangle strip, under a parent given as a matrix.
+    const SCENE: &str = r#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{"byteLength": 128, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAACAPwAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AACAPwAAgD8="}],
+        "bufferViews": [
+            {"buffer": 0, "byteLength": 48},
+            {"buffer": 0, "byteOffset": 48, "byteLength": 48},
+            {"buffer": 0, "byteOffset": 96, "byteLength": 32}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3"},
+            {"bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC2"}
+        ],
+        "meshes": [{"primitives": [
+            {"attributes": {"POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2}, "mode": 5, "material": 0},
+            {"attributes": {"POSITION": 0}, "mode": 1}
+        ]}],
+        "materials": [{
+            "name": "red",
+            "pbrMetallicRoughness": {"baseColorFactor": [1, 0, 0, 1], "baseColorTexture": {"index": 0}, "metallicFactor": 0, "roughnessFactor": 0.5}
+        }],
+        "textures": [{"source": 0}],
+        "images": [{"uri": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAACCAIAAAAW4yFwAAAADUlEQVR4nGP4zwAC/wEIAAH/2ZC7NQAAAABJRU5ErkJggg=="}],
+        "nodes": [
+            {"name": "parent", "matrix": [2, 0, 0, 0, 0, 2, 0, 0, 0, 0, 2, 0, 1, 2, 3, 1], "children": [1]},
+            {"name": "child", "mesh": 0, "rotation": [0, 0.70710677, 0, 0.70710677]},
+            {"name": "hidden"}
+        ],
+        "scenes": [{"nodes": [0]}],
+        "scene": 0
+    }"#;
+
+    fn parse_str(json: &str) -> Result<GltfScene, GltfError> {
+        GltfScene::from_slice(json.as_bytes(), Path::new("."))
+    }
+
+    fn positions(mesh: &Mesh) -> Vec<[[f32; 3]; 3]> {
+        mesh.triangles().map(|triangle| triangle.map(|vertex| vertex.position)).collect()
+    }
+
+    /// Wraps a glTF document into a GLB container with one binary chunk.
+    fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+        let pad = |bytes: &mut Vec<u8>, with: u8| bytes.resize(bytes.len().div_ceil(4) * 4, with);
+        let mut json = json.as_bytes().to_vec();
+        pad(&mut json, b' ');
+        let mut bin = bin.to_vec();
+        pad(&mut bin, 0);
+
+        let mut glb = Vec::new();
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+        glb
+    }
+
+    #[test]
+    fn test_triangle_gets_flat_normals_and_reversed_winding() {
+        let scene = parse_str(TRIANGLE).unwrap();
+        assert_eq!(scene.meshes.len(), 1);
+        let primitive = &scene.meshes[0][0];
+        assert_eq!(primitive.material, None);
+        assert_eq!(positions(&primitive.mesh), vec![[[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]]);
+        assert!(primitive.mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+        // Without a scene every parentless node is shown.
+        assert_eq!(scene.roots, vec![0]);
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = parse_str(SCENE).unwrap();
+
+        // The line primitive is skipped, the strip becomes two triangles.
+        assert_eq!(scene.meshes[0].len(), 1);
+        let quad = &scene.meshes[0][0];
+        assert_eq!(quad.material, Some(0));
+        assert_eq!(quad.mesh.vertex_count(), 4);
+        assert_eq!(quad.mesh.indices, vec![0, 2, 1, 2, 3, 1]);
+        assert_eq!(quad.mesh.vertices[2].tex_coords, [0.0, 0.0]);
+        assert_eq!(quad.mesh.vertices[0].tex_coords, [0.0, 1.0]);
+
+        let red = &scene.materials[0];
+        assert_eq!(red.name.as_deref(), Some("red"));
+        assert_eq!(red.material.diffuse, Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(red.material.shininess, 30.0);
+        assert_eq!(red.diffuse_image, Some(0));
+        assert_eq!(red.normal_image, None);
+
+        assert_eq!(scene.images, vec![GltfImage { width: 1, height: 2, pixels: vec![255, 0, 0, 255, 0, 0, 255, 255] }]);
+
+        assert_eq!(scene.roots, vec![0]);
+        let parent = &scene.nodes[0];
+        assert_eq!(parent.name.as_deref(), Some("parent"));
+        assert_eq!((parent.translation, parent.scale), ([1.0, 2.0, 3.0], [2.0; 3]));
+        assert_eq!(parent.children, vec![1]);
+        assert_eq!(scene.nodes[1].mesh, Some(0));
+        assert_eq!(scene.nodes[1].rotation, [0.0, 0.70710677, 0.0, 0.70710677]);
+    }
+
+    #[test]
+    fn test_glb() {
+        let json = TRIANGLE.replace(
+            r#""byteLength": 44, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=""#,
+            r#""byteLength": 44"#,
+        );
+        let mut bin: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0].iter().flat_map(|value| value.to_le_bytes()).collect();
+        bin.extend([0u16, 1, 2].iter().flat_map(|index| index.to_le_bytes()));
+
+        let from_glb = GltfScene::from_slice(&glb(&json, &bin), Path::new(".")).unwrap();
+        assert_eq!(from_glb, parse_str(TRIANGLE).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let out_of_range = TRIANGLE.replace(r#""count": 3, "type": "VEC3""#, r#""count": 2, "type": "VEC3""#);
+        match parse_str(&out_of_range) {
+            Err(GltfError::IndexOutOfRange { mesh: 0, primitive: 0, index: 2, count: 2 }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let short_normals = SCENE.replace(
+            r#"{"bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3"}"#,
+            r#"{"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3"}"#,
+        );
+        match parse_str(&short_normals) {
+            Err(GltfError::AttributeCountMismatch { mesh: 0, primitive: 0, attribute: "NORMAL", count: 3, expected: 4 }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        let short_tex_coords = SCENE.replace(
+            r#"{"bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC2"}"#,
+            r#"{"bufferView": 2, "componentType": 5126, "count": 2, "type": "VEC2"}"#,
+        );
+        assert!(matches!(parse_str(&short_tex_coords), Err(GltfError::AttributeCountMismatch { attribute: "TEXCOORD_0", .. })));
+
+        assert!(matches!(parse_str("{ not json"), Err(GltfError::Gltf(_))));
+        assert!(matches!(GltfScene::parse(Path::new("does/not/exist.gltf")), Err(GltfError::Gltf(_))));
+    }
+}
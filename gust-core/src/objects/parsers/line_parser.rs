@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+/// A whitespace separated token and its 1-based column.
+type Token<'a> = (usize, &'a str);
+
+/// Tracks the position in the current line for error reporting.
+pub(crate) struct LineParser<'a> {
+    line: &'a str,
+    line_number: usize,
+    end_column: usize,
+    tokens: std::vec::IntoIter<Token<'a>>,
+}
+
+impl<'a> LineParser<'a> {
+    pub(crate) fn new(line_number: usize, line: &'a str) -> Self {
+        let tokens: Vec<Token> = line
+            .split_whitespace()
+            .map(|token| (token.as_ptr() as usize - line.as_ptr() as usize + 1, token))
+            .collect();
+        LineParser {
+            line,
+            line_number,
+            end_column: line.trim_end().len() + 1,
+            tokens: tokens.into_iter(),
+        }
+    }
+
+    pub(crate) fn error(&self, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError::Parse { line: self.line_number, column, kind }
+    }
+
+    pub(crate) fn next(&mut self) -> Option<Token<'a>> {
+        self.tokens.next()
+    }
+
+    /// The next token, which has to exist.
+    pub(crate) fn token(&mut self, what: &'static str) -> Result<Token<'a>, ParseError> {
+        self.next().ok_or_else(|| self.error(self.end_column, ParseErrorKind::MissingValue(what)))
+    }
+
+    pub(crate) fn float(&mut self, what: &'static str) -> Result<f32, ParseError> {
+        self.number(what)
+    }
+
+    pub(crate) fn integer(&mut self, what: &'static str) -> Result<u32, ParseError> {
+        self.number(what)
+    }
+
+    pub(crate) fn number<T: FromStr>(&mut self, what: &'static str) -> Result<T, ParseError> {
+        match self.next() {
+            Some((column, text)) => text
+                .parse()
+                .map_err(|_| self.error(column, ParseErrorKind::InvalidNumber(text.to_string()))),
+            None => Err(self.error(self.end_column, ParseErrorKind::MissingValue(what))),
+        }
+    }
+
+    /// Consumes the next token, which has to be `keyword`.
+    pub(crate) fn expect(&mut self, keyword: &'static str) -> Result<(), ParseError> {
+        match self.next() {
+            Some((_, text)) if text == keyword => Ok(()),
+            Some((column, text)) => Err(self.error(
+                column,
+                ParseErrorKind::UnexpectedToken { expected: keyword, found: text.to_string() },
+            )),
+            None => Err(self.error(self.end_column, ParseErrorKind::MissingValue(keyword))),
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<&'a str> {
+        self.tokens.as_slice().first().map(|(_, text)| *text)
+    }
+
+    /// The rest of the line as a single value, for names that may contain spaces.
+    pub(crate) fn rest(&mut self, what: &'static str) -> Result<&'a str, ParseError> {
+        match self.next() {
+            Some((column, _)) => {
+                self.tokens.by_ref().for_each(drop);
+                Ok(self.line[column - 1..].trim_end())
+            }
+            None => Err(self.error(self.end_column, ParseErrorKind::MissingValue(what))),
+        }
+    }
+
+    pub(crate) fn optional_float(&mut self, default: f32) -> Result<f32, ParseError> {
+        match self.peek() {
+            Some(_) => self.float("value"),
+            None => Ok(default),
+        }
+    }
+}
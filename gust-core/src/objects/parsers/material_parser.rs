@@ -0,0 +1,195 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::objects::intermediaries::wavefront_material::{TextureMap, WavefrontMaterial, WavefrontMaterialLibrary};
+use crate::objects::parsers::line_parser::LineParser;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+impl WavefrontMaterialLibrary {
+    pub fn parse(file_path: &Path) -> Result<WavefrontMaterialLibrary, ParseError> {
+        WavefrontMaterialLibrary::from_reader(File::open(file_path)?)
+    }
+
+    pub fn parse_str(text: &str) -> Result<WavefrontMaterialLibrary, ParseError> {
+        WavefrontMaterialLibrary::from_reader(text.as_bytes())
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> Result<WavefrontMaterialLibrary, ParseError> {
+        let mut library = WavefrontMaterialLibrary::default();
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line = line.split('#').next().unwrap_or("");
+            let mut parser = LineParser::new(index + 1, line);
+
+            let Some((column, statement)) = parser.next() else {
+                continue;
+            };
+            if statement == "newmtl" {
+                let name = parser.rest("material name")?;
+                library.materials.push(WavefrontMaterial::new(name));
+                continue;
+            }
+
+            let Some(material) = library.materials.last_mut() else {
+                return Err(parser.error(column, ParseErrorKind::NoCurrentMaterial(statement.to_string())));
+            };
+            match statement {
+                "Ka" => material.ambient = parse_color(&mut parser)?,
+                "Kd" => material.diffuse = parse_color(&mut parser)?,
+                "Ks" => material.specular = parse_color(&mut parser)?,
+                "Ke" => material.emissive = parse_color(&mut parser)?,
+                "Ns" => material.shininess = parser.float("specular exponent")?,
+                "Ni" => material.optical_density = parser.float("optical density")?,
+                "d" => material.dissolve = parser.float("dissolve")?,
+                "Tr" => material.dissolve = 1.0 - parser.float("transparency")?,
+                "illum" => material.illumination_model = parser.integer("illumination model")?,
+                "map_Ka" => material.ambient_map = Some(parse_texture_map(&mut parser)?),
+                "map_Kd" => material.diffuse_map = Some(parse_texture_map(&mut parser)?),
+                "map_Ks" => material.specular_map = Some(parse_texture_map(&mut parser)?),
+                "map_Ke" => material.emissive_map = Some(parse_texture_map(&mut parser)?),
+                "map_Ns" => material.shininess_map = Some(parse_texture_map(&mut parser)?),
+                "map_d" => material.dissolve_map = Some(parse_texture_map(&mut parser)?),
+                "map_Bump" | "map_bump" | "bump" => material.bump_map = Some(parse_texture_map(&mut parser)?),
+                "norm" => material.normal_map = Some(parse_texture_map(&mut parser)?),
+                "disp" => material.displacement_map = Some(parse_texture_map(&mut parser)?),
+                _ => {}
+            }
+        }
+
+        Ok(library)
+    }
+}
+
+/// `r g b`, where a single value stands for a gray.
+fn parse_color(parser: &mut LineParser) -> Result<[f32; 3], ParseError> {
+    let r = parser.float("red component")?;
+    let g = parser.optional_float(r)?;
+    let b = parser.optional_float(r)?;
+    Ok([r, g, b])
+}
+
+/// Parses `[options] path`, e.g. `map_Bump -bm 0.5 textures/bump.png`.
+fn parse_texture_map(parser: &mut LineParser) -> Result<TextureMap, ParseError> {
+    let mut offset = [0.0; 3];
+    let mut scale = [1.0; 3];
+    let mut clamp = false;
+    let mut bump_multiplier = 1.0;
+
+    while let Some(option) = parser.peek().filter(|token| token.starts_with('-')) {
+        parser.next();
+        match option {
+            "-o" => offset = parse_vector(parser, 0.0)?,
+            "-s" => scale = parse_vector(parser, 1.0)?,
+            "-t" => {
+                parse_vector(parser, 0.0)?;
+            }
+            "-mm" => {
+                parser.float("base value")?;
+                parser.float("gain value")?;
+            }
+            "-bm" => bump_multiplier = parser.float("bump multiplier")?,
+            "-clamp" => clamp = parser.next().map(|(_, value)| value) == Some("on"),
+            // -blendu, -blendv, -boost, -cc, -imfchan, -texres and -type all take one argument.
+            _ => {
+                parser.next();
+            }
+        }
+    }
+
+    Ok(TextureMap {
+        path: parser.rest("texture path")?.to_string(),
+        offset,
+        scale,
+        clamp,
+        bump_multiplier,
+    })
+}
+
+/// One to three numbers; missing components keep `default`.
+fn parse_vector(parser: &mut LineParser, default: f32) -> Result<[f32; 3], ParseError> {
+    let mut vector = [default; 3];
+    vector[0] = parser.float("value")?;
+    for component in vector.iter_mut().skip(1) {
+        match parser.peek() {
+            Some(token) if token.parse::<f32>().is_ok() => *component = parser.float("value")?,
+            _ => break,
+        }
+    }
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_materials() {
+        let text = "\
+# comment
+newmtl Shiny Red
+Ka 0.1 0.1 0.1
+Kd 0.8 0.0 0.0
+Ks 1.0
+Ns 96.5
+d 0.5
+illum 2
+map_Kd textures/red paint.png
+map_Bump -bm 0.25 -o 0.5 0.5 -clamp on bump.png
+
+newmtl plain
+Tr 0.25
+";
+        let library = WavefrontMaterialLibrary::parse_str(text).unwrap();
+        assert_eq!(library.materials.len(), 2);
+
+        let red = library.get("Shiny Red").unwrap();
+        assert_eq!(red.ambient, [0.1, 0.1, 0.1]);
+        assert_eq!(red.diffuse, [0.8, 0.0, 0.0]);
+        assert_eq!(red.specular, [1.0, 1.0, 1.0]);
+        assert_eq!(red.shininess, 96.5);
+        assert_eq!(red.dissolve, 0.5);
+        assert_eq!(red.illumination_model, 2);
+        assert_eq!(red.diffuse_map, Some(TextureMap::new("textures/red paint.png")));
+
+        let bump = red.bump_map.as_ref().unwrap();
+        assert_eq!(bump.path, "bump.png");
+        assert_eq!(bump.bump_multiplier, 0.25);
+        assert_eq!(bump.offset, [0.5, 0.5, 0.0]);
+        assert!(bump.clamp);
+
+        let plain = library.get("plain").unwrap();
+        assert_eq!(plain.dissolve, 0.75);
+        assert_eq!(plain.diffuse, [0.8; 3]);
+        assert!(library.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        match WavefrontMaterialLibrary::parse_str("Kd 1 1 1\n") {
+            Err(ParseError::Parse { line: 1, column: 1, kind }) => {
+                assert_eq!(kind, ParseErrorKind::NoCurrentMaterial("Kd".to_string()))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        match WavefrontMaterialLibrary::parse_str("newmtl a\nNs high\n") {
+            Err(ParseError::Parse { line: 2, column: 4, kind }) => {
+                assert_eq!(kind, ParseErrorKind::InvalidNumber("high".to_string()))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parses_bundled_assets() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/objects/floor.mtl");
+        let library = WavefrontMaterialLibrary::parse(&path).unwrap();
+        let material = library.get("Material.001").unwrap();
+        assert_eq!(material.shininess, 250.0);
+        assert_eq!(material.diffuse, [0.8; 3]);
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/objects/monkey.mtl");
+        assert!(WavefrontMaterialLibrary::parse(&path).unwrap().materials.is_empty());
+    }
+}
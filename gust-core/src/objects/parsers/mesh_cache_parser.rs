@@ -0,0 +1,175 @@
+use std::io::Read;
+use std::path::Path;
+
+use gust_math::vectors::vect3::Vect3;
+
+use crate::objects::intermediaries::mesh_cache::{MeshCache, SubMesh, MAGIC, VERSION};
+use crate::objects::parsers::binary_reader::BinaryReader;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+use crate::primitives::bounds::{Aabb, BoundingSphere, MeshBounds};
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// Strings are stored with a u32 length, `u32::MAX` marks a missing one.
+fn optional_string(reader: &mut BinaryReader, expected: &'static str) -> Result<Option<String>, ParseError> {
+    match reader.u32(expected)? {
+        u32::MAX => Ok(None),
+        length => reader.utf8(length as usize, expected).map(Some),
+    }
+}
+
+impl MeshCache {
+    pub fn parse(file_path: &Path) -> Result<MeshCache, ParseError> {
+        MeshCache::from_bytes(&std::fs::read(file_path)?)
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<MeshCache, ParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        MeshCache::from_bytes(&bytes)
+    }
+
+    /// Checks the magic, version and checksum before reading anything else, so a stale or
+    /// damaged cache fails without producing a broken mesh.
+    pub fn from_bytes(bytes: &[u8]) -> Result<MeshCache, ParseError> {
+        let mut reader = BinaryReader::new(bytes);
+        if reader.take(4, "magic")? != MAGIC {
+            return Err(ParseError::Binary { offset: 0, kind: ParseErrorKind::InvalidMagic });
+        }
+        let version = reader.u32("version")?;
+        if version != VERSION {
+            return Err(ParseError::Binary { offset: 4, kind: ParseErrorKind::UnsupportedVersion(version) });
+        }
+
+        let body_end = bytes.len().checked_sub(4).filter(|&end| end >= reader.offset).ok_or(ParseError::Binary {
+            offset: bytes.len(),
+            kind: ParseErrorKind::UnexpectedEnd("checksum"),
+        })?;
+        let expected = u32::from_le_bytes(bytes[body_end..].try_into().unwrap());
+        let found = crc32fast::hash(&bytes[..body_end]);
+        if expected != found {
+            return Err(ParseError::Binary { offset: body_end, kind: ParseErrorKind::ChecksumMismatch { expected, found } });
+        }
+        reader.bytes = &bytes[..body_end];
+
+        let vertex_count = reader.u32("vertex count")? as usize;
+        let index_count = reader.u32("index count")? as usize;
+        let sub_mesh_count = reader.u32("sub-mesh count")? as usize;
+
+        let [min_x, min_y, min_z, max_x, max_y, max_z, center_x, center_y, center_z, radius] = reader.f32s("bounds")?;
+        let bounds = MeshBounds {
+            aabb: Aabb::new(Vect3::new(min_x, min_y, min_z), Vect3::new(max_x, max_y, max_z)),
+            sphere: BoundingSphere::new(Vect3::new(center_x, center_y, center_z), radius),
+        };
+
+        // The counts come from the file, so they only limit the preallocation by what is there.
+        let mut mesh = Mesh::new();
+        mesh.vertices.reserve(vertex_count.min(bytes.len() / 48));
+        for _ in 0..vertex_count {
+            let [px, py, pz, nx, ny, nz, u, v, tx, ty, tz, tw] = reader.f32s("vertex")?;
+            mesh.vertices.push(Vertex {
+                position: [px, py, pz],
+                normal: [nx, ny, nz],
+                tex_coords: [u, v],
+                tangent: [tx, ty, tz, tw],
+            });
+        }
+
+        mesh.indices.reserve(index_count.min(bytes.len() / 4));
+        for _ in 0..index_count {
+            let index = reader.u32("index")?;
+            if index as usize >= vertex_count {
+                return Err(reader.error(ParseErrorKind::IndexOutOfRange { element: "vertex", index: index as i64, count: vertex_count }));
+            }
+            mesh.indices.push(index);
+        }
+
+        let mut sub_meshes = Vec::with_capacity(sub_mesh_count.min(bytes.len() / 20));
+        for _ in 0..sub_mesh_count {
+            let object = optional_string(&mut reader, "sub-mesh object")?;
+            let group_count = reader.u32("sub-mesh group count")?;
+            let groups = (0..group_count)
+                .map(|_| optional_string(&mut reader, "sub-mesh group")?.ok_or_else(|| reader.error(ParseErrorKind::MissingValue("group name"))))
+                .collect::<Result<_, _>>()?;
+            let material = optional_string(&mut reader, "sub-mesh material")?;
+            let (start, end) = (reader.u32("sub-mesh start")?, reader.u32("sub-mesh end")?);
+            if start > end || end as usize > index_count {
+                return Err(reader.error(ParseErrorKind::IndexOutOfRange { element: "index", index: end as i64, count: index_count }));
+            }
+            sub_meshes.push(SubMesh { object, groups, material, indices: start..end });
+        }
+
+        if reader.offset != body_end {
+            return Err(reader.error(ParseErrorKind::UnexpectedToken { expected: "checksum", found: format!("{} more bytes", body_end - reader.offset) }));
+        }
+
+        Ok(MeshCache { mesh, bounds, sub_meshes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::intermediaries::wavefront_object::WavefrontObject;
+
+    use super::*;
+
+    const TWO_OBJECTS: &str = "\
+o first
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+vt 0.5 0.5
+usemtl red
+f 1/1 2/1 3/1
+o second
+g left right
+f 1 3 4
+";
+
+    #[test]
+    fn test_round_trip() {
+        let cache = MeshCache::from_wavefront(&WavefrontObject::parse_str(TWO_OBJECTS).unwrap());
+        assert_eq!(cache.mesh.triangle_count(), 2);
+        assert_eq!(cache.sub_meshes.len(), 2);
+        assert_eq!(cache.sub_meshes[0].material.as_deref(), Some("red"));
+        assert_eq!(cache.sub_meshes[1].groups, vec!["left".to_string(), "right".to_string()]);
+        assert_eq!(cache.sub_meshes[1].indices, 3..6);
+
+        let bytes = cache.to_bytes();
+        assert_eq!(MeshCache::from_bytes(&bytes).unwrap(), cache);
+        assert_eq!(MeshCache::from_reader(bytes.as_slice()).unwrap(), cache);
+
+        let cube = MeshCache::from_mesh(Mesh::cube(2.0));
+        assert_eq!(cube.bounds, Mesh::cube(2.0).bounds());
+        assert_eq!(MeshCache::from_bytes(&cube.to_bytes()).unwrap(), cube);
+    }
+
+    #[test]
+    fn test_errors() {
+        let bytes = MeshCache::from_mesh(Mesh::cube(1.0)).to_bytes();
+
+        let mut damaged = bytes.clone();
+        damaged[100] ^= 1;
+        match MeshCache::from_bytes(&damaged) {
+            Err(ParseError::Binary { kind: ParseErrorKind::ChecksumMismatch { .. }, .. }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut old = bytes.clone();
+        old[4..8].copy_from_slice(&0u32.to_le_bytes());
+        match MeshCache::from_bytes(&old) {
+            Err(ParseError::Binary { offset: 4, kind }) => assert_eq!(kind, ParseErrorKind::UnsupportedVersion(0)),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        match MeshCache::from_bytes(b"solid cube") {
+            Err(ParseError::Binary { offset: 0, kind }) => assert_eq!(kind, ParseErrorKind::InvalidMagic),
+            other => panic!("unexpected result {:?}", other),
+        }
+        match MeshCache::from_bytes(&bytes[..6]) {
+            Err(ParseError::Binary { kind, .. }) => assert_eq!(kind, ParseErrorKind::UnexpectedEnd("version")),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -1 +1,10 @@
-pub mod wavefront_parser;
\ No newline at end of file
+pub mod wavefront_parser;
+pub mod material_parser;
+pub mod gltf_parser;
+pub mod stl_parser;
+pub mod ply_parser;
+pub mod mesh_cache_parser;
+pub mod aseprite_parser;
+pub mod parse_error;
+pub(crate) mod line_parser;
+pub(crate) mod binary_reader;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// Errors from parsing mesh, material and sprite files: OBJ, MTL, STL, PLY, mesh caches and
+/// Aseprite files.
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    /// A malformed statement in a text file, at a 1-based line and column.
+    Parse { line: usize, column: usize, kind: ParseErrorKind },
+    /// Malformed binary data, at a byte offset from the start of the file.
+    Binary { offset: usize, kind: ParseErrorKind },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    MissingValue(&'static str),
+    InvalidNumber(String),
+    InvalidFaceVertex(String),
+    IndexOutOfRange { element: &'static str, index: i64, count: usize },
+    TooFewFaceVertices(usize),
+    NoCurrentMaterial(String),
+    UnexpectedToken { expected: &'static str, found: String },
+    UnexpectedEnd(&'static str),
+    MissingProperty { element: &'static str, property: &'static str },
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    ChecksumMismatch { expected: u32, found: u32 },
+    InvalidString,
+    UnsupportedColorDepth(u16),
+    /// Compressed data that couldn't be inflated.
+    InvalidCompressedData,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "failed to read file: {}", error),
+            ParseError::Parse { line, column, kind } => write!(f, "{}:{}: {}", line, column, kind),
+            ParseError::Binary { offset, kind } => write!(f, "byte {}: {}", offset, kind),
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::MissingValue(what) => write!(f, "missing {}", what),
+            ParseErrorKind::InvalidNumber(text) => write!(f, "invalid number '{}'", text),
+            ParseErrorKind::InvalidFaceVertex(text) => write!(f, "invalid face vertex '{}'", text),
+            ParseErrorKind::IndexOutOfRange { element, index, count } => {
+                write!(f, "{} index {} out of range, {} defined so far", element, index, count)
+            }
+            ParseErrorKind::TooFewFaceVertices(count) => write!(f, "face has {} vertices, needs at least 3", count),
+            ParseErrorKind::NoCurrentMaterial(statement) => write!(f, "'{}' before any newmtl statement", statement),
+            ParseErrorKind::UnexpectedToken { expected, found } => write!(f, "expected {}, found '{}'", expected, found),
+            ParseErrorKind::UnexpectedEnd(expected) => write!(f, "unexpected end of file, expected {}", expected),
+            ParseErrorKind::MissingProperty { element, property } => {
+                write!(f, "element '{}' has no property '{}'", element, property)
+            }
+            ParseErrorKind::InvalidMagic => write!(f, "file signature doesn't match the format"),
+            ParseErrorKind::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+            ParseErrorKind::ChecksumMismatch { expected, found } => {
+                write!(f, "checksum {:08x} doesn't match the data, expected {:08x}", found, expected)
+            }
+            ParseErrorKind::InvalidString => write!(f, "string is not valid UTF-8"),
+            ParseErrorKind::UnsupportedColorDepth(depth) => write!(f, "unsupported color depth of {} bits", depth),
+            ParseErrorKind::InvalidCompressedData => write!(f, "compressed data is damaged"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(error) => Some(error),
+            ParseError::Parse { .. } | ParseError::Binary { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(error: std::io::Error) -> Self {
+        ParseError::Io(error)
+    }
+}
@@ -0,0 +1,460 @@
+use std::io::Read;
+use std::path::Path;
+
+use crate::objects::intermediaries::ply_object::{PlyFormat, PlyObject};
+use crate::objects::parsers::line_parser::LineParser;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+/// The number types a property can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Scalar> {
+        match name {
+            "char" | "int8" => Some(Scalar::Int8),
+            "uchar" | "uint8" => Some(Scalar::UInt8),
+            "short" | "int16" => Some(Scalar::Int16),
+            "ushort" | "uint16" => Some(Scalar::UInt16),
+            "int" | "int32" => Some(Scalar::Int32),
+            "uint" | "uint32" => Some(Scalar::UInt32),
+            "float" | "float32" => Some(Scalar::Float32),
+            "double" | "float64" => Some(Scalar::Float64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::Int8 | Scalar::UInt8 => 1,
+            Scalar::Int16 | Scalar::UInt16 => 2,
+            Scalar::Int32 | Scalar::UInt32 | Scalar::Float32 => 4,
+            Scalar::Float64 => 8,
+        }
+    }
+}
+
+struct Property {
+    name: String,
+    /// The type of the length of a list property, `None` for single values.
+    list_count: Option<Scalar>,
+    scalar: Scalar,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+    /// The header line declaring the element, for errors.
+    line: usize,
+}
+
+impl Element {
+    fn property(&self, names: &[&str]) -> Option<usize> {
+        self.properties.iter().position(|property| names.contains(&property.name.as_str()))
+    }
+
+    fn missing(&self, element: &'static str, property: &'static str) -> ParseError {
+        ParseError::Parse {
+            line: self.line,
+            column: 1,
+            kind: ParseErrorKind::MissingProperty { element, property },
+        }
+    }
+}
+
+/// Reads the values of the body one after another, from text lines or from binary data.
+enum BodyReader<'a> {
+    Ascii {
+        lines: std::iter::Enumerate<std::str::Lines<'a>>,
+        first_line: usize,
+        row: Option<LineParser<'a>>,
+    },
+    Binary {
+        bytes: &'a [u8],
+        offset: usize,
+        big_endian: bool,
+    },
+}
+
+impl<'a> BodyReader<'a> {
+    /// Moves to the next row. ASCII files have one row per line.
+    fn start_row(&mut self) -> Result<(), ParseError> {
+        if let BodyReader::Ascii { lines, first_line, row } = self {
+            let (index, line) = lines
+                .by_ref()
+                .find(|(_, line)| !line.trim().is_empty())
+                .ok_or(ParseError::Parse { line: *first_line, column: 1, kind: ParseErrorKind::UnexpectedEnd("element row") })?;
+            *row = Some(LineParser::new(*first_line + index, line));
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, scalar: Scalar) -> Result<f64, ParseError> {
+        match self {
+            BodyReader::Ascii { row, .. } => row.as_mut().expect("start_row is called first").number("property value"),
+            BodyReader::Binary { bytes, offset, big_endian } => {
+                let data = bytes.get(*offset..*offset + scalar.size()).ok_or(ParseError::Binary {
+                    offset: bytes.len(),
+                    kind: ParseErrorKind::UnexpectedEnd("property value"),
+                })?;
+                *offset += scalar.size();
+
+                let mut buffer = [0; 8];
+                buffer[..data.len()].copy_from_slice(data);
+                if *big_endian {
+                    buffer[..data.len()].reverse();
+                }
+                let [b0, b1, b2, b3, ..] = buffer;
+                Ok(match scalar {
+                    Scalar::Int8 => b0 as i8 as f64,
+                    Scalar::UInt8 => b0 as f64,
+                    Scalar::Int16 => i16::from_le_bytes([b0, b1]) as f64,
+                    Scalar::UInt16 => u16::from_le_bytes([b0, b1]) as f64,
+                    Scalar::Int32 => i32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::UInt32 => u32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::Float32 => f32::from_le_bytes([b0, b1, b2, b3]) as f64,
+                    Scalar::Float64 => f64::from_le_bytes(buffer),
+                })
+            }
+        }
+    }
+
+    /// An error at the start of the current row, or at the current byte.
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        match self {
+            BodyReader::Ascii { row: Some(row), .. } => row.error(1, kind),
+            BodyReader::Ascii { first_line, .. } => ParseError::Parse { line: *first_line, column: 1, kind },
+            BodyReader::Binary { offset, .. } => ParseError::Binary { offset: *offset, kind },
+        }
+    }
+}
+
+impl PlyObject {
+    pub fn parse(file_path: &Path) -> Result<PlyObject, ParseError> {
+        PlyObject::from_bytes(&std::fs::read(file_path)?)
+    }
+
+    pub fn parse_str(text: &str) -> Result<PlyObject, ParseError> {
+        PlyObject::from_bytes(text.as_bytes())
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<PlyObject, ParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        PlyObject::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<PlyObject, ParseError> {
+        let mut object = PlyObject::default();
+
+        // The header is always text and ends with the line holding `end_header`.
+        let header_end = bytes
+            .windows(b"end_header".len())
+            .position(|window| window == b"end_header")
+            .and_then(|start| bytes[start..].iter().position(|&byte| byte == b'\n').map(|end| start + end + 1))
+            .unwrap_or(bytes.len());
+        let header = String::from_utf8_lossy(&bytes[..header_end]);
+        let header_lines = header.lines().count();
+
+        let mut format = None;
+        let mut elements: Vec<Element> = Vec::new();
+        let mut ended = false;
+        for (index, line) in header.lines().enumerate() {
+            let mut parser = LineParser::new(index + 1, line);
+            if index == 0 {
+                parser.expect("ply")?;
+                continue;
+            }
+
+            match parser.next() {
+                Some((_, "format")) => {
+                    format = Some(match parser.token("format")? {
+                        (_, "ascii") => PlyFormat::Ascii,
+                        (_, "binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                        (_, "binary_big_endian") => PlyFormat::BinaryBigEndian,
+                        (column, found) => {
+                            return Err(parser.error(
+                                column,
+                                ParseErrorKind::UnexpectedToken { expected: "format", found: found.to_string() },
+                            ))
+                        }
+                    });
+                }
+                Some((_, "comment")) => object.comments.push(parser.rest("comment").unwrap_or("").to_string()),
+                Some((_, "element")) => {
+                    let (_, name) = parser.token("element name")?;
+                    elements.push(Element {
+                        name: name.to_string(),
+                        count: parser.number("element count")?,
+                        properties: Vec::new(),
+                        line: index + 1,
+                    });
+                }
+                Some((column, "property")) => {
+                    let Some(element) = elements.last_mut() else {
+                        return Err(parser.error(
+                            column,
+                            ParseErrorKind::UnexpectedToken { expected: "element", found: "property".to_string() },
+                        ));
+                    };
+                    let scalar = |parser: &mut LineParser| {
+                        let (column, name) = parser.token("property type")?;
+                        Scalar::from_name(name).ok_or_else(|| {
+                            parser.error(
+                                column,
+                                ParseErrorKind::UnexpectedToken { expected: "property type", found: name.to_string() },
+                            )
+                        })
+                    };
+                    let list_count = match parser.peek() {
+                        Some("list") => {
+                            parser.next();
+                            Some(scalar(&mut parser)?)
+                        }
+                        _ => None,
+                    };
+                    let scalar = scalar(&mut parser)?;
+                    let name = parser.rest("property name")?.to_string();
+                    element.properties.push(Property { name, list_count, scalar });
+                }
+                Some((_, "end_header")) => {
+                    ended = true;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if !ended {
+            return Err(ParseError::Parse {
+                line: header_lines + 1,
+                column: 1,
+                kind: ParseErrorKind::UnexpectedEnd("end_header"),
+            });
+        }
+        let format = format.ok_or(ParseError::Parse {
+            line: header_lines,
+            column: 1,
+            kind: ParseErrorKind::MissingValue("format"),
+        })?;
+
+        // Invalid text in an ASCII body turns into invalid numbers
+        let text = String::from_utf8_lossy(&bytes[header_end..]);
+        let mut reader = match format {
+            PlyFormat::Ascii => BodyReader::Ascii {
+                lines: text.lines().enumerate(),
+                first_line: header_lines + 1,
+                row: None,
+            },
+            PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => BodyReader::Binary {
+                bytes,
+                offset: header_end,
+                big_endian: format == PlyFormat::BinaryBigEndian,
+            },
+        };
+
+        let vertex_count = elements.iter().find(|element| element.name == "vertex").map_or(0, |element| element.count);
+        for element in &elements {
+            object.read_element(element, &mut reader, vertex_count)?;
+        }
+
+        Ok(object)
+    }
+
+    /// Reads every row of an element, keeping the properties we know about.
+    fn read_element(&mut self, element: &Element, reader: &mut BodyReader, vertex_count: usize) -> Result<(), ParseError> {
+        let mut positions = None;
+        let mut normals = None;
+        let mut tex_coords = None;
+        let mut indices = None;
+        match element.name.as_str() {
+            "vertex" => {
+                let required = |name| element.property(&[name]).ok_or_else(|| element.missing("vertex", name));
+                positions = Some([required("x")?, required("y")?, required("z")?]);
+                normals = match ["nx", "ny", "nz"].map(|name| element.property(&[name])) {
+                    [Some(x), Some(y), Some(z)] => Some([x, y, z]),
+                    _ => None,
+                };
+                let u = element.property(&["u", "s", "texture_u", "texture_s"]);
+                let v = element.property(&["v", "t", "texture_v", "texture_t"]);
+                tex_coords = u.zip(v);
+            }
+            "face" => {
+                indices = Some(
+                    element
+                        .property(&["vertex_indices", "vertex_index"])
+                        .ok_or_else(|| element.missing("face", "vertex_indices"))?,
+                );
+            }
+            _ => {}
+        }
+
+        for _ in 0..element.count {
+            reader.start_row()?;
+            let mut values = Vec::with_capacity(element.properties.len());
+            let mut list = Vec::new();
+            for (index, property) in element.properties.iter().enumerate() {
+                match property.list_count {
+                    None => values.push(reader.read(property.scalar)?),
+                    Some(count) => {
+                        let count = reader.read(count)?.max(0.0) as usize;
+                        let items = (0..count).map(|_| reader.read(property.scalar)).collect::<Result<Vec<_>, _>>()?;
+                        values.push(count as f64);
+                        if indices == Some(index) {
+                            list = items;
+                        }
+                    }
+                }
+            }
+
+            let value = |index: usize| values[index] as f32;
+            if let Some([x, y, z]) = positions {
+                self.vertices.push([value(x), value(y), value(z)]);
+            }
+            if let Some([x, y, z]) = normals {
+                self.normals.push([value(x), value(y), value(z)]);
+            }
+            if let Some((u, v)) = tex_coords {
+                self.tex_coords.push([value(u), value(v)]);
+            }
+            if indices.is_some() {
+                if list.len() < 3 {
+                    return Err(reader.error(ParseErrorKind::TooFewFaceVertices(list.len())));
+                }
+                if let Some(&index) = list.iter().find(|&&index| index < 0.0 || index >= vertex_count as f64) {
+                    return Err(reader.error(ParseErrorKind::IndexOutOfRange {
+                        element: "vertex",
+                        index: index as i64,
+                        count: vertex_count,
+                    }));
+                }
+                self.faces.push(list.iter().map(|&index| index as u32).collect());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: &str = "\
+ply
+format ascii 1.0
+comment made by hand
+element vertex 4
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+property float s
+property float t
+property uchar red
+element face 1
+property list uchar int vertex_indices
+property uchar flags
+end_header
+0 0 0 0 0 1 0 0 255
+1 0 0 0 0 1 1 0 255
+
+0 1 0 0 0 1 0 1 255
+1 1 0 0 0 1 1 1 255
+4 0 1 3 2 7
+";
+
+    fn binary_quad(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+        let mut bytes = format!(
+            "ply\nformat {} 1.0\nelement vertex 4\nproperty double x\nproperty float y\nproperty float z\nelement face 1\nproperty list uchar uint vertex_indices\nend_header\n",
+            format
+        )
+        .into_bytes();
+        for [x, y] in [[0.0f32, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]] {
+            let x = x as f64;
+            match big_endian {
+                true => bytes.extend([x.to_be_bytes().as_slice(), &y.to_be_bytes(), &0f32.to_be_bytes()].concat()),
+                false => bytes.extend([x.to_le_bytes().as_slice(), &y.to_le_bytes(), &0f32.to_le_bytes()].concat()),
+            }
+        }
+        bytes.push(4);
+        for index in [0u32, 1, 3, 2] {
+            bytes.extend(match big_endian {
+                true => index.to_be_bytes(),
+                false => index.to_le_bytes(),
+            });
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_ascii() {
+        let object = PlyObject::parse_str(QUAD).unwrap();
+        assert_eq!(object.comments, vec!["made by hand".to_string()]);
+        assert_eq!(object.vertices, vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]]);
+        assert_eq!(object.normals, vec![[0.0, 0.0, 1.0]; 4]);
+        assert_eq!(object.tex_coords[3], [1.0, 1.0]);
+        assert_eq!(object.faces, vec![vec![0, 1, 3, 2]]);
+    }
+
+    #[test]
+    fn test_binary() {
+        for big_endian in [false, true] {
+            let object = PlyObject::from_bytes(&binary_quad(big_endian)).unwrap();
+            assert_eq!(object.vertices[3], [1.0, 1.0, 0.0]);
+            assert!(object.normals.is_empty() && object.tex_coords.is_empty());
+            assert_eq!(object.faces, vec![vec![0, 1, 3, 2]]);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |text: &str| match PlyObject::parse_str(text) {
+            Err(ParseError::Parse { line, column, kind }) => (line, column, kind),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(
+            error("obj\n"),
+            (1, 1, ParseErrorKind::UnexpectedToken { expected: "ply", found: "obj".to_string() })
+        );
+        assert_eq!(error("ply\nformat ascii 1.0\n").2, ParseErrorKind::UnexpectedEnd("end_header"));
+        assert_eq!(
+            error("ply\nformat ascii 1.0\nelement vertex 1\nproperty float y\nend_header\n1\n"),
+            (3, 1, ParseErrorKind::MissingProperty { element: "vertex", property: "x" })
+        );
+        assert_eq!(
+            error("ply\nformat ascii 1.0\nelement vertex 1\nproperty half x\nend_header\n"),
+            (4, 10, ParseErrorKind::UnexpectedToken { expected: "property type", found: "half".to_string() })
+        );
+        assert_eq!(error(&QUAD.replace("4 0 1 3 2 7", "3 0 1 4 7")).0, 23);
+        assert_eq!(error(&QUAD.replace("4 0 1 3 2 7", "3 0 1 4 7")).2, ParseErrorKind::IndexOutOfRange {
+            element: "vertex",
+            index: 4,
+            count: 4
+        });
+        assert_eq!(error(&QUAD.replace("\n4 0 1 3 2 7\n", "\n")).2, ParseErrorKind::UnexpectedEnd("element row"));
+
+        let mut truncated = binary_quad(false);
+        truncated.pop();
+        let length = truncated.len();
+        match PlyObject::from_bytes(&truncated) {
+            Err(ParseError::Binary { offset, kind }) => {
+                assert_eq!((offset, kind), (length, ParseErrorKind::UnexpectedEnd("property value")))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::io::Read;
+use std::path::Path;
+
+use crate::objects::intermediaries::stl_object::{StlObject, StlTriangle};
+use crate::objects::parsers::line_parser::LineParser;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
+
+const HEADER_SIZE: usize = 80;
+/// A normal and three vertices as `f32`, followed by a 2 byte attribute.
+const TRIANGLE_SIZE: usize = 50;
+
+impl StlObject {
+    pub fn parse(file_path: &Path) -> Result<StlObject, ParseError> {
+        StlObject::from_bytes(&std::fs::read(file_path)?)
+    }
+
+    pub fn parse_str(text: &str) -> Result<StlObject, ParseError> {
+        StlObject::from_ascii(text)
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<StlObject, ParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        StlObject::from_bytes(&bytes)
+    }
+
+    /// Parses either form. Binary files may also start with `solid`, so a file is only taken as
+    /// ASCII if its size doesn't match the triangle count of a binary file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<StlObject, ParseError> {
+        let binary_size = bytes
+            .get(HEADER_SIZE..HEADER_SIZE + 4)
+            .map(|count| HEADER_SIZE + 4 + TRIANGLE_SIZE * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+        if bytes.starts_with(b"solid") && binary_size != Some(bytes.len()) {
+            StlObject::from_ascii(&String::from_utf8_lossy(bytes))
+        } else {
+            StlObject::from_binary(bytes)
+        }
+    }
+
+    fn from_ascii(text: &str) -> Result<StlObject, ParseError> {
+        let end_line = text.lines().count() + 1;
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| LineParser::new(index + 1, line))
+            .filter(|parser| parser.peek().is_some());
+        let mut next_line = |expected: &'static str| {
+            lines.next().ok_or(ParseError::Parse {
+                line: end_line,
+                column: 1,
+                kind: ParseErrorKind::UnexpectedEnd(expected),
+            })
+        };
+
+        let mut object = StlObject::default();
+        let mut parser = next_line("solid")?;
+        parser.expect("solid")?;
+        object.name = parser.rest("name").unwrap_or("").to_string();
+
+        // Some files hold several solids one after another, their triangles are merged.
+        loop {
+            let mut parser = next_line("facet")?;
+            match parser.peek() {
+                Some("endsolid") => match next_line("solid") {
+                    Ok(mut parser) => {
+                        parser.expect("solid")?;
+                        continue;
+                    }
+                    Err(_) => break,
+                },
+                _ => parser.expect("facet")?,
+            }
+            parser.expect("normal")?;
+            let normal = parse_vector(&mut parser)?;
+
+            let mut parser = next_line("outer loop")?;
+            parser.expect("outer")?;
+            parser.expect("loop")?;
+            let mut vertices = [[0.0; 3]; 3];
+            for vertex in &mut vertices {
+                let mut parser = next_line("vertex")?;
+                parser.expect("vertex")?;
+                *vertex = parse_vector(&mut parser)?;
+            }
+            next_line("endloop")?.expect("endloop")?;
+            next_line("endfacet")?.expect("endfacet")?;
+
+            object.triangles.push(StlTriangle { normal, vertices });
+        }
+
+        Ok(object)
+    }
+
+    fn from_binary(bytes: &[u8]) -> Result<StlObject, ParseError> {
+        let truncated = |expected| ParseError::Binary {
+            offset: bytes.len(),
+            kind: ParseErrorKind::UnexpectedEnd(expected),
+        };
+        let header = bytes.get(..HEADER_SIZE).ok_or_else(|| truncated("header"))?;
+        let count = bytes.get(HEADER_SIZE..HEADER_SIZE + 4).ok_or_else(|| truncated("triangle count"))?;
+        let count = u32::from_le_bytes(count.try_into().unwrap()) as usize;
+
+        let body = &bytes[HEADER_SIZE + 4..];
+        if body.len() < count * TRIANGLE_SIZE {
+            return Err(truncated("triangle"));
+        }
+
+        let triangles = body
+            .chunks_exact(TRIANGLE_SIZE)
+            .take(count)
+            .map(|triangle| {
+                let float = |index: usize| f32::from_le_bytes(triangle[index * 4..index * 4 + 4].try_into().unwrap());
+                let vector = |first: usize| [float(first), float(first + 1), float(first + 2)];
+                StlTriangle {
+                    normal: vector(0),
+                    vertices: [vector(3), vector(6), vector(9)],
+                }
+            })
+            .collect();
+
+        Ok(StlObject {
+            name: String::from_utf8_lossy(header).trim_end_matches(['\0', ' ']).to_string(),
+            triangles,
+        })
+    }
+}
+
+fn parse_vector(parser: &mut LineParser) -> Result<[f32; 3], ParseError> {
+    Ok([parser.float("x")?, parser.float("y")?, parser.float("z")?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE_CORNER: &str = "\
+solid corner
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 0 1
+    endloop
+  endfacet
+endsolid corner
+";
+
+    fn binary(header: &[u8], triangles: &[StlTriangle]) -> Vec<u8> {
+        let mut bytes = header.to_vec();
+        bytes.resize(HEADER_SIZE, 0);
+        bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            let [a, b, c] = triangle.vertices;
+            for value in [triangle.normal, a, b, c].concat() {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_ascii() {
+        let object = StlObject::parse_str(CUBE_CORNER).unwrap();
+        assert_eq!(object.name, "corner");
+        assert_eq!(object.triangles.len(), 2);
+        assert_eq!(object.triangles[0].normal, [0.0, 0.0, -1.0]);
+        assert_eq!(object.triangles[1].vertices, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(StlObject::from_bytes(CUBE_CORNER.as_bytes()).unwrap(), object);
+    }
+
+    #[test]
+    fn test_binary() {
+        let ascii = StlObject::parse_str(CUBE_CORNER).unwrap();
+        // Binary files that start with "solid" must not be mistaken for ASCII ones.
+        let bytes = binary(b"solid corner", &ascii.triangles);
+        let object = StlObject::from_bytes(&bytes).unwrap();
+        assert_eq!(object.name, "solid corner");
+        assert_eq!(object.triangles, ascii.triangles);
+        assert_eq!(StlObject::from_reader(bytes.as_slice()).unwrap(), object);
+    }
+
+    #[test]
+    fn test_errors() {
+        match StlObject::parse_str("solid x\nfacet normal 0 0 1\nouter loop\nvertex 0 0\n") {
+            Err(ParseError::Parse { line: 4, column: 11, kind }) => assert_eq!(kind, ParseErrorKind::MissingValue("z")),
+            other => panic!("unexpected result {:?}", other),
+        }
+        match StlObject::parse_str("solid x\nfacet normal 0 0 1\nouter loop\n") {
+            Err(ParseError::Parse { line: 4, kind, .. }) => assert_eq!(kind, ParseErrorKind::UnexpectedEnd("vertex")),
+            other => panic!("unexpected result {:?}", other),
+        }
+        match StlObject::parse_str("solid x\nfacet normal 0 0 1\ninner loop\n") {
+            Err(ParseError::Parse { line: 3, column: 1, kind }) => assert_eq!(
+                kind,
+                ParseErrorKind::UnexpectedToken { expected: "outer", found: "inner".to_string() }
+            ),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let mut truncated = binary(b"", &StlObject::parse_str(CUBE_CORNER).unwrap().triangles);
+        truncated.truncate(120);
+        match StlObject::from_bytes(&truncated) {
+            Err(ParseError::Binary { offset: 120, kind }) => assert_eq!(kind, ParseErrorKind::UnexpectedEnd("triangle")),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
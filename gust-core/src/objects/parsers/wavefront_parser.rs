@@ -1,66 +1,234 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 
-use crate::objects::intermediaries::wavefront_object::WavefrontObject;
+use crate::objects::intermediaries::wavefront_object::{FaceGroup, FaceVertex, WavefrontObject};
+use crate::objects::parsers::line_parser::LineParser;
+use crate::objects::parsers::parse_error::{ParseError, ParseErrorKind};
 
 impl WavefrontObject {
-    pub fn parse(file_path: &Path) -> WavefrontObject {
-        let file = std::fs::read_to_string(file_path).expect("Failed to read file");
-
-        let mut vertices = Vec::new();
-        let mut tex_coords = Vec::new();
-        let mut normals = Vec::new();
-        let mut faces = Vec::new();
-
-        for line in file.lines() {
-            let mut parts = line.split_whitespace();
-            match parts.next() {
-                Some("v") => {
-                    let x: f32 = parts.next().unwrap().parse().unwrap();
-                    let y: f32 = parts.next().unwrap().parse().unwrap();
-                    let z: f32 = parts.next().unwrap().parse().unwrap();
-                    vertices.push([x, y, z]);
+    pub fn parse(file_path: &Path) -> Result<WavefrontObject, ParseError> {
+        WavefrontObject::from_reader(File::open(file_path)?)
+    }
+
+    pub fn parse_str(text: &str) -> Result<WavefrontObject, ParseError> {
+        WavefrontObject::from_reader(text.as_bytes())
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> Result<WavefrontObject, ParseError> {
+        let mut object = WavefrontObject::default();
+        let mut group = FaceGroup::default();
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            // Comments may also trail a statement.
+            let line = line.split('#').next().unwrap_or("");
+            let mut parser = LineParser::new(index + 1, line);
+
+            match parser.next() {
+                Some((_, "v")) => {
+                    let x = parser.float("x coordinate")?;
+                    let y = parser.float("y coordinate")?;
+                    let z = parser.float("z coordinate")?;
+                    object.vertices.push([x, y, z]);
+                }
+                Some((_, "vt")) => {
+                    let u = parser.float("u coordinate")?;
+                    let v = parser.optional_float(0.0)?;
+                    object.tex_coords.push([u, v]);
+                }
+                Some((_, "vn")) => {
+                    let x = parser.float("x component")?;
+                    let y = parser.float("y component")?;
+                    let z = parser.float("z component")?;
+                    object.normals.push([x, y, z]);
+                }
+                Some((column, "f")) => {
+                    let face = object.parse_face(&mut parser)?;
+                    if face.len() < 3 {
+                        return Err(parser.error(column, ParseErrorKind::TooFewFaceVertices(face.len())));
+                    }
+                    object.faces.push(face);
+                }
+                Some((_, "o")) => {
+                    let name = parser.rest("object name")?.to_string();
+                    object.start_group(&mut group).object = Some(name);
                 }
-                Some("vt") => {
-                    let u: f32 = parts.next().unwrap().parse().unwrap();
-                    let v: f32 = parts.next().unwrap().parse().unwrap();
-                    tex_coords.push([u, v]);
+                Some((_, "g")) => {
+                    let names = std::iter::from_fn(|| parser.next()).map(|(_, name)| name.to_string()).collect();
+                    object.start_group(&mut group).groups = names;
                 }
-                Some("vn") => {
-                    let x: f32 = parts.next().unwrap().parse().unwrap();
-                    let y: f32 = parts.next().unwrap().parse().unwrap();
-                    let z: f32 = parts.next().unwrap().parse().unwrap();
-                    normals.push([x, y, z]);
+                Some((_, "usemtl")) => {
+                    let name = parser.rest("material name")?.to_string();
+                    object.start_group(&mut group).material = Some(name);
                 }
-                Some("f") => {
-                    let face = WavefrontObject::parse_face(line);
-                    faces.push(face);
+                Some((_, "s")) => {
+                    let smoothing_group = match parser.peek() {
+                        Some("off") => 0,
+                        _ => parser.integer("smoothing group")?,
+                    };
+                    object.start_group(&mut group).smoothing_group = smoothing_group;
+                }
+                Some((_, "mtllib")) => {
+                    let name = parser.rest("material library")?.to_string();
+                    object.material_libraries.push(name);
                 }
                 _ => {}
             }
         }
 
-        WavefrontObject {
-            vertices,
-            tex_coords,
-            normals,
-            faces,
+        object.start_group(&mut group);
+        Ok(object)
+    }
+
+    /// Closes the current face group, if it has any faces, and returns the next one to modify.
+    /// The next group inherits the current state.
+    fn start_group<'a>(&mut self, group: &'a mut FaceGroup) -> &'a mut FaceGroup {
+        let end = self.faces.len();
+        group.faces.end = end;
+        if !group.faces.is_empty() {
+            self.face_groups.push(group.clone());
         }
+        group.faces = end..end;
+        group
     }
 
-    fn parse_face(mut line: &str) -> Vec<[u32; 3]> {
-        //remove the 'f' from the line
-        line = line.trim_start_matches('f').trim();
-        let mut faces = Vec::new();
-        for face in line.split_whitespace() {
-            let mut indices: [u32; 3] = [0; 3];
-            for (index, value) in face.split('/').enumerate() {
-                if value.is_empty() {
-                    continue;
-                }
-                indices[index] = value.parse().unwrap();
+    fn parse_face(&self, parser: &mut LineParser) -> Result<Vec<FaceVertex>, ParseError> {
+        let mut face = Vec::new();
+        while let Some((column, text)) = parser.next() {
+            let invalid = || parser.error(column, ParseErrorKind::InvalidFaceVertex(text.to_string()));
+
+            let mut parts = text.split('/');
+            let position = parts.next().filter(|part| !part.is_empty()).ok_or_else(invalid)?;
+            let tex_coord = parts.next().filter(|part| !part.is_empty());
+            let normal = parts.next().filter(|part| !part.is_empty());
+            if parts.next().is_some() {
+                return Err(invalid());
             }
-            faces.push(indices);
+
+            let resolve = |part: &str, element: &'static str, count: usize| -> Result<u32, ParseError> {
+                let index: i64 = part.parse().map_err(|_| invalid())?;
+                let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+                if index == 0 || resolved < 0 || resolved >= count as i64 {
+                    return Err(parser.error(column, ParseErrorKind::IndexOutOfRange { element, index, count }));
+                }
+                Ok(resolved as u32)
+            };
+
+            face.push(FaceVertex {
+                position: resolve(position, "vertex", self.vertices.len())?,
+                tex_coord: tex_coord.map(|part| resolve(part, "texture coordinate", self.tex_coords.len())).transpose()?,
+                normal: normal.map(|part| resolve(part, "normal", self.normals.len())).transpose()?,
+            });
         }
-        faces
+        Ok(face)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1\nvn 0 0 1\n";
+
+    fn parse_error(text: &str) -> (usize, usize, ParseErrorKind) {
+        match WavefrontObject::parse_str(text) {
+            Err(ParseError::Parse { line, column, kind }) => (line, column, kind),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_face_forms() {
+        let text = format!("{}f 1 2 3\nf 1/1 2/2 3/1\nf 1//1 2//1 3//1\nf 1/2/1 2/1/1 3/2/1 # quad-less\n", TRIANGLE);
+        let object = WavefrontObject::parse_str(&text).unwrap();
+        assert_eq!(object.tex_coords[1], [1.0, 0.0]);
+        assert_eq!(object.faces.len(), 4);
+        assert_eq!(object.faces[0][1], FaceVertex { position: 1, tex_coord: None, normal: None });
+        assert_eq!(object.faces[1][1], FaceVertex { position: 1, tex_coord: Some(1), normal: None });
+        assert_eq!(object.faces[2][2], FaceVertex { position: 2, tex_coord: None, normal: Some(0) });
+        assert_eq!(object.faces[3][0], FaceVertex { position: 0, tex_coord: Some(1), normal: Some(0) });
+    }
+
+    #[test]
+    fn test_negative_indices() {
+        let text = format!("{}f -3/-2/-1 -2/-1/-1 -1/-2/-1\nv 5 5 5\nf -1 -2 -3\n", TRIANGLE);
+        let object = WavefrontObject::parse_str(&text).unwrap();
+        assert_eq!(object.faces[0].iter().map(|vertex| vertex.position).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(object.faces[0][0].tex_coord, Some(0));
+        assert_eq!(object.faces[1].iter().map(|vertex| vertex.position).collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_errors_have_positions() {
+        assert_eq!(parse_error("v 1 2 x\n"), (1, 7, ParseErrorKind::InvalidNumber("x".to_string())));
+        assert_eq!(parse_error("\nv 1 2\n"), (2, 6, ParseErrorKind::MissingValue("z coordinate")));
+        assert_eq!(
+            parse_error(&format!("{}f 1 2 4\n", TRIANGLE)),
+            (7, 7, ParseErrorKind::IndexOutOfRange { element: "vertex", index: 4, count: 3 })
+        );
+        assert_eq!(
+            parse_error(&format!("{}f 1 2 0\n", TRIANGLE)),
+            (7, 7, ParseErrorKind::IndexOutOfRange { element: "vertex", index: 0, count: 3 })
+        );
+        assert_eq!(parse_error(&format!("{}f 1/a 2 3\n", TRIANGLE)), (7, 3, ParseErrorKind::InvalidFaceVertex("1/a".to_string())));
+        assert_eq!(parse_error(&format!("{}  f 1 2\n", TRIANGLE)), (7, 3, ParseErrorKind::TooFewFaceVertices(2)));
+    }
+
+    #[test]
+    fn test_face_groups() {
+        let text = format!(
+            "mtllib scene.mtl\n{}o Left Wing\ng a b\nusemtl red paint\nf 1 2 3\nf 1 2 3\ns 1\nf 1 2 3\no Tail\ng\nusemtl blue\nusemtl green\ns off\nf 3 2 1\n",
+            TRIANGLE
+        );
+        let object = WavefrontObject::parse_str(&text).unwrap();
+        assert_eq!(object.material_libraries, vec!["scene.mtl".to_string()]);
+        assert_eq!(
+            object.face_groups,
+            vec![
+                FaceGroup {
+                    object: Some("Left Wing".to_string()),
+                    groups: vec!["a".to_string(), "b".to_string()],
+                    material: Some("red paint".to_string()),
+                    smoothing_group: 0,
+                    faces: 0..2,
+                },
+                FaceGroup {
+                    object: Some("Left Wing".to_string()),
+                    groups: vec!["a".to_string(), "b".to_string()],
+                    material: Some("red paint".to_string()),
+                    smoothing_group: 1,
+                    faces: 2..3,
+                },
+                FaceGroup {
+                    object: Some("Tail".to_string()),
+                    groups: vec![],
+                    material: Some("green".to_string()),
+                    smoothing_group: 0,
+                    faces: 3..4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_faces_without_statements_form_one_group() {
+        let object = WavefrontObject::parse_str(&format!("{}f 1 2 3\nf 3 2 1\n", TRIANGLE)).unwrap();
+        assert_eq!(object.face_groups, vec![FaceGroup { faces: 0..2, ..FaceGroup::default() }]);
+        assert!(WavefrontObject::parse_str(TRIANGLE).unwrap().face_groups.is_empty());
+    }
+
+    #[test]
+    fn test_io_error() {
+        let error = WavefrontObject::parse(Path::new("does/not/exist.obj")).unwrap_err();
+        assert!(matches!(error, ParseError::Io(_)));
+    }
+
+    #[test]
+    fn test_parses_bundled_assets() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets/objects/monkey.obj");
+        let object = WavefrontObject::parse(&path).unwrap();
+        assert_eq!(object.vertices.len(), 507);
+        assert!(object.faces.iter().all(|face| face.len() >= 3));
     }
-}
\ No newline at end of file
+}
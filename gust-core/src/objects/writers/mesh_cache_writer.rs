@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::objects::intermediaries::mesh_cache::{MeshCache, SubMesh, MAGIC, VERSION};
+use crate::objects::intermediaries::wavefront_object::{FaceGroup, WavefrontObject};
+use crate::primitives::mesh::Mesh;
+
+impl MeshCache {
+    /// A cache of a single mesh without names.
+    pub fn from_mesh(mesh: Mesh) -> Self {
+        let sub_meshes = match mesh.index_count() {
+            0 => Vec::new(),
+            count => vec![SubMesh { indices: 0..count as u32, ..SubMesh::default() }],
+        };
+        MeshCache { bounds: mesh.bounds(), mesh, sub_meshes }
+    }
+
+    /// Converts every face group of the object into a sub-mesh of one mesh.
+    pub fn from_wavefront(wavefront_object: &WavefrontObject) -> Self {
+        let groups = match wavefront_object.face_groups.is_empty() {
+            true => vec![FaceGroup { faces: 0..wavefront_object.faces.len(), ..FaceGroup::default() }],
+            false => wavefront_object.face_groups.clone(),
+        };
+
+        let mut mesh = Mesh::new();
+        let mut sub_meshes = Vec::new();
+        for group in groups {
+            let part = Mesh::from_wavefront_faces(wavefront_object, group.faces.clone());
+            let (first_vertex, first_index) = (mesh.vertex_count() as u32, mesh.index_count() as u32);
+            mesh.vertices.extend(part.vertices);
+            mesh.indices.extend(part.indices.iter().map(|index| index + first_vertex));
+            sub_meshes.push(SubMesh {
+                object: group.object,
+                groups: group.groups,
+                material: group.material,
+                indices: first_index..mesh.index_count() as u32,
+            });
+        }
+
+        MeshCache { bounds: mesh.bounds(), mesh, sub_meshes }
+    }
+
+    pub fn save(&self, file_path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file_path)?);
+        writer.write_all(&self.to_bytes())?;
+        writer.flush()
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())?;
+        writer.flush()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + self.mesh.vertex_count() * 48 + self.mesh.index_count() * 4);
+        let u32s = |bytes: &mut Vec<u8>, values: &[u32]| values.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        let f32s = |bytes: &mut Vec<u8>, values: &[f32]| values.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+
+        bytes.extend_from_slice(MAGIC);
+        u32s(&mut bytes, &[VERSION, self.mesh.vertex_count() as u32, self.mesh.index_count() as u32, self.sub_meshes.len() as u32]);
+
+        let (aabb, sphere) = (&self.bounds.aabb, &self.bounds.sphere);
+        f32s(&mut bytes, &aabb.min.as_slice());
+        f32s(&mut bytes, &aabb.max.as_slice());
+        f32s(&mut bytes, &sphere.center.as_slice());
+        f32s(&mut bytes, &[sphere.radius]);
+
+        for vertex in &self.mesh.vertices {
+            f32s(&mut bytes, &vertex.position);
+            f32s(&mut bytes, &vertex.normal);
+            f32s(&mut bytes, &vertex.tex_coords);
+            f32s(&mut bytes, &vertex.tangent);
+        }
+        u32s(&mut bytes, &self.mesh.indices);
+
+        for sub_mesh in &self.sub_meshes {
+            write_string(&mut bytes, sub_mesh.object.as_deref());
+            u32s(&mut bytes, &[sub_mesh.groups.len() as u32]);
+            sub_mesh.groups.iter().for_each(|group| write_string(&mut bytes, Some(group)));
+            write_string(&mut bytes, sub_mesh.material.as_deref());
+            u32s(&mut bytes, &[sub_mesh.indices.start, sub_mesh.indices.end]);
+        }
+
+        let checksum = crc32fast::hash(&bytes);
+        u32s(&mut bytes, &[checksum]);
+        bytes
+    }
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: Option<&str>) {
+    match string {
+        Some(string) => {
+            bytes.extend_from_slice(&(string.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(string.as_bytes());
+        }
+        None => bytes.extend_from_slice(&u32::MAX.to_le_bytes()),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod wavefront_writer;
+pub mod ply_writer;
+pub mod mesh_cache_writer;
\ No newline at end of file
@@ -0,0 +1,158 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::objects::intermediaries::ply_object::{PlyFormat, PlyObject};
+use crate::primitives::mesh::Mesh;
+
+/// A value of the body, in the type it is declared with in the header.
+#[derive(Clone, Copy)]
+enum Value {
+    Float(f32),
+    UChar(u8),
+    UInt(u32),
+}
+
+impl PlyObject {
+    /// Writes out every vertex of the mesh and a face per triangle, so `Mesh::from_ply` gives
+    /// back the same mesh.
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        PlyObject {
+            vertices: mesh.vertices.iter().map(|vertex| vertex.position).collect(),
+            normals: mesh.vertices.iter().map(|vertex| vertex.normal).collect(),
+            tex_coords: mesh.vertices.iter().map(|vertex| vertex.tex_coords).collect(),
+            // Undo the winding reversal of `Mesh::from_ply`
+            faces: mesh.indices.chunks_exact(3).map(|triangle| vec![triangle[0], triangle[2], triangle[1]]).collect(),
+            comments: Vec::new(),
+        }
+    }
+
+    pub fn save(&self, file_path: &Path, format: PlyFormat) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(file_path)?), format)
+    }
+
+    /// Writes the object in PLY format. Normals and texture coordinates are only written if
+    /// every vertex has one.
+    pub fn write<W: Write>(&self, mut writer: W, format: PlyFormat) -> io::Result<()> {
+        let has_normals = !self.vertices.is_empty() && self.normals.len() == self.vertices.len();
+        let has_tex_coords = !self.vertices.is_empty() && self.tex_coords.len() == self.vertices.len();
+        // `uchar` counts are what most readers expect, but limit faces to 255 vertices.
+        let small_faces = self.faces.iter().all(|face| face.len() <= u8::MAX as usize);
+
+        writeln!(writer, "ply")?;
+        let format_name = match format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+            PlyFormat::BinaryBigEndian => "binary_big_endian",
+        };
+        writeln!(writer, "format {} 1.0", format_name)?;
+        for comment in &self.comments {
+            writeln!(writer, "comment {}", comment)?;
+        }
+        writeln!(writer, "element vertex {}", self.vertices.len())?;
+        writeln!(writer, "property float x\nproperty float y\nproperty float z")?;
+        if has_normals {
+            writeln!(writer, "property float nx\nproperty float ny\nproperty float nz")?;
+        }
+        if has_tex_coords {
+            writeln!(writer, "property float s\nproperty float t")?;
+        }
+        writeln!(writer, "element face {}", self.faces.len())?;
+        let count_type = if small_faces { "uchar" } else { "uint" };
+        writeln!(writer, "property list {} uint vertex_indices", count_type)?;
+        writeln!(writer, "end_header")?;
+
+        let mut row = Vec::new();
+        for (index, position) in self.vertices.iter().enumerate() {
+            row.clear();
+            row.extend(position.map(Value::Float));
+            if has_normals {
+                row.extend(self.normals[index].map(Value::Float));
+            }
+            if has_tex_coords {
+                row.extend(self.tex_coords[index].map(Value::Float));
+            }
+            write_row(&mut writer, format, &row)?;
+        }
+        for face in &self.faces {
+            row.clear();
+            row.push(match small_faces {
+                true => Value::UChar(face.len() as u8),
+                false => Value::UInt(face.len() as u32),
+            });
+            row.extend(face.iter().map(|&index| Value::UInt(index)));
+            write_row(&mut writer, format, &row)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// ASCII rows are space separated lines, binary rows are the bare values.
+fn write_row<W: Write>(writer: &mut W, format: PlyFormat, row: &[Value]) -> io::Result<()> {
+    for (index, value) in row.iter().enumerate() {
+        match (format, *value) {
+            (PlyFormat::Ascii, value) => {
+                let separator = if index == 0 { "" } else { " " };
+                match value {
+                    Value::Float(value) => write!(writer, "{}{}", separator, value)?,
+                    Value::UChar(value) => write!(writer, "{}{}", separator, value)?,
+                    Value::UInt(value) => write!(writer, "{}{}", separator, value)?,
+                }
+            }
+            (PlyFormat::BinaryLittleEndian, Value::Float(value)) => writer.write_all(&value.to_le_bytes())?,
+            (PlyFormat::BinaryBigEndian, Value::Float(value)) => writer.write_all(&value.to_be_bytes())?,
+            (_, Value::UChar(value)) => writer.write_all(&[value])?,
+            (PlyFormat::BinaryLittleEndian, Value::UInt(value)) => writer.write_all(&value.to_le_bytes())?,
+            (PlyFormat::BinaryBigEndian, Value::UInt(value)) => writer.write_all(&value.to_be_bytes())?,
+        }
+    }
+    if format == PlyFormat::Ascii {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::vertex::Vertex;
+
+    use super::*;
+
+    fn write(object: &PlyObject, format: PlyFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        object.write(&mut bytes, format).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_mesh_round_trip() {
+        let vertex = |position, tex_coords| Vertex::new(position, [0.0, 0.0, 1.0], tex_coords);
+        let mesh = Mesh::from_triangles(&[
+            [vertex([0.0, 0.0, 0.0], [0.0, 0.0]), vertex([0.0, 1.0, 0.0], [0.0, 1.0]), vertex([1.0, 0.0, 0.0], [1.0, 0.0])],
+            [vertex([1.0, 0.0, 0.0], [1.0, 0.0]), vertex([0.0, 1.0, 0.0], [0.0, 1.0]), vertex([1.0, 1.0, 0.5], [1.0, 1.0])],
+        ]);
+        let object = PlyObject::from_mesh(&mesh);
+
+        for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian, PlyFormat::BinaryBigEndian] {
+            let parsed = PlyObject::from_bytes(&write(&object, format)).unwrap();
+            assert_eq!(parsed, object);
+            assert_eq!(Mesh::from_ply(&parsed), mesh);
+        }
+    }
+
+    #[test]
+    fn test_ascii_output() {
+        let object = PlyObject {
+            vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.5, 0.0]],
+            faces: vec![vec![0, 1, 2]],
+            comments: vec!["triangle".to_string()],
+            ..PlyObject::default()
+        };
+        let text = String::from_utf8(write(&object, PlyFormat::Ascii)).unwrap();
+        assert_eq!(
+            text,
+            "ply\nformat ascii 1.0\ncomment triangle\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\nelement face 1\nproperty list uchar uint vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1.5 0\n3 0 1 2\n"
+        );
+    }
+}
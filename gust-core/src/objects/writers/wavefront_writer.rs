@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::objects::intermediaries::wavefront_object::{FaceGroup, FaceVertex, WavefrontObject};
+use crate::primitives::mesh::Mesh;
+
+impl WavefrontObject {
+    /// Writes out every vertex of the mesh with its texture coordinate and normal, and a face
+    /// per triangle, so `Mesh::from_wavefront` gives back the same mesh.
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let faces: Vec<Vec<FaceVertex>> = mesh
+            .indices
+            .chunks_exact(3)
+            // Undo the winding reversal of `Mesh::from_wavefront`
+            .map(|triangle| {
+                [triangle[0], triangle[2], triangle[1]]
+                    .iter()
+                    .map(|&index| FaceVertex { position: index, tex_coord: Some(index), normal: Some(index) })
+                    .collect()
+            })
+            .collect();
+
+        WavefrontObject {
+            vertices: mesh.vertices.iter().map(|vertex| vertex.position).collect(),
+            tex_coords: mesh.vertices.iter().map(|vertex| vertex.tex_coords).collect(),
+            normals: mesh.vertices.iter().map(|vertex| vertex.normal).collect(),
+            face_groups: match faces.len() {
+                0 => Vec::new(),
+                count => vec![FaceGroup { faces: 0..count, ..FaceGroup::default() }],
+            },
+            faces,
+            material_libraries: Vec::new(),
+        }
+    }
+
+    pub fn save(&self, file_path: &Path) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(file_path)?))
+    }
+
+    /// Writes the object in OBJ format. Statements for the face groups are only written when
+    /// their state changes.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for library in &self.material_libraries {
+            writeln!(writer, "mtllib {}", library)?;
+        }
+        for [x, y, z] in &self.vertices {
+            writeln!(writer, "v {} {} {}", x, y, z)?;
+        }
+        for [u, v] in &self.tex_coords {
+            writeln!(writer, "vt {} {}", u, v)?;
+        }
+        for [x, y, z] in &self.normals {
+            writeln!(writer, "vn {} {} {}", x, y, z)?;
+        }
+
+        // Objects built by hand may have faces but no groups.
+        let all_faces = [FaceGroup { faces: 0..self.faces.len(), ..FaceGroup::default() }];
+        let groups = match self.face_groups.is_empty() {
+            true => &all_faces[..],
+            false => &self.face_groups[..],
+        };
+
+        let mut previous = FaceGroup::default();
+        for group in groups {
+            if let Some(object) = group.object.as_ref().filter(|&object| previous.object.as_ref() != Some(object)) {
+                writeln!(writer, "o {}", object)?;
+            }
+            if group.groups != previous.groups {
+                match group.groups.is_empty() {
+                    true => writeln!(writer, "g")?,
+                    false => writeln!(writer, "g {}", group.groups.join(" "))?,
+                }
+            }
+            if let Some(material) = group.material.as_ref().filter(|&material| previous.material.as_ref() != Some(material)) {
+                writeln!(writer, "usemtl {}", material)?;
+            }
+            if group.smoothing_group != previous.smoothing_group {
+                match group.smoothing_group {
+                    0 => writeln!(writer, "s off")?,
+                    smoothing_group => writeln!(writer, "s {}", smoothing_group)?,
+                }
+            }
+
+            for face in &self.faces[group.faces.clone()] {
+                write!(writer, "f")?;
+                for vertex in face {
+                    // OBJ indices are one based
+                    match (vertex.tex_coord, vertex.normal) {
+                        (Some(tex_coord), Some(normal)) => write!(writer, " {}/{}/{}", vertex.position + 1, tex_coord + 1, normal + 1)?,
+                        (Some(tex_coord), None) => write!(writer, " {}/{}", vertex.position + 1, tex_coord + 1)?,
+                        (None, Some(normal)) => write!(writer, " {}//{}", vertex.position + 1, normal + 1)?,
+                        (None, None) => write!(writer, " {}", vertex.position + 1)?,
+                    }
+                }
+                writeln!(writer)?;
+            }
+            previous = group.clone();
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::vertex::Vertex;
+
+    use super::*;
+
+    fn to_string(object: &WavefrontObject) -> String {
+        let mut bytes = Vec::new();
+        object.write(&mut bytes).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_mesh_round_trip() {
+        let vertex = |position, tex_coords| Vertex::new(position, [0.0, 0.0, 1.0], tex_coords);
+        let mesh = Mesh::from_triangles(&[
+            [vertex([0.0, 0.0, 0.0], [0.0, 0.0]), vertex([0.0, 1.0, 0.0], [0.0, 1.0]), vertex([1.0, 0.0, 0.0], [1.0, 0.0])],
+            [vertex([1.0, 0.0, 0.0], [1.0, 0.0]), vertex([0.0, 1.0, 0.0], [0.0, 1.0]), vertex([1.0, 1.0, 0.5], [1.0, 1.0])],
+        ]);
+
+        let text = to_string(&WavefrontObject::from_mesh(&mesh));
+        assert!(text.contains("v 1 1 0.5\n"));
+        assert!(text.contains("f 1/1/1 3/3/3 2/2/2\n"));
+        assert_eq!(Mesh::from_wavefront(WavefrontObject::parse_str(&text).unwrap()), mesh);
+    }
+
+    #[test]
+    fn test_groups_round_trip() {
+        let text = "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\no Wing\ng a b\nusemtl red\nf 1/1/1 2/1/1 3/1/1\ns 2\nf 1 2 3\ng\nusemtl blue\ns off\nf 3//1 2//1 1//1\nf 1/1 2/1 3/1\n";
+        let object = WavefrontObject::parse_str(text).unwrap();
+        let written = to_string(&object);
+        assert_eq!(written, text);
+        assert_eq!(WavefrontObject::parse_str(&written).unwrap(), object);
+    }
+}
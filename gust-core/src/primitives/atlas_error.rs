@@ -0,0 +1,41 @@
+use std::fmt;
+
+use crate::primitives::texture_error::TextureError;
+
+/// Errors from building a texture atlas.
+#[derive(Debug)]
+pub enum AtlasError {
+    /// Two images were added under the same name.
+    DuplicateName(String),
+    /// The images don't fit together in an atlas of the largest allowed size. `name` is the
+    /// first image left without room.
+    DoesNotFit { name: String, max_size: u32 },
+    Texture(TextureError),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AtlasError::DuplicateName(name) => write!(f, "atlas already has an image named '{}'", name),
+            AtlasError::DoesNotFit { name, max_size } => {
+                write!(f, "no room for image '{}' in a {}x{} atlas", name, max_size, max_size)
+            }
+            AtlasError::Texture(error) => write!(f, "failed to create atlas texture: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for AtlasError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AtlasError::Texture(error) => Some(error),
+            AtlasError::DuplicateName(_) | AtlasError::DoesNotFit { .. } => None,
+        }
+    }
+}
+
+impl From<TextureError> for AtlasError {
+    fn from(error: TextureError) -> Self {
+        AtlasError::Texture(error)
+    }
+}
@@ -0,0 +1,242 @@
+use gust_math::matrices::mat4::Mat4;
+use gust_math::vectors::vect3::Vect3;
+
+use crate::primitives::mesh::Mesh;
+
+/// An axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vect3,
+    pub max: Vect3,
+}
+
+/// A sphere enclosing a set of points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vect3,
+    pub radius: f32,
+}
+
+/// Both bounding volumes of a mesh. The box fits axis aligned shapes tightly, the sphere is
+/// cheaper to test and doesn't depend on the rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshBounds {
+    pub aabb: Aabb,
+    pub sphere: BoundingSphere,
+}
+
+impl Aabb {
+    pub fn new(min: Vect3, max: Vect3) -> Self {
+        Aabb { min, max }
+    }
+
+    /// The smallest box around the points, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Vect3>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb::new(first, first), |aabb, point| Aabb::new(aabb.min.min(&point), aabb.max.max(&point))))
+    }
+
+    pub fn center(&self) -> Vect3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn size(&self) -> Vect3 {
+        self.max - self.min
+    }
+
+    /// Half the size along each axis.
+    pub fn extents(&self) -> Vect3 {
+        self.size() / 2.0
+    }
+
+    pub fn contains(&self, point: Vect3) -> bool {
+        (0..3).all(|axis| self.min[axis] <= point[axis] && point[axis] <= self.max[axis])
+    }
+
+    /// Touching boxes count as intersecting.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|axis| self.min[axis] <= other.max[axis] && other.min[axis] <= self.max[axis])
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    /// The box around this one after transforming it by a model matrix. Rotations make it
+    /// larger than the box around the transformed shape would be.
+    pub fn transformed(&self, matrix: &Mat4) -> Aabb {
+        let matrix = matrix.transpose();
+        let center = matrix * self.center();
+        let extents = self.extents();
+        let extents = Vect3::new(
+            (0..3).map(|axis| matrix[0][axis].abs() * extents[axis]).sum(),
+            (0..3).map(|axis| matrix[1][axis].abs() * extents[axis]).sum(),
+            (0..3).map(|axis| matrix[2][axis].abs() * extents[axis]).sum(),
+        );
+        Aabb::new(center - extents, center + extents)
+    }
+
+    /// How far along the ray it enters the box, in multiples of `direction`. A ray starting
+    /// inside hits at 0.
+    pub fn intersect_ray(&self, origin: Vect3, direction: Vect3) -> Option<f32> {
+        let (mut near, mut far) = (0.0f32, f32::INFINITY);
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let first = (self.min[axis] - origin[axis]) / direction[axis];
+            let second = (self.max[axis] - origin[axis]) / direction[axis];
+            near = near.max(first.min(second));
+            far = far.min(first.max(second));
+        }
+        (near <= far).then_some(near)
+    }
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vect3, radius: f32) -> Self {
+        BoundingSphere { center, radius }
+    }
+
+    /// A sphere centered on the box around the points. It is not the smallest possible sphere,
+    /// but close for most meshes and stable when the points change a little.
+    pub fn from_points(points: &[Vect3]) -> Option<Self> {
+        let center = Aabb::from_points(points.iter().copied())?.center();
+        let radius = points.iter().map(|point| point.distance(&center)).fold(0.0, f32::max);
+        Some(BoundingSphere::new(center, radius))
+    }
+
+    pub fn contains(&self, point: Vect3) -> bool {
+        point.distance(&self.center) <= self.radius
+    }
+
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        self.center.distance(&other.center) <= self.radius + other.radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let closest = self.center.max(&aabb.min).min(&aabb.max);
+        self.contains(closest)
+    }
+
+    /// The sphere after transforming it by a model matrix. Non-uniform scales grow it by the
+    /// largest one.
+    pub fn transformed(&self, matrix: &Mat4) -> BoundingSphere {
+        let scale = (0..3).map(|axis| Vect3::from_slice(&matrix[axis][..3]).norm()).fold(0.0, f32::max);
+        BoundingSphere::new(matrix.transpose() * self.center, self.radius * scale)
+    }
+
+    /// How far along the ray it enters the sphere, in multiples of `direction`. A ray starting
+    /// inside hits at 0.
+    pub fn intersect_ray(&self, origin: Vect3, direction: Vect3) -> Option<f32> {
+        let offset = origin - self.center;
+        let c = offset.dot(&offset) - self.radius * self.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let (a, b) = (direction.dot(&direction), offset.dot(&direction));
+        let discriminant = b * b - a * c;
+        if a == 0.0 || b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        Some((-b - discriminant.sqrt()) / a)
+    }
+}
+
+impl MeshBounds {
+    pub fn transformed(&self, matrix: &Mat4) -> MeshBounds {
+        MeshBounds {
+            aabb: self.aabb.transformed(matrix),
+            sphere: self.sphere.transformed(matrix),
+        }
+    }
+}
+
+impl Mesh {
+    /// The bounds of the vertices in the mesh's own space. An empty mesh gets a point at the
+    /// origin.
+    pub fn bounds(&self) -> MeshBounds {
+        let positions: Vec<Vect3> = self.vertices.iter().map(|vertex| Vect3::from(vertex.position)).collect();
+        let origin = Vect3::zeros();
+        MeshBounds {
+            aabb: Aabb::from_points(positions.iter().copied()).unwrap_or(Aabb::new(origin, origin)),
+            sphere: BoundingSphere::from_points(&positions).unwrap_or(BoundingSphere::new(origin, 0.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::transform_component::TransformComponent;
+
+    use super::*;
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Vect3::new(-1.0, -1.0, -1.0), Vect3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn test_mesh_bounds() {
+        let bounds = Mesh::cube(2.0).bounds();
+        assert_eq!(bounds.aabb, unit_box());
+        assert_eq!(bounds.sphere, BoundingSphere::new(Vect3::zeros(), 3.0f32.sqrt()));
+
+        let bounds = Mesh::cylinder(1.0, 4.0, 16).bounds();
+        assert_eq!(bounds.aabb.size(), Vect3::new(2.0, 2.0, 4.0));
+        assert_eq!(Mesh::new().bounds().sphere.radius, 0.0);
+    }
+
+    #[test]
+    fn test_transformed_matches_transformed_points() {
+        // Model matrices apply their translation before the rotation, like the renderer does.
+        let transform = TransformComponent::default()
+            .with_position(Vect3::new(1.0, 2.0, 3.0))
+            .with_forward(Vect3::new(0.0, 1.0, 0.0))
+            .with_scale(Vect3::new(2.0, 1.0, 1.0))
+            .get_transform_matrix();
+        let mesh = Mesh::cube(2.0);
+        let points: Vec<Vect3> = mesh.vertices.iter().map(|vertex| transform.transpose() * Vect3::from(vertex.position)).collect();
+
+        let bounds = mesh.bounds().transformed(&transform);
+        assert_eq!(bounds.aabb, Aabb::from_points(points.iter().copied()).unwrap());
+        assert!(points.iter().all(|&point| (point.distance(&bounds.sphere.center) - bounds.sphere.radius) < 1e-5));
+        assert!((bounds.sphere.radius - 2.0 * 3.0f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let aabb = unit_box();
+        assert!(aabb.contains(Vect3::new(1.0, 0.0, -1.0)));
+        assert!(!aabb.contains(Vect3::new(1.1, 0.0, 0.0)));
+        assert!(aabb.intersects(&Aabb::new(Vect3::new(1.0, 1.0, 1.0), Vect3::new(2.0, 2.0, 2.0))));
+        assert!(!aabb.intersects(&Aabb::new(Vect3::new(1.0, 1.5, 1.0), Vect3::new(2.0, 2.0, 2.0))));
+        assert_eq!(aabb.union(&Aabb::new(Vect3::zeros(), Vect3::new(2.0, 0.0, 0.0))).max, Vect3::new(2.0, 1.0, 1.0));
+
+        let sphere = BoundingSphere::new(Vect3::new(3.0, 0.0, 0.0), 2.5);
+        assert!(sphere.intersects(&BoundingSphere::new(Vect3::zeros(), 1.5)));
+        assert!(sphere.intersects_aabb(&aabb));
+        assert!(!BoundingSphere::new(Vect3::new(2.0, 2.0, 0.0), 1.4).intersects_aabb(&aabb));
+    }
+
+    #[test]
+    fn test_ray_intersection() {
+        let aabb = unit_box();
+        let sphere = BoundingSphere::new(Vect3::zeros(), 1.0);
+        let origin = Vect3::new(-5.0, 0.5, 0.0);
+        let direction = Vect3::new(2.0, 0.0, 0.0);
+        assert_eq!(aabb.intersect_ray(origin, direction), Some(2.0));
+        assert_eq!(sphere.intersect_ray(Vect3::new(-5.0, 0.0, 0.0), direction), Some(2.0));
+
+        // Pointing away, passing by and starting inside
+        assert_eq!(aabb.intersect_ray(origin, -direction), None);
+        assert_eq!(sphere.intersect_ray(Vect3::new(-5.0, 0.0, 0.0), -direction), None);
+        assert_eq!(aabb.intersect_ray(Vect3::new(-5.0, 1.5, 0.0), direction), None);
+        assert_eq!(sphere.intersect_ray(Vect3::new(-5.0, 1.5, 0.0), direction), None);
+        assert_eq!(aabb.intersect_ray(Vect3::zeros(), direction), Some(0.0));
+        assert_eq!(sphere.intersect_ray(Vect3::zeros(), direction), Some(0.0));
+    }
+}
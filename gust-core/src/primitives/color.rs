@@ -1,4 +1,11 @@
-#[derive(Debug, Clone, Copy)]
+use std::fmt;
+use std::str::FromStr;
+
+/// An RGBA color with straight (non-premultiplied) alpha.
+///
+/// Colors built with `new`, `from_hex` or parsed from strings are in sRGB, the space colors are
+/// authored in. Shaders light in linear space, so convert with `to_linear` before uploading.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -6,11 +13,51 @@ pub struct Color {
     pub a: f32,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorParseError {
+    InvalidLength(usize),
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorParseError::InvalidLength(len) => write!(f, "expected 3, 4, 6 or 8 hex digits, found {}", len),
+            ColorParseError::InvalidDigit(c) => write!(f, "invalid hex digit '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// Converts a single sRGB encoded channel to linear light.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a single linear channel to sRGB encoding.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 impl Color {
+    pub const BLACK: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+    pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+    pub const TRANSPARENT: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+
     pub fn new(r: f32, g: f32, b: f32, a: f32) -> Color {
         Color { r, g, b, a }
     }
 
+    /// Creates an opaque color from `0xRRGGBB`.
     pub fn from_hex(hex: u32) -> Color {
         let r = ((hex >> 16) & 0xFF) as f32 / 255.0;
         let g = ((hex >> 8) & 0xFF) as f32 / 255.0;
@@ -18,6 +65,166 @@ impl Color {
         Color::new(r, g, b, 1.0)
     }
 
+    /// Creates a color from `0xRRGGBBAA`.
+    pub fn from_hex_rgba(hex: u32) -> Color {
+        let mut color = Color::from_hex(hex >> 8);
+        color.a = (hex & 0xFF) as f32 / 255.0;
+        color
+    }
+
+    /// Returns the color as `0xRRGGBBAA`, rounding and clamping each channel to a byte.
+    pub fn to_hex_rgba(&self) -> u32 {
+        let byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+        (byte(self.r) << 24) | (byte(self.g) << 16) | (byte(self.b) << 8) | byte(self.a)
+    }
+
+    /// Parses `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`. The leading `#` is optional.
+    pub fn parse(text: &str) -> Result<Color, ColorParseError> {
+        let digits = text.trim();
+        let digits = digits.strip_prefix('#').unwrap_or(digits);
+
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ColorParseError::InvalidDigit(c));
+        }
+
+        let value = |range: std::ops::Range<usize>| u32::from_str_radix(&digits[range], 16).unwrap();
+        match digits.len() {
+            3 | 4 => {
+                // Each digit is doubled, so `#f80` is `#ff8800`.
+                let hex = (0..digits.len()).fold(0, |acc, i| (acc << 8) | (value(i..i + 1) * 0x11));
+                Ok(if digits.len() == 3 { Color::from_hex(hex) } else { Color::from_hex_rgba(hex) })
+            }
+            6 => Ok(Color::from_hex(value(0..6))),
+            8 => Ok(Color::from_hex_rgba(value(0..8))),
+            len => Err(ColorParseError::InvalidLength(len)),
+        }
+    }
+
+    /// Creates a color from hue in degrees, and saturation and value in `[0, 1]`.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+        let chroma = value * saturation;
+        Color::from_hue_chroma(hue, chroma, value - chroma, alpha)
+    }
+
+    /// Returns `(hue, saturation, value)`, with hue in degrees in `[0, 360)`.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let chroma = max - self.r.min(self.g).min(self.b);
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        (self.hue(max, chroma), saturation, max)
+    }
+
+    /// Creates a color from hue in degrees, and saturation and lightness in `[0, 1]`.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Color::from_hue_chroma(hue, chroma, lightness - chroma / 2.0, alpha)
+    }
+
+    /// Returns `(hue, saturation, lightness)`, with hue in degrees in `[0, 360)`.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (self.hue(max, chroma), saturation, lightness)
+    }
+
+    fn from_hue_chroma(hue: f32, chroma: f32, offset: f32, alpha: f32) -> Color {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color::new(r + offset, g + offset, b + offset, alpha)
+    }
+
+    fn hue(&self, max: f32, chroma: f32) -> f32 {
+        if chroma == 0.0 {
+            return 0.0;
+        }
+        let sector = if max == self.r {
+            ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            (self.b - self.r) / chroma + 2.0
+        } else {
+            (self.r - self.g) / chroma + 4.0
+        };
+        (sector * 60.0).rem_euclid(360.0)
+    }
+
+    /// Converts the color channels from sRGB to linear. Alpha is already linear and kept as is.
+    pub fn to_linear(&self) -> Color {
+        Color::new(srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
+
+    /// Converts the color channels from linear to sRGB. Alpha is kept as is.
+    pub fn to_srgb(&self) -> Color {
+        Color::new(linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a)
+    }
+
+    /// Multiplies the color channels by alpha.
+    pub fn premultiplied(&self) -> Color {
+        Color::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Reverses `premultiplied`. A fully transparent color stays black.
+    pub fn unpremultiplied(&self) -> Color {
+        if self.a == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        Color::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    pub fn with_alpha(&self, a: f32) -> Color {
+        Color { a, ..*self }
+    }
+
+    /// Interpolates every channel. Interpolate linear colors to mix light physically correctly.
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    /// Composites `self` over `background` with the Porter-Duff "over" operator.
+    pub fn blend_over(&self, background: &Color) -> Color {
+        let a = self.a + background.a * (1.0 - self.a);
+        if a == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let source = self.premultiplied();
+        let destination = background.premultiplied();
+        let keep = 1.0 - self.a;
+        Color::new(
+            source.r + destination.r * keep,
+            source.g + destination.g * keep,
+            source.b + destination.b * keep,
+            a,
+        )
+        .unpremultiplied()
+    }
+
+    pub fn clamped(&self) -> Color {
+        Color::new(self.r.clamp(0.0, 1.0), self.g.clamp(0.0, 1.0), self.b.clamp(0.0, 1.0), self.a.clamp(0.0, 1.0))
+    }
+
+    pub fn to_rgb_array(&self) -> [f32; 3] {
+        [self.r, self.g, self.b]
+    }
+
     pub fn to_array(&self) -> [f32; 4] {
         [self.r, self.g, self.b, self.a]
     }
@@ -25,4 +232,157 @@ impl Color {
     pub fn to_vec(&self) -> Vec<f32> {
         vec![self.r, self.g, self.b, self.a]
     }
-}
\ No newline at end of file
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::WHITE
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Color::parse(text)
+    }
+}
+
+/// Multiplies channel by channel, e.g. to tint a texture color.
+impl std::ops::Mul for Color {
+    type Output = Color;
+
+    fn mul(self, other: Color) -> Color {
+        Color::new(self.r * other.r, self.g * other.g, self.b * other.b, self.a * other.a)
+    }
+}
+
+/// Scales the color channels, leaving alpha untouched, e.g. for light intensity.
+impl std::ops::Mul<f32> for Color {
+    type Output = Color;
+
+    fn mul(self, other: f32) -> Color {
+        Color::new(self.r * other, self.g * other, self.b * other, self.a)
+    }
+}
+
+/// An ordered set of colors that can be indexed or sampled as a gradient.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Palette {
+    pub colors: Vec<Color>,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<Color>) -> Palette {
+        Palette { colors }
+    }
+
+    /// Builds a palette from hex strings such as `"#264653"`.
+    pub fn parse(colors: &[&str]) -> Result<Palette, ColorParseError> {
+        Ok(Palette::new(colors.iter().map(|text| Color::parse(text)).collect::<Result<_, _>>()?))
+    }
+
+    /// `count` colors with evenly spaced hues at the given saturation and value.
+    pub fn hue_wheel(count: usize, saturation: f32, value: f32) -> Palette {
+        Palette::new((0..count).map(|i| Color::from_hsv(i as f32 * 360.0 / count as f32, saturation, value, 1.0)).collect())
+    }
+
+    /// Samples the palette as an evenly spaced gradient, `t` in `[0, 1]`. Interpolation happens
+    /// in linear space and the result is converted back to sRGB.
+    pub fn sample(&self, t: f32) -> Color {
+        match self.colors.len() {
+            0 => Color::BLACK,
+            1 => self.colors[0],
+            len => {
+                let position = t.clamp(0.0, 1.0) * (len - 1) as f32;
+                let index = (position as usize).min(len - 2);
+                let from = self.colors[index].to_linear();
+                let to = self.colors[index + 1].to_linear();
+                from.lerp(&to, position - index as f32).to_srgb()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Color, b: Color) {
+        for (x, y) in a.to_array().iter().zip(b.to_array().iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(Color::from_hex(0xFF8000), Color::new(1.0, 128.0 / 255.0, 0.0, 1.0));
+        assert_eq!(Color::from_hex_rgba(0xFF800040).a, 64.0 / 255.0);
+        assert_eq!(Color::from_hex_rgba(0x12345678).to_hex_rgba(), 0x12345678);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Color::parse("#FF8000").unwrap(), Color::from_hex(0xFF8000));
+        assert_eq!("#ff800040".parse::<Color>().unwrap(), Color::from_hex_rgba(0xFF800040));
+        assert_eq!(Color::parse("f80").unwrap(), Color::from_hex(0xFF8800));
+        assert_eq!(Color::parse("#f808").unwrap(), Color::from_hex_rgba(0xFF880088));
+        assert_eq!(Color::parse("#12345"), Err(ColorParseError::InvalidLength(5)));
+        assert_eq!(Color::parse("#12345g"), Err(ColorParseError::InvalidDigit('g')));
+    }
+
+    #[test]
+    fn test_srgb_round_trip() {
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        assert!((srgb_to_linear(0.5) - 0.214_041).abs() < 1e-5);
+        for i in 0..=255 {
+            let value = i as f32 / 255.0;
+            assert!((linear_to_srgb(srgb_to_linear(value)) - value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_hsv() {
+        assert_close(Color::from_hsv(0.0, 1.0, 1.0, 1.0), Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_close(Color::from_hsv(120.0, 1.0, 1.0, 1.0), Color::new(0.0, 1.0, 0.0, 1.0));
+        assert_close(Color::from_hsv(-120.0, 1.0, 0.5, 1.0), Color::new(0.0, 0.0, 0.5, 1.0));
+        let color = Color::new(0.2, 0.6, 0.4, 1.0);
+        let (h, s, v) = color.to_hsv();
+        assert!((h - 150.0).abs() < 1e-3);
+        assert_close(Color::from_hsv(h, s, v, 1.0), color);
+    }
+
+    #[test]
+    fn test_hsl() {
+        assert_close(Color::from_hsl(240.0, 1.0, 0.5, 1.0), Color::new(0.0, 0.0, 1.0, 1.0));
+        assert_close(Color::from_hsl(0.0, 0.0, 0.5, 1.0), Color::new(0.5, 0.5, 0.5, 1.0));
+        let color = Color::new(0.9, 0.3, 0.7, 0.5);
+        let (h, s, l) = color.to_hsl();
+        assert_close(Color::from_hsl(h, s, l, 0.5), color);
+    }
+
+    #[test]
+    fn test_premultiplied_and_blend() {
+        let color = Color::new(1.0, 0.5, 0.0, 0.5);
+        assert_eq!(color.premultiplied(), Color::new(0.5, 0.25, 0.0, 0.5));
+        assert_close(color.premultiplied().unpremultiplied(), color);
+
+        assert_close(color.blend_over(&Color::BLACK), Color::new(0.5, 0.25, 0.0, 1.0));
+        assert_eq!(Color::WHITE.blend_over(&color), Color::WHITE);
+        assert_eq!(Color::TRANSPARENT.blend_over(&Color::TRANSPARENT), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn test_lerp_and_palette() {
+        assert_eq!(Color::BLACK.lerp(&Color::WHITE, 0.5), Color::new(0.5, 0.5, 0.5, 1.0));
+
+        let palette = Palette::parse(&["#000000", "#ffffff"]).unwrap();
+        assert_eq!(palette.sample(0.0), Color::BLACK);
+        assert_close(palette.sample(1.0), Color::WHITE);
+        // Halfway in linear light is brighter than halfway in sRGB.
+        assert!(palette.sample(0.5).r > 0.7);
+        assert_eq!(Palette::hue_wheel(6, 1.0, 1.0).colors.len(), 6);
+        assert!(Palette::parse(&["#000000", "oops"]).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use glium::{Display, IndexBuffer, VertexBuffer};
+use glium::glutin::surface::WindowSurface;
+use glium::index::PrimitiveType;
+
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// The GPU resident copy of a `Mesh`.
+pub struct GpuMesh {
+    pub vertex_buffer: VertexBuffer<Vertex>,
+    pub index_buffer: IndexBuffer<u32>,
+}
+
+impl GpuMesh {
+    pub fn new(display: &Display<WindowSurface>, mesh: &Mesh) -> Self {
+        GpuMesh {
+            vertex_buffer: VertexBuffer::new(display, &mesh.vertices).unwrap(),
+            index_buffer: IndexBuffer::new(display, PrimitiveType::TrianglesList, &mesh.indices).unwrap(),
+        }
+    }
+}
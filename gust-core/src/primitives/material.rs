@@ -0,0 +1,120 @@
+use crate::objects::intermediaries::wavefront_material::{TextureMap, WavefrontMaterial};
+use crate::primitives::color::Color;
+use crate::storages::texture_storage::TextureId;
+
+/// Surface properties used when shading a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub ambient: Color,
+    /// Multiplied with the diffuse texture. Its alpha is the opacity of the surface.
+    pub diffuse: Color,
+    pub specular: Color,
+    pub emissive: Color,
+    /// The specular exponent; higher values give smaller, sharper highlights.
+    pub shininess: f32,
+    pub diffuse_texture: Option<TextureId>,
+    pub normal_texture: Option<TextureId>,
+}
+
+impl Material {
+    /// A plain white material.
+    pub fn new() -> Self {
+        Material {
+            ambient: Color::BLACK,
+            diffuse: Color::WHITE,
+            specular: Color::WHITE,
+            emissive: Color::BLACK,
+            shininess: 16.0,
+            diffuse_texture: None,
+            normal_texture: None,
+        }
+    }
+
+    /// A white material showing the given texture.
+    pub fn textured(texture_id: TextureId) -> Self {
+        Material {
+            diffuse_texture: Some(texture_id),
+            ..Material::new()
+        }
+    }
+
+    /// Converts a parsed MTL material. `load_texture` is called for each referenced texture and
+    /// decides how paths are resolved and loaded.
+    ///
+    /// The shininess is clamped to `[1, 1024]` like glTF materials. Without an `Ns` line it is 0,
+    /// which would light the whole surface with full specular. A shininess that isn't a finite
+    /// number gets the default of `Material::new`.
+    pub fn from_wavefront<F>(material: &WavefrontMaterial, mut load_texture: F) -> Self
+    where
+        F: FnMut(&TextureMap) -> Option<TextureId>,
+    {
+        let [r, g, b] = material.diffuse;
+        Material {
+            ambient: Material::opaque(material.ambient),
+            diffuse: Color::new(r, g, b, material.dissolve),
+            specular: Material::opaque(material.specular),
+            emissive: Material::opaque(material.emissive),
+            shininess: if material.shininess.is_finite() {
+                material.shininess.clamp(1.0, 1024.0)
+            } else {
+                Material::new().shininess
+            },
+            diffuse_texture: material.diffuse_map.as_ref().and_then(&mut load_texture),
+            normal_texture: material
+                .normal_map
+                .as_ref()
+                .or(material.bump_map.as_ref())
+                .and_then(&mut load_texture),
+        }
+    }
+
+    fn opaque([r, g, b]: [f32; 3]) -> Color {
+        Color::new(r, g, b, 1.0)
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_wavefront() {
+        let mut wavefront = WavefrontMaterial::new("brick");
+        wavefront.diffuse = [0.5, 0.25, 0.0];
+        wavefront.dissolve = 0.5;
+        wavefront.shininess = 32.0;
+        wavefront.diffuse_map = Some(TextureMap::new("brick.png"));
+        wavefront.bump_map = Some(TextureMap::new("brick_bump.png"));
+
+        let mut requested = Vec::new();
+        let material = Material::from_wavefront(&wavefront, |map| {
+            requested.push(map.path.clone());
+            Some(TextureId(requested.len() as u32))
+        });
+
+        assert_eq!(material.diffuse, Color::new(0.5, 0.25, 0.0, 0.5));
+        assert_eq!(material.shininess, 32.0);
+        assert_eq!(material.diffuse_texture, Some(TextureId(1)));
+        assert_eq!(material.normal_texture, Some(TextureId(2)));
+        assert_eq!(requested, vec!["brick.png".to_string(), "brick_bump.png".to_string()]);
+    }
+
+    #[test]
+    fn test_from_wavefront_without_shininess() {
+        let material = Material::from_wavefront(&WavefrontMaterial::new("plain"), |_| None);
+        assert_eq!(material.shininess, 1.0);
+
+        let mut wavefront = WavefrontMaterial::new("mirror");
+        wavefront.shininess = 5000.0;
+        assert_eq!(Material::from_wavefront(&wavefront, |_| None).shininess, 1024.0);
+
+        wavefront.shininess = f32::NAN;
+        assert_eq!(Material::from_wavefront(&wavefront, |_| None).shininess, 16.0);
+    }
+}
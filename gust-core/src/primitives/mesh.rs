@@ -1,40 +1,153 @@
-use crate::objects::intermediaries::wavefront_object::WavefrontObject;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use gust_math::vectors::vect3::Vect3;
+
+use crate::objects::intermediaries::ply_object::PlyObject;
+use crate::objects::intermediaries::stl_object::StlObject;
+use crate::objects::intermediaries::wavefront_object::{FaceGroup, WavefrontObject};
 use crate::primitives::vertex::Vertex;
 
-#[derive(Debug)]
+/// An indexed triangle list: every three entries of `indices` form a triangle of `vertices`.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Mesh {
-    pub triangles: Vec<[Vertex; 3]>,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+/// The bit patterns of a vertex, so identical vertices can be found with a hash map.
+type VertexKey = ([u32; 3], [u32; 3], [u32; 2], [u32; 4]);
+
+fn vertex_key(vertex: &Vertex) -> VertexKey {
+    (
+        vertex.position.map(f32::to_bits),
+        vertex.normal.map(f32::to_bits),
+        vertex.tex_coords.map(f32::to_bits),
+        vertex.tangent.map(f32::to_bits),
+    )
 }
 
 impl Mesh {
     pub fn new() -> Self {
         Mesh {
-            triangles: Vec::new(),
+            vertices: Vec::new(),
+            indices: Vec::new(),
         }
     }
 
+    /// Builds an indexed mesh from separate triangles, merging vertices that are identical.
+    pub fn from_triangles(triangles: &[[Vertex; 3]]) -> Self {
+        let mut builder = MeshBuilder::new();
+        for triangle in triangles {
+            builder.add_triangle(triangle);
+        }
+        builder.build()
+    }
+
     pub fn from_wavefront(wavefront_object: WavefrontObject) -> Self {
-        let mut polygons = Vec::new();
-        for face in wavefront_object.faces {
-            let mut vertices = Vec::new();
-
-            for vertex_indices in face {
-                let vertex = Vertex {
-                    position: wavefront_object.vertices[(vertex_indices[0] - 1) as usize],
-                    tex_coords: wavefront_object.tex_coords[(vertex_indices[1] - 1) as usize],
-                    normal: wavefront_object.normals[(vertex_indices[2] - 1) as usize],
-                };
-                vertices.push(vertex);
+        Mesh::from_wavefront_faces(&wavefront_object, 0..wavefront_object.faces.len())
+    }
+
+    /// Builds one mesh per face group, e.g. to spawn each object of an OBJ file as its own entity
+    /// or to draw each material separately.
+    pub fn from_wavefront_groups(wavefront_object: &WavefrontObject) -> Vec<(FaceGroup, Mesh)> {
+        wavefront_object
+            .face_groups
+            .iter()
+            .map(|group| (group.clone(), Mesh::from_wavefront_faces(wavefront_object, group.faces.clone())))
+            .collect()
+    }
+
+    /// Builds a mesh from a range of the faces, only keeping the vertices those faces use.
+    pub fn from_wavefront_faces(wavefront_object: &WavefrontObject, faces: Range<usize>) -> Self {
+        let mut builder = MeshBuilder::new();
+        for face in &wavefront_object.faces[faces] {
+            // Faces without normals get the flat normal of the polygon.
+            let positions: Vec<[f32; 3]> = face
+                .iter()
+                .map(|face_vertex| wavefront_object.vertices[face_vertex.position as usize])
+                .collect();
+            let face_normal = Mesh::polygon_normal(&positions);
+            let mut polygon = Vec::new();
+
+            for face_vertex in face {
+                let vertex = Vertex::new(
+                    wavefront_object.vertices[face_vertex.position as usize],
+                    face_vertex.normal.map_or(face_normal, |index| wavefront_object.normals[index as usize]),
+                    face_vertex.tex_coord.map_or([0.0, 0.0], |index| wavefront_object.tex_coords[index as usize]),
+                );
+                polygon.push(vertex);
+            }
+
+            for triangle in Mesh::polygon_to_triangles(polygon) {
+                builder.add_triangle(&triangle);
             }
-            polygons.push(vertices);
         }
 
-        let mut triangles = Vec::new();
-        for polygon in polygons {
-            triangles.append(&mut Mesh::polygon_to_triangles(polygon));
+        builder.build()
+    }
+
+    /// Builds a flat shaded mesh. Missing facet normals are computed from the vertices.
+    pub fn from_stl(stl_object: &StlObject) -> Self {
+        let mut builder = MeshBuilder::new();
+        for triangle in &stl_object.triangles {
+            let normal = match triangle.normal {
+                [0.0, 0.0, 0.0] => Mesh::polygon_normal(&triangle.vertices),
+                normal => normal,
+            };
+            let polygon = triangle
+                .vertices
+                .iter()
+                .map(|&position| Vertex::new(position, normal, [0.0, 0.0]))
+                .collect();
+
+            for triangle in Mesh::polygon_to_triangles(polygon) {
+                builder.add_triangle(&triangle);
+            }
         }
+        builder.build()
+    }
+
+    /// Vertices without normals get the flat normal of each face they are used in. Faces with
+    /// fewer than three vertices or with indices past `vertices` are skipped, `PlyObject::parse`
+    /// never returns those.
+    pub fn from_ply(ply_object: &PlyObject) -> Self {
+        let mut builder = MeshBuilder::new();
+        for face in &ply_object.faces {
+            if face.len() < 3 || face.iter().any(|&index| index as usize >= ply_object.vertices.len()) {
+                continue;
+            }
+            let positions: Vec<[f32; 3]> = face.iter().map(|&index| ply_object.vertices[index as usize]).collect();
+            let face_normal = Mesh::polygon_normal(&positions);
+            let polygon = face
+                .iter()
+                .map(|&index| {
+                    Vertex::new(
+                        ply_object.vertices[index as usize],
+                        ply_object.normals.get(index as usize).copied().unwrap_or(face_normal),
+                        ply_object.tex_coords.get(index as usize).copied().unwrap_or([0.0, 0.0]),
+                    )
+                })
+                .collect();
+
+            for triangle in Mesh::polygon_to_triangles(polygon) {
+                builder.add_triangle(&triangle);
+            }
+        }
+        builder.build()
+    }
 
-        Mesh { triangles }
+    /// Newell's method, which also copes with slightly non-planar polygons. Polygons without an
+    /// area get a normal pointing up along Z.
+    fn polygon_normal(positions: &[[f32; 3]]) -> [f32; 3] {
+        let mut normal = Vect3::new(0.0, 0.0, 0.0);
+        for (i, &current) in positions.iter().enumerate() {
+            let current = Vect3::from(current);
+            let next = Vect3::from(positions[(i + 1) % positions.len()]);
+            normal += (current - next).cross(&(current + next));
+        }
+        let normal = normal.try_normalized().unwrap_or(Vect3::new(0.0, 0.0, 1.0));
+        [normal.x, normal.y, normal.z]
     }
 
     fn polygon_to_triangles(polygon: Vec<Vertex>) -> Vec<[Vertex; 3]> {
@@ -42,9 +155,185 @@ impl Mesh {
 
         // it is important that every triangle has the same winding order
         for i in 1..polygon.len() - 1 {
-            triangles.push([polygon[0].clone(), polygon[i + 1].clone(), polygon[i].clone()]);
+            triangles.push([polygon[0], polygon[i + 1], polygon[i]]);
         }
 
         triangles
     }
-}
\ No newline at end of file
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Iterates over the triangles with their vertices resolved.
+    pub fn triangles(&self) -> impl Iterator<Item = [Vertex; 3]> + '_ {
+        self.indices
+            .chunks_exact(3)
+            .map(|triangle| [0, 1, 2].map(|corner| self.vertices[triangle[corner] as usize]))
+    }
+}
+
+impl Default for Mesh {
+    fn default() -> Self {
+        Mesh::new()
+    }
+}
+
+/// Accumulates triangles into a `Mesh`, reusing the index of any vertex seen before.
+struct MeshBuilder {
+    mesh: Mesh,
+    lookup: HashMap<VertexKey, u32>,
+}
+
+impl MeshBuilder {
+    fn new() -> Self {
+        MeshBuilder {
+            mesh: Mesh::new(),
+            lookup: HashMap::new(),
+        }
+    }
+
+    fn add_vertex(&mut self, vertex: &Vertex) -> u32 {
+        let vertices = &mut self.mesh.vertices;
+        *self.lookup.entry(vertex_key(vertex)).or_insert_with(|| {
+            vertices.push(*vertex);
+            (vertices.len() - 1) as u32
+        })
+    }
+
+    fn add_triangle(&mut self, triangle: &[Vertex; 3]) {
+        for vertex in triangle {
+            let index = self.add_vertex(vertex);
+            self.mesh.indices.push(index);
+        }
+    }
+
+    fn build(self) -> Mesh {
+        self.mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::intermediaries::stl_object::StlTriangle;
+    use crate::objects::intermediaries::wavefront_object::FaceVertex;
+
+    use super::*;
+
+    fn face_vertex(position: u32, tex_coord: Option<u32>, normal: Option<u32>) -> FaceVertex {
+        FaceVertex { position, tex_coord, normal }
+    }
+
+    fn quad() -> WavefrontObject {
+        WavefrontObject {
+            vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            tex_coords: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            normals: vec![[0.0, 0.0, 1.0]],
+            faces: vec![(0..4).map(|i| face_vertex(i, Some(i), Some(0))).collect()],
+            ..WavefrontObject::default()
+        }
+    }
+
+    #[test]
+    fn test_from_wavefront_shares_vertices() {
+        let mesh = Mesh::from_wavefront(quad());
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.index_count(), 6);
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 3, 1]);
+    }
+
+    #[test]
+    fn test_vertices_differing_in_one_attribute_are_kept() {
+        let mut object = quad();
+        object.normals.push([0.0, 0.0, -1.0]);
+        object.faces.push((0..3).map(|i| face_vertex(i, Some(i), Some(1))).collect());
+        let mesh = Mesh::from_wavefront(object);
+        assert_eq!(mesh.vertex_count(), 7);
+        assert_eq!(mesh.triangle_count(), 3);
+    }
+
+    #[test]
+    fn test_missing_attributes_get_defaults() {
+        let mut object = quad();
+        object.faces = vec![(0..4).map(|i| face_vertex(i, None, None)).collect()];
+        let mesh = Mesh::from_wavefront(object);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0] && vertex.tex_coords == [0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_from_wavefront_groups() {
+        let mut object = quad();
+        object.faces.push((1..4).map(|i| face_vertex(i, Some(i), Some(0))).collect());
+        object.face_groups = vec![
+            FaceGroup { material: Some("a".to_string()), faces: 0..1, ..FaceGroup::default() },
+            FaceGroup { material: Some("b".to_string()), faces: 1..2, ..FaceGroup::default() },
+        ];
+        let meshes = Mesh::from_wavefront_groups(&object);
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[0].1, Mesh::from_wavefront_faces(&object, 0..1));
+        assert_eq!((meshes[1].1.vertex_count(), meshes[1].1.triangle_count()), (3, 1));
+        assert_eq!(meshes[1].0.material.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_from_triangles_round_trip() {
+        let mesh = Mesh::from_wavefront(quad());
+        let triangles: Vec<[Vertex; 3]> = mesh.triangles().collect();
+        assert_eq!(Mesh::from_triangles(&triangles), mesh);
+    }
+
+    #[test]
+    fn test_from_stl() {
+        let stl = StlObject {
+            name: String::new(),
+            triangles: vec![
+                StlTriangle { normal: [0.0, 0.0, 0.0], vertices: [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] },
+                StlTriangle { normal: [0.0, 0.0, 1.0], vertices: [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]] },
+            ],
+        };
+        let mesh = Mesh::from_stl(&stl);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+        // Reversed like OBJ faces
+        let first = mesh.triangles().next().unwrap().map(|vertex| vertex.position);
+        assert_eq!(first, [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_from_ply_matches_wavefront() {
+        let ply = PlyObject {
+            vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            tex_coords: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 4],
+            faces: vec![vec![0, 1, 2, 3]],
+            ..PlyObject::default()
+        };
+        assert_eq!(Mesh::from_ply(&ply), Mesh::from_wavefront(quad()));
+
+        let without_normals = PlyObject { normals: Vec::new(), ..ply };
+        assert!(Mesh::from_ply(&without_normals).vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_from_ply_skips_invalid_faces() {
+        let ply = PlyObject {
+            vertices: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]],
+            faces: vec![vec![0, 1, 7], vec![0, 1], vec![0, 1, 2]],
+            ..PlyObject::default()
+        };
+        let mesh = Mesh::from_ply(&ply);
+        assert_eq!(mesh.triangle_count(), 1);
+        // The remaining face lies on a line, so it has no normal of its own.
+        assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use gust_math::vectors::vect3::Vect3;
+
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// Changing the normals invalidates the tangents, so they are cleared and have to be computed
+/// again afterwards.
+impl Mesh {
+    /// Gives every triangle its own vertices with the normal of its face, for a faceted look.
+    pub fn compute_flat_normals(&mut self) {
+        let triangles: Vec<[Vertex; 3]> = self
+            .triangles()
+            .map(|triangle| {
+                let normal = triangle_normal(&triangle);
+                triangle.map(|vertex| match normal {
+                    Some(normal) => Vertex::new(vertex.position, [normal.x, normal.y, normal.z], vertex.tex_coords),
+                    None => Vertex::new(vertex.position, vertex.normal, vertex.tex_coords),
+                })
+            })
+            .collect();
+        *self = Mesh::from_triangles(&triangles);
+    }
+
+    /// Averages the normals of the faces around each position, weighted by the angle of their
+    /// corner so the result doesn't depend on how a surface is split into triangles. Faces at
+    /// an angle above `crease_angle` (in radians) to each other don't share normals, which keeps
+    /// their edge hard. Vertices only differing in their normal are merged.
+    pub fn compute_smooth_normals(&mut self, crease_angle: f32) {
+        let triangles: Vec<[Vertex; 3]> = self.triangles().collect();
+        let normals: Vec<Option<Vect3>> = triangles.iter().map(triangle_normal).collect();
+
+        // The weighted face normals at each position, found by bit pattern so vertices that are
+        // only split for their texture coordinates are smoothed as well.
+        let mut corners: HashMap<[u32; 3], Vec<(Vect3, f32)>> = HashMap::new();
+        for (triangle, normal) in triangles.iter().zip(&normals) {
+            if let Some(normal) = normal {
+                for (corner, vertex) in triangle.iter().enumerate() {
+                    let angle = corner_angle(triangle, corner);
+                    corners.entry(vertex.position.map(f32::to_bits)).or_default().push((*normal, angle));
+                }
+            }
+        }
+
+        let min_cos = crease_angle.cos();
+        let triangles: Vec<[Vertex; 3]> = triangles
+            .iter()
+            .zip(&normals)
+            .map(|(triangle, face_normal)| {
+                triangle.map(|vertex| {
+                    let normal = face_normal.and_then(|face_normal| {
+                        corners[&vertex.position.map(f32::to_bits)]
+                            .iter()
+                            .filter(|(normal, _)| normal.dot(&face_normal) >= min_cos)
+                            .fold(Vect3::zeros(), |sum, &(normal, angle)| sum + normal * angle)
+                            .try_normalized()
+                    });
+                    let normal = normal.map_or(vertex.normal, |normal| [normal.x, normal.y, normal.z]);
+                    Vertex::new(vertex.position, normal, vertex.tex_coords)
+                })
+            })
+            .collect();
+        *self = Mesh::from_triangles(&triangles);
+    }
+
+    /// Computes tangents the way MikkTSpace does, so normal maps baked with it show up right:
+    /// the tangents of the faces around a vertex are weighted by their corner angle and made
+    /// perpendicular to the vertex normal. Vertices used by faces with mirrored texture
+    /// coordinates are split, since each side needs its own bitangent sign. Vertices without
+    /// usable texture coordinates get an arbitrary tangent.
+    pub fn compute_tangents(&mut self) {
+        // Summed tangent and bitangent per vertex and bitangent sign
+        let mut frames: HashMap<(u32, bool), (Vect3, Vect3)> = HashMap::new();
+        let mut mirrored = vec![false; self.indices.len()];
+
+        for (index, triangle) in self.triangles().enumerate() {
+            let Some((tangent, bitangent)) = triangle_tangents(&triangle) else {
+                continue;
+            };
+            let Some(face_normal) = triangle_normal(&triangle) else {
+                continue;
+            };
+            let flipped = face_normal.cross(&tangent).dot(&bitangent) < 0.0;
+
+            for corner in 0..3 {
+                let normal = Vect3::from(triangle[corner].normal);
+                let project = |vector: Vect3| (vector - normal * normal.dot(&vector)).try_normalized();
+                let (Some(tangent), Some(bitangent)) = (project(tangent), project(bitangent)) else {
+                    continue;
+                };
+                let angle = corner_angle(&triangle, corner);
+                let frame = frames.entry((self.indices[index * 3 + corner], flipped)).or_insert((Vect3::zeros(), Vect3::zeros()));
+                frame.0 += tangent * angle;
+                frame.1 += bitangent * angle;
+                mirrored[index * 3 + corner] = flipped;
+            }
+        }
+
+        // Mirrored corners of vertices also used unmirrored move to a copy of the vertex.
+        let mut copies: HashMap<u32, u32> = HashMap::new();
+        for (corner, index) in self.indices.iter_mut().enumerate() {
+            if mirrored[corner] && frames.contains_key(&(*index, false)) {
+                let vertices = &mut self.vertices;
+                let copy = *copies.entry(*index).or_insert_with(|| {
+                    vertices.push(vertices[*index as usize]);
+                    vertices.len() as u32 - 1
+                });
+                let frame = frames[&(*index, true)];
+                frames.entry((copy, true)).or_insert(frame);
+                *index = copy;
+            }
+        }
+
+        for (index, vertex) in self.vertices.iter_mut().enumerate() {
+            let normal = Vect3::from(vertex.normal);
+            let frame = [false, true]
+                .into_iter()
+                .find_map(|flipped| frames.get(&(index as u32, flipped)).map(|&frame| (frame, flipped)));
+
+            let (tangent, sign) = match frame {
+                Some(((tangent, bitangent), flipped)) => {
+                    let sign = if flipped { -1.0 } else { 1.0 };
+                    let tangent = (tangent - normal * normal.dot(&tangent))
+                        .try_normalized()
+                        // The bitangent still gives a usable direction when the tangents cancel out.
+                        .or_else(|| (bitangent.cross(&normal) * sign).try_normalized())
+                        .unwrap_or_else(|| any_perpendicular(normal));
+                    (tangent, sign)
+                }
+                None => (any_perpendicular(normal), 1.0),
+            };
+            vertex.tangent = [tangent.x, tangent.y, tangent.z, sign];
+        }
+    }
+}
+
+/// The normal of the front face, taking the reversed winding of stored triangles into account.
+fn triangle_normal(triangle: &[Vertex; 3]) -> Option<Vect3> {
+    let [a, b, c] = triangle.map(|vertex| Vect3::from(vertex.position));
+    (c - a).cross(&(b - a)).try_normalized()
+}
+
+/// The angle between the two edges meeting at the corner, 0 for degenerate corners.
+fn corner_angle(triangle: &[Vertex; 3], corner: usize) -> f32 {
+    let position = |offset: usize| Vect3::from(triangle[(corner + offset) % 3].position);
+    match ((position(1) - position(0)).try_normalized(), (position(2) - position(0)).try_normalized()) {
+        (Some(first), Some(second)) => first.dot(&second).clamp(-1.0, 1.0).acos(),
+        _ => 0.0,
+    }
+}
+
+/// The directions in which `u` and `v` increase over the triangle, or `None` if its texture
+/// coordinates don't span an area.
+fn triangle_tangents(triangle: &[Vertex; 3]) -> Option<(Vect3, Vect3)> {
+    let [a, b, c] = triangle.map(|vertex| Vect3::from(vertex.position));
+    let [uv_a, uv_b, uv_c] = triangle.map(|vertex| vertex.tex_coords);
+    let (edge_1, edge_2) = (b - a, c - a);
+    let ([du_1, dv_1], [du_2, dv_2]) = ([uv_b[0] - uv_a[0], uv_b[1] - uv_a[1]], [uv_c[0] - uv_a[0], uv_c[1] - uv_a[1]]);
+
+    let determinant = du_1 * dv_2 - du_2 * dv_1;
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+    let tangent = (edge_1 * dv_2 - edge_2 * dv_1) / determinant;
+    let bitangent = (edge_2 * du_1 - edge_1 * du_2) / determinant;
+    Some((tangent, bitangent))
+}
+
+fn any_perpendicular(normal: Vect3) -> Vect3 {
+    let axis = match normal.x.abs() < 0.9 {
+        true => Vect3::new(1.0, 0.0, 0.0),
+        false => Vect3::new(0.0, 1.0, 0.0),
+    };
+    (axis - normal * normal.dot(&axis)).try_normalized().unwrap_or(axis)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    fn vect(array: [f32; 3]) -> Vect3 {
+        Vect3::from(array)
+    }
+
+    fn close(a: Vect3, b: Vect3) -> bool {
+        (a - b).norm() < 1e-5
+    }
+
+    #[test]
+    fn test_flat_normals_of_smooth_sphere() {
+        let mut sphere = Mesh::icosphere(1.0, 1);
+        sphere.compute_flat_normals();
+        assert_eq!(sphere.vertex_count(), sphere.index_count());
+        for triangle in sphere.triangles() {
+            let center = triangle.iter().fold(Vect3::zeros(), |sum, vertex| sum + vect(vertex.position)) / 3.0;
+            assert!(triangle.iter().all(|vertex| vertex.normal == triangle[0].normal));
+            assert!(vect(triangle[0].normal).dot(&center) > 0.9 * center.norm());
+        }
+    }
+
+    #[test]
+    fn test_smooth_normals_respect_crease_angle() {
+        // Each face of the cube is split into two triangles, which only leaves the corners
+        // pointing diagonally outwards if the normals are weighted by angle.
+        let mut cube = Mesh::cube(2.0);
+        cube.compute_smooth_normals(PI);
+        for vertex in &cube.vertices {
+            assert!(close(vect(vertex.normal), vect(vertex.position).normalized()));
+        }
+
+        // Faces at right angles stay hard below the crease angle.
+        let mut cube = Mesh::cube(2.0);
+        cube.compute_smooth_normals(FRAC_PI_2 - 0.1);
+        assert!(cube.triangles().eq(Mesh::cube(2.0).triangles()));
+    }
+
+    #[test]
+    fn test_tangents_follow_texture_coordinates() {
+        let mut plane = Mesh::plane(2.0, 2.0, 2, 2);
+        plane.compute_tangents();
+        assert!(plane.vertices.iter().all(|vertex| vertex.tangent == [1.0, 0.0, 0.0, 1.0]));
+
+        let mut sphere = Mesh::uv_sphere(1.0, 16, 8);
+        sphere.compute_tangents();
+        for vertex in &sphere.vertices {
+            let (normal, tangent) = (vect(vertex.normal), Vect3::new(vertex.tangent[0], vertex.tangent[1], vertex.tangent[2]));
+            assert!(vertex.has_tangent());
+            assert!(normal.dot(&tangent).abs() < 1e-5);
+            assert!((tangent.norm() - 1.0).abs() < 1e-5);
+            // Around the equator `u` runs counter-clockwise seen from above, `v` upwards. The
+            // vertices on the seam only see the faces on one side, which turns them a little.
+            if vertex.position[2].abs() < 0.5 {
+                assert!(tangent.dot(&Vect3::new(-vertex.position[1], vertex.position[0], 0.0).normalized()) > 0.95);
+                assert!(normal.cross(&tangent).z * vertex.tangent[3] > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirrored_texture_coordinates_split_vertices() {
+        // Two triangles of a square sharing an edge, the second with its `u` mirrored
+        let vertex = |x: f32, y: f32, u: f32| Vertex::new([x, y, 0.0], [0.0, 0.0, 1.0], [u, y]);
+        let mut mesh = Mesh::from_triangles(&[
+            [vertex(0.0, 0.0, 0.0), vertex(0.0, 1.0, 0.0), vertex(1.0, 0.0, 1.0)],
+            [vertex(0.0, 1.0, 0.0), vertex(0.0, 0.0, 0.0), vertex(-1.0, 0.0, 1.0)],
+        ]);
+        assert_eq!(mesh.vertex_count(), 4);
+        mesh.compute_tangents();
+        assert_eq!(mesh.vertex_count(), 6);
+
+        let triangles: Vec<[Vertex; 3]> = mesh.triangles().collect();
+        assert!(triangles[0].iter().all(|vertex| vertex.tangent == [1.0, 0.0, 0.0, 1.0]));
+        assert!(triangles[1].iter().all(|vertex| vertex.tangent == [-1.0, 0.0, 0.0, -1.0]));
+    }
+
+    #[test]
+    fn test_missing_texture_coordinates_still_give_tangents() {
+        let mut mesh = Mesh::cube(1.0);
+        mesh.vertices.iter_mut().for_each(|vertex| vertex.tex_coords = [0.0, 0.0]);
+        mesh.compute_tangents();
+        for vertex in &mesh.vertices {
+            assert!(vertex.has_tangent());
+            assert!(vect(vertex.normal).dot(&Vect3::new(vertex.tangent[0], vertex.tangent[1], vertex.tangent[2])).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use gust_math::vectors::vect3::Vect3;
+
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// One row of a surface of revolution around the Z axis.
+#[derive(Debug, Clone, Copy)]
+struct ProfilePoint {
+    /// The distance from the Z axis.
+    radius: f32,
+    z: f32,
+    /// The normal in the plane through the axis: outwards from it and along Z.
+    normal: [f32; 2],
+    v: f32,
+}
+
+/// Generated shapes are centered on the origin with Z up. Every shape has normals pointing out
+/// and texture coordinates in `[0, 1]`, except for the icosphere's seam which stays below 1.5, and
+/// is wound like the meshes made by `Mesh::from_wavefront`, the order the render system's
+/// back-face culling expects.
+///
+/// Shapes going around the Z axis need at least 3 segments, fewer panics.
+impl Mesh {
+    /// An axis aligned cube with its own vertices per face, so its edges stay sharp. Every face
+    /// shows the whole texture.
+    pub fn cube(size: f32) -> Self {
+        let half = size / 2.0;
+        let mut builder = ShapeBuilder::new();
+        // The normal of each face and two axes spanning it, counter-clockwise seen from outside.
+        let faces: [[[f32; 3]; 3]; 6] = [
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            [[-1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 1.0]],
+            [[0.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+            [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+            [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            [[0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        ];
+
+        for [normal, u_axis, v_axis] in faces {
+            let [normal, u_axis, v_axis] = [normal, u_axis, v_axis].map(Vect3::from);
+            let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].map(|[u, v]| {
+                let position = (normal + u_axis * (2.0 * u - 1.0) + v_axis * (2.0 * v - 1.0)) * half;
+                builder.vertex(position, normal, [u, v])
+            });
+            builder.quad(corners);
+        }
+
+        builder.build()
+    }
+
+    /// A sphere made of `rings` rows of latitude and `segments` columns of longitude, with
+    /// its poles on the Z axis. The texture wraps around once, from the south to the north pole.
+    /// Needs at least 2 rings.
+    pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Self {
+        assert!(segments >= 3 && rings >= 2, "a uv sphere needs at least 3 segments and 2 rings");
+        let profile: Vec<ProfilePoint> = (0..=rings)
+            .map(|ring| {
+                let latitude = PI * ring as f32 / rings as f32 - FRAC_PI_2;
+                ProfilePoint {
+                    radius: radius * latitude.cos(),
+                    z: radius * latitude.sin(),
+                    normal: [latitude.cos(), latitude.sin()],
+                    v: ring as f32 / rings as f32,
+                }
+            })
+            .collect();
+
+        let mut builder = ShapeBuilder::new();
+        builder.revolve(&profile, segments);
+        builder.build()
+    }
+
+    /// A sphere made by subdividing an icosahedron, with evenly sized triangles. `subdivisions`
+    /// of 0 gives the icosahedron itself, every step has four times as many triangles.
+    pub fn icosphere(radius: f32, subdivisions: u32) -> Self {
+        let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+        let mut positions: Vec<Vect3> = [
+            [-1.0, t, 0.0],
+            [1.0, t, 0.0],
+            [-1.0, -t, 0.0],
+            [1.0, -t, 0.0],
+            [0.0, -1.0, t],
+            [0.0, 1.0, t],
+            [0.0, -1.0, -t],
+            [0.0, 1.0, -t],
+            [t, 0.0, -1.0],
+            [t, 0.0, 1.0],
+            [-t, 0.0, -1.0],
+            [-t, 0.0, 1.0],
+        ]
+        .iter()
+        .map(|&position| Vect3::from(position).normalized())
+        .collect();
+        // Counter-clockwise seen from outside
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+            let mut midpoint = |a: u32, b: u32| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push((positions[a as usize] + positions[b as usize]).normalized());
+                    positions.len() as u32 - 1
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let [ab, bc, ca] = [midpoint(a, b), midpoint(b, c), midpoint(c, a)];
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        let tex_coords = |position: Vect3| [0.5 + position.y.atan2(position.x) / TAU, 0.5 + position.z.asin() / PI];
+        let mut builder = ShapeBuilder::new();
+        for &position in &positions {
+            builder.vertex(position * radius, position, tex_coords(position));
+        }
+        for [a, b, c] in triangles {
+            // Triangles crossing the seam at the back get copies of their vertices with `u`
+            // past 1, so they don't stretch over the whole texture. The texture has to repeat.
+            let [ua, ub, uc] = [a, b, c].map(|index| tex_coords(positions[index as usize])[0]);
+            let crosses_seam = ua.max(ub).max(uc) - ua.min(ub).min(uc) > 0.5;
+            let [a, b, c] = [a, b, c].map(|index| {
+                let position = positions[index as usize];
+                match tex_coords(position) {
+                    [u, v] if crosses_seam && u < 0.5 => builder.vertex(position * radius, position, [u + 1.0, v]),
+                    _ => index,
+                }
+            });
+            builder.triangle(a, b, c);
+        }
+
+        builder.build()
+    }
+
+    /// A cylinder of the given `height` along Z, with flat caps.
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Self {
+        assert!(segments >= 3, "a cylinder needs at least 3 segments");
+        let half = height / 2.0;
+        let side = [-half, half].map(|z| ProfilePoint {
+            radius,
+            z,
+            normal: [1.0, 0.0],
+            v: z / height + 0.5,
+        });
+
+        let mut builder = ShapeBuilder::new();
+        builder.revolve(&side, segments);
+        builder.disc(radius, -half, segments, false);
+        builder.disc(radius, half, segments, true);
+        builder.build()
+    }
+
+    /// A cone of the given `height` along Z, with its tip on top and a flat base.
+    pub fn cone(radius: f32, height: f32, segments: u32) -> Self {
+        assert!(segments >= 3, "a cone needs at least 3 segments");
+        let half = height / 2.0;
+        let slope = (radius * radius + height * height).sqrt();
+        let normal = [height / slope, radius / slope];
+        let side = [
+            ProfilePoint { radius, z: -half, normal, v: 0.0 },
+            ProfilePoint { radius: 0.0, z: half, normal, v: 1.0 },
+        ];
+
+        let mut builder = ShapeBuilder::new();
+        builder.revolve(&side, segments);
+        builder.disc(radius, -half, segments, false);
+        builder.build()
+    }
+
+    /// A cylinder of the given `height` along Z with a half sphere on each end, so the whole
+    /// capsule is `height + 2 * radius` tall. Each half sphere has `rings` rows of latitude, at
+    /// least 1.
+    pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Self {
+        assert!(segments >= 3 && rings >= 1, "a capsule needs at least 3 segments and 1 ring");
+        let half = height / 2.0;
+        let total = height + 2.0 * radius;
+        // Both equators are included, the band between them is the cylinder.
+        let profile: Vec<ProfilePoint> = (0..=rings)
+            .map(|ring| (-half, -FRAC_PI_2 + FRAC_PI_2 * ring as f32 / rings as f32))
+            .chain((0..=rings).map(|ring| (half, FRAC_PI_2 * ring as f32 / rings as f32)))
+            .map(|(center, latitude)| {
+                let z = center + radius * latitude.sin();
+                ProfilePoint {
+                    radius: radius * latitude.cos(),
+                    z,
+                    normal: [latitude.cos(), latitude.sin()],
+                    v: (z + total / 2.0) / total,
+                }
+            })
+            .collect();
+
+        let mut builder = ShapeBuilder::new();
+        builder.revolve(&profile, segments);
+        builder.build()
+    }
+
+    /// A torus lying in the XY plane. `major_radius` is the distance from the center to the
+    /// middle of the tube, `minor_radius` the radius of the tube. Both need at least 3 segments.
+    pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Self {
+        assert!(major_segments >= 3 && minor_segments >= 3, "a torus needs at least 3 segments around and across");
+        let profile: Vec<ProfilePoint> = (0..=minor_segments)
+            .map(|segment| {
+                let angle = TAU * segment as f32 / minor_segments as f32;
+                ProfilePoint {
+                    radius: major_radius + minor_radius * angle.cos(),
+                    z: minor_radius * angle.sin(),
+                    normal: [angle.cos(), angle.sin()],
+                    v: segment as f32 / minor_segments as f32,
+                }
+            })
+            .collect();
+
+        let mut builder = ShapeBuilder::new();
+        builder.revolve(&profile, major_segments);
+        builder.build()
+    }
+
+    /// A flat rectangle in the XY plane facing +Z, split into a grid of quads.
+    pub fn plane(width: f32, depth: f32, subdivisions_x: u32, subdivisions_y: u32) -> Self {
+        let heights = vec![0.0; ((subdivisions_x + 1) * (subdivisions_y + 1)) as usize];
+        Mesh::heightmap(&heights, subdivisions_x as usize + 1, [width, depth])
+    }
+
+    /// A grid in the XY plane of `size`, raised along Z by `heights`. The heights are given
+    /// row by row, with `columns` values per row from -X to +X and rows from -Y to +Y. Normals
+    /// follow the slope of the surface.
+    pub fn heightmap(heights: &[f32], columns: usize, size: [f32; 2]) -> Self {
+        assert!(columns >= 2 && heights.len().is_multiple_of(columns) && heights.len() / columns >= 2, "a heightmap needs at least 2x2 heights");
+        let rows = heights.len() / columns;
+        let [step_x, step_y] = [size[0] / (columns - 1) as f32, size[1] / (rows - 1) as f32];
+        let height = |column: usize, row: usize| heights[row * columns + column];
+
+        let mut builder = ShapeBuilder::new();
+        for row in 0..rows {
+            for column in 0..columns {
+                // Central differences, one sided at the border
+                let [left, right] = [column.saturating_sub(1), (column + 1).min(columns - 1)];
+                let [down, up] = [row.saturating_sub(1), (row + 1).min(rows - 1)];
+                let slope_x = (height(right, row) - height(left, row)) / ((right - left) as f32 * step_x);
+                let slope_y = (height(column, up) - height(column, down)) / ((up - down) as f32 * step_y);
+
+                let [u, v] = [column as f32 / (columns - 1) as f32, row as f32 / (rows - 1) as f32];
+                let position = Vect3::new((u - 0.5) * size[0], (v - 0.5) * size[1], height(column, row));
+                builder.vertex(position, Vect3::new(-slope_x, -slope_y, 1.0).normalized(), [u, v]);
+            }
+        }
+
+        let index = |column: usize, row: usize| (row * columns + column) as u32;
+        for row in 0..rows - 1 {
+            for column in 0..columns - 1 {
+                builder.quad([index(column, row), index(column + 1, row), index(column + 1, row + 1), index(column, row + 1)]);
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// Collects vertices and triangles of generated shapes, which don't need deduplication.
+struct ShapeBuilder {
+    mesh: Mesh,
+}
+
+impl ShapeBuilder {
+    fn new() -> Self {
+        ShapeBuilder { mesh: Mesh::new() }
+    }
+
+    fn vertex(&mut self, position: Vect3, normal: Vect3, tex_coords: [f32; 2]) -> u32 {
+        self.mesh.vertices.push(Vertex::new(
+            [position.x, position.y, position.z],
+            [normal.x, normal.y, normal.z],
+            tex_coords,
+        ));
+        self.mesh.vertices.len() as u32 - 1
+    }
+
+    /// Takes the corners counter-clockwise seen from the front, and stores them in the reversed
+    /// order used by `Mesh::from_wavefront`.
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.mesh.indices.extend([a, c, b]);
+    }
+
+    /// Takes the corners counter-clockwise seen from the front.
+    fn quad(&mut self, [a, b, c, d]: [u32; 4]) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+
+    /// Sweeps the profile, ordered from bottom to top on its outside, around the Z axis. The
+    /// first column is repeated at the end to close the texture seam, and no triangles are made
+    /// where the profile touches the axis.
+    fn revolve(&mut self, profile: &[ProfilePoint], segments: u32) {
+        let first = self.mesh.vertices.len() as u32;
+        for point in profile {
+            for segment in 0..=segments {
+                let u = segment as f32 / segments as f32;
+                let (sin, cos) = (TAU * u).sin_cos();
+                let position = Vect3::new(point.radius * cos, point.radius * sin, point.z);
+                let [radial, z] = point.normal;
+                self.vertex(position, Vect3::new(radial * cos, radial * sin, z), [u, point.v]);
+            }
+        }
+
+        let index = |row: usize, segment: u32| first + row as u32 * (segments + 1) + segment;
+        for row in 0..profile.len() - 1 {
+            let (bottom, top) = (profile[row].radius > 0.0, profile[row + 1].radius > 0.0);
+            for segment in 0..segments {
+                let [a, b] = [index(row, segment), index(row, segment + 1)];
+                let [c, d] = [index(row + 1, segment + 1), index(row + 1, segment)];
+                if bottom {
+                    self.triangle(a, b, c);
+                }
+                if top {
+                    self.triangle(a, c, d);
+                }
+            }
+        }
+    }
+
+    /// A flat disc at height `z` facing up or down, with the texture mapped onto it from above.
+    fn disc(&mut self, radius: f32, z: f32, segments: u32, facing_up: bool) {
+        let normal = Vect3::new(0.0, 0.0, if facing_up { 1.0 } else { -1.0 });
+        let center = self.vertex(Vect3::new(0.0, 0.0, z), normal, [0.5, 0.5]);
+        for segment in 0..=segments {
+            let (sin, cos) = (TAU * segment as f32 / segments as f32).sin_cos();
+            self.vertex(Vect3::new(radius * cos, radius * sin, z), normal, [0.5 + cos / 2.0, 0.5 + sin / 2.0]);
+        }
+
+        for segment in 0..segments {
+            let [current, next] = [center + 1 + segment, center + 2 + segment];
+            match facing_up {
+                true => self.triangle(center, current, next),
+                false => self.triangle(center, next, current),
+            }
+        }
+    }
+
+    fn build(self) -> Mesh {
+        self.mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes() -> Vec<(&'static str, Mesh)> {
+        vec![
+            ("cube", Mesh::cube(2.0)),
+            ("uv sphere", Mesh::uv_sphere(1.0, 16, 8)),
+            ("icosphere", Mesh::icosphere(1.0, 2)),
+            ("cylinder", Mesh::cylinder(1.0, 2.0, 12)),
+            ("cone", Mesh::cone(1.0, 2.0, 12)),
+            ("capsule", Mesh::capsule(0.5, 1.0, 12, 4)),
+            ("torus", Mesh::torus(1.0, 0.25, 16, 8)),
+            ("plane", Mesh::plane(2.0, 1.0, 4, 2)),
+        ]
+    }
+
+    fn vect(array: [f32; 3]) -> Vect3 {
+        Vect3::from(array)
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3 segments")]
+    fn test_too_few_segments() {
+        Mesh::uv_sphere(1.0, 0, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3 segments")]
+    fn test_torus_too_few_segments() {
+        Mesh::torus(1.0, 0.25, 16, 2);
+    }
+
+    /// The same orientation test as the one that decides culling: the corners as stored are
+    /// clockwise seen from the side the normals point to.
+    #[test]
+    fn test_winding_matches_wavefront_meshes() {
+        for (name, mesh) in shapes() {
+            assert!(mesh.triangle_count() > 0, "{} has no triangles", name);
+            for triangle in mesh.triangles() {
+                let [a, b, c] = triangle.map(|vertex| vect(vertex.position));
+                let face_normal = (b - a).cross(&(c - a));
+                let vertex_normal = triangle.iter().fold(Vect3::new(0.0, 0.0, 0.0), |sum, vertex| sum + vect(vertex.normal));
+                assert!(face_normal.norm() > 1e-6, "{} has a degenerate triangle", name);
+                assert!(face_normal.dot(&vertex_normal) < 0.0, "{} has a triangle wound the wrong way", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_normals_and_tex_coords() {
+        for (name, mesh) in shapes() {
+            for vertex in &mesh.vertices {
+                assert!((vect(vertex.normal).norm() - 1.0).abs() < 1e-5, "{} has a normal that isn't unit length", name);
+                // The icosphere wraps its seam past 1.
+                let max_u = if name == "icosphere" { 1.5 } else { 1.0 };
+                let [u, v] = vertex.tex_coords;
+                assert!((0.0..=max_u).contains(&u) && (0.0..=1.0).contains(&v), "{} has uv {:?}", name, vertex.tex_coords);
+            }
+        }
+    }
+
+    #[test]
+    fn test_points_lie_on_the_surface() {
+        let sphere = Mesh::uv_sphere(2.0, 8, 6);
+        let icosphere = Mesh::icosphere(2.0, 1);
+        for vertex in sphere.vertices.iter().chain(&icosphere.vertices) {
+            assert!((vect(vertex.position).norm() - 2.0).abs() < 1e-5);
+            assert!((vect(vertex.position).normalized() - vect(vertex.normal)).norm() < 1e-5);
+        }
+
+        let capsule = Mesh::capsule(0.5, 1.0, 8, 3);
+        let top = capsule.vertices.iter().map(|vertex| vertex.position[2]).fold(f32::MIN, f32::max);
+        assert!((top - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_triangle_counts() {
+        assert_eq!(Mesh::cube(1.0).triangle_count(), 12);
+        assert_eq!(Mesh::cube(1.0).vertex_count(), 24);
+        assert_eq!(Mesh::icosphere(1.0, 0).triangle_count(), 20);
+        assert_eq!(Mesh::icosphere(1.0, 2).triangle_count(), 320);
+        // Each pole row only has one triangle per segment.
+        assert_eq!(Mesh::uv_sphere(1.0, 16, 8).triangle_count(), 16 * 2 * (8 - 1));
+        assert_eq!(Mesh::cylinder(1.0, 1.0, 10).triangle_count(), 10 * 4);
+        assert_eq!(Mesh::cone(1.0, 1.0, 10).triangle_count(), 10 * 2);
+        assert_eq!(Mesh::torus(1.0, 0.5, 10, 6).triangle_count(), 10 * 6 * 2);
+        assert_eq!(Mesh::plane(1.0, 1.0, 3, 2).triangle_count(), 3 * 2 * 2);
+    }
+
+    #[test]
+    fn test_heightmap_normals_follow_the_slope() {
+        // A ramp rising towards +X by 1 per unit
+        let heights = [0.0, 1.0, 2.0, 0.0, 1.0, 2.0];
+        let mesh = Mesh::heightmap(&heights, 3, [2.0, 1.0]);
+        assert_eq!(mesh.vertex_count(), 6);
+        assert_eq!(mesh.vertices[5].position, [1.0, 0.5, 2.0]);
+        let expected = Vect3::new(-1.0, 0.0, 1.0).normalized();
+        assert!(mesh.vertices.iter().all(|vertex| (vect(vertex.normal) - expected).norm() < 1e-6));
+    }
+}
@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use gust_math::vectors::vect3::Vect3;
+
+use crate::primitives::mesh::Mesh;
+use crate::primitives::vertex::Vertex;
+
+/// Open borders weigh this much more than the surface, so the outline of a mesh is kept.
+const BORDER_WEIGHT: f64 = 100.0;
+/// Collapses turning a triangle further than this (as the cosine of the angle) are refused,
+/// they would fold the surface over.
+const MIN_NORMAL_COS: f32 = 0.2;
+
+impl Mesh {
+    /// Reduces the mesh to at most `max_triangles` triangles by collapsing the edges that change
+    /// its shape least, measured by quadric error metrics. Vertices move onto one of their
+    /// neighbours and keep its attributes, so texture coordinates stay valid. Fewer triangles
+    /// than asked for may remain when every further collapse would fold the surface over.
+    pub fn simplified(&self, max_triangles: usize) -> Mesh {
+        let mut simplifier = Simplifier::new(self);
+        simplifier.run(max_triangles);
+        simplifier.build()
+    }
+
+    /// The mesh followed by `levels - 1` simplified versions, each with at most half the
+    /// triangles of the one before.
+    pub fn lod_chain(&self, levels: usize) -> Vec<Mesh> {
+        let mut chain = vec![self.clone()];
+        while chain.len() < levels {
+            let previous = chain.last().unwrap();
+            let next = previous.simplified(previous.triangle_count() / 2);
+            if next.triangle_count() == 0 || next.triangle_count() == previous.triangle_count() {
+                break;
+            }
+            chain.push(next);
+        }
+        chain
+    }
+}
+
+/// The symmetric 4x4 matrix of a quadric, as its upper triangle.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// The squared distance to the plane through `point` with the unit `normal`, times `weight`.
+    fn plane(normal: Vect3, point: Vect3, weight: f64) -> Self {
+        let [a, b, c] = [normal.x, normal.y, normal.z].map(f64::from);
+        let d = -(a * point.x as f64 + b * point.y as f64 + c * point.z as f64);
+        Quadric([a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|value| value * weight))
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (value, other) in self.0.iter_mut().zip(other.0) {
+            *value += other;
+        }
+    }
+
+    fn error(&self, point: Vect3) -> f64 {
+        let [x, y, z] = [point.x, point.y, point.z].map(f64::from);
+        let q = &self.0;
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+/// Moving every vertex at position `from` onto position `to`.
+#[derive(Debug)]
+struct Collapse {
+    cost: f64,
+    from: usize,
+    to: usize,
+    /// The versions of both positions when the cost was computed. Later changes make it stale.
+    versions: (u32, u32),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reversed, so the heap pops the cheapest collapse first.
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Vertices with the same position form one point, so edges along texture seams collapse
+/// together with the rest of the surface.
+struct Simplifier<'a> {
+    mesh: &'a Mesh,
+    /// The vertex indices of each triangle. Collapsed triangles are `None`.
+    triangles: Vec<Option<[u32; 3]>>,
+    triangle_count: usize,
+    /// The point of each vertex
+    point_of: Vec<usize>,
+    positions: Vec<Vect3>,
+    quadrics: Vec<Quadric>,
+    /// The vertices and triangles using each point. Collapses leave stale entries behind.
+    vertices: Vec<Vec<u32>>,
+    point_triangles: Vec<Vec<usize>>,
+    versions: Vec<u32>,
+    heap: BinaryHeap<Collapse>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(mesh: &'a Mesh) -> Self {
+        let mut points: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let mut vertices: Vec<Vec<u32>> = Vec::new();
+        let point_of: Vec<usize> = mesh
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(index, vertex)| {
+                let point = *points.entry(vertex.position.map(f32::to_bits)).or_insert_with(|| {
+                    positions.push(Vect3::from(vertex.position));
+                    vertices.push(Vec::new());
+                    positions.len() - 1
+                });
+                vertices[point].push(index as u32);
+                point
+            })
+            .collect();
+
+        let triangles: Vec<Option<[u32; 3]>> = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .filter(|triangle| {
+                let [a, b, c] = triangle.map(|index| point_of[index as usize]);
+                a != b && b != c && c != a
+            })
+            .map(Some)
+            .collect();
+
+        let mut simplifier = Simplifier {
+            mesh,
+            triangle_count: triangles.len(),
+            triangles,
+            point_of,
+            quadrics: vec![Quadric::default(); positions.len()],
+            point_triangles: vec![Vec::new(); positions.len()],
+            versions: vec![0; positions.len()],
+            positions,
+            vertices,
+            heap: BinaryHeap::new(),
+        };
+        simplifier.init_quadrics();
+        simplifier
+    }
+
+    fn points(&self, triangle: [u32; 3]) -> [usize; 3] {
+        triangle.map(|index| self.point_of[index as usize])
+    }
+
+    /// The front facing normal, scaled by twice the area. Stored triangles are wound clockwise.
+    fn normal(&self, [a, b, c]: [usize; 3]) -> Vect3 {
+        let [a, b, c] = [a, b, c].map(|point| self.positions[point]);
+        (c - a).cross(&(b - a))
+    }
+
+    fn init_quadrics(&mut self) {
+        // How many triangles use each edge, to find the open borders
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for index in 0..self.triangles.len() {
+            let points = self.points(self.triangles[index].unwrap());
+            let normal = self.normal(points);
+            let area = normal.norm() as f64 / 2.0;
+            if let Some(unit) = normal.try_normalized() {
+                let quadric = Quadric::plane(unit, self.positions[points[0]], area);
+                points.iter().for_each(|&point| self.quadrics[point].add(&quadric));
+            }
+            for corner in 0..3 {
+                let (a, b) = (points[corner], points[(corner + 1) % 3]);
+                self.point_triangles[a].push(index);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+
+        // A plane through each border edge, upright on its triangle, keeps its points on the border.
+        for index in 0..self.triangles.len() {
+            let points = self.points(self.triangles[index].unwrap());
+            let Some(normal) = self.normal(points).try_normalized() else {
+                continue;
+            };
+            for corner in 0..3 {
+                let (a, b) = (points[corner], points[(corner + 1) % 3]);
+                if edges[&(a.min(b), a.max(b))] != 1 {
+                    continue;
+                }
+                let edge = self.positions[b] - self.positions[a];
+                if let Some(border_normal) = edge.cross(&normal).try_normalized() {
+                    let weight = edge.dot(&edge) as f64 * BORDER_WEIGHT;
+                    let quadric = Quadric::plane(border_normal, self.positions[a], weight);
+                    self.quadrics[a].add(&quadric);
+                    self.quadrics[b].add(&quadric);
+                }
+            }
+        }
+
+        // Sorted, so equal costs are taken in the same order every time.
+        let mut edges: Vec<(usize, usize)> = edges.into_keys().collect();
+        edges.sort_unstable();
+        for (a, b) in edges {
+            self.push_edge(a, b);
+        }
+    }
+
+    /// Queues the cheaper direction of collapsing the edge between two points.
+    fn push_edge(&mut self, a: usize, b: usize) {
+        let mut quadric = self.quadrics[a];
+        quadric.add(&self.quadrics[b]);
+        let (from, to) = match quadric.error(self.positions[b]) <= quadric.error(self.positions[a]) {
+            true => (a, b),
+            false => (b, a),
+        };
+        self.heap.push(Collapse {
+            cost: quadric.error(self.positions[to]),
+            from,
+            to,
+            versions: (self.versions[from], self.versions[to]),
+        });
+    }
+
+    fn alive_triangles(&self, point: usize) -> Vec<usize> {
+        let mut triangles: Vec<usize> = self.point_triangles[point]
+            .iter()
+            .copied()
+            .filter(|&index| self.triangles[index].is_some_and(|triangle| self.points(triangle).contains(&point)))
+            .collect();
+        triangles.sort_unstable();
+        triangles.dedup();
+        triangles
+    }
+
+    fn run(&mut self, max_triangles: usize) {
+        while self.triangle_count > max_triangles {
+            let Some(collapse) = self.heap.pop() else {
+                break;
+            };
+            if collapse.versions != (self.versions[collapse.from], self.versions[collapse.to]) {
+                continue;
+            }
+            self.collapse(collapse.from, collapse.to);
+        }
+    }
+
+    fn collapse(&mut self, from: usize, to: usize) {
+        let triangles = self.alive_triangles(from);
+
+        // Refuse to flip any triangle that stays
+        for &index in &triangles {
+            let points = self.points(self.triangles[index].unwrap());
+            if points.contains(&to) {
+                continue;
+            }
+            let moved = points.map(|point| if point == from { to } else { point });
+            let (before, after) = (self.normal(points), self.normal(moved));
+            match (before.try_normalized(), after.try_normalized()) {
+                (Some(before), Some(after)) if before.dot(&after) >= MIN_NORMAL_COS => {}
+                _ => return,
+            }
+        }
+
+        // Each vertex takes the attributes of a vertex at `to` it shares a triangle with, so
+        // texture seams through both points stay intact.
+        let mut replacements: HashMap<u32, u32> = HashMap::new();
+        for &index in &triangles {
+            let triangle = self.triangles[index].unwrap();
+            let points = self.points(triangle);
+            if let (Some(from_corner), Some(to_corner)) = (points.iter().position(|&point| point == from), points.iter().position(|&point| point == to)) {
+                replacements.entry(triangle[from_corner]).or_insert(triangle[to_corner]);
+            }
+        }
+        let fallback = self.vertices[to][0];
+
+        for &index in &triangles {
+            let mut triangle = self.triangles[index].unwrap();
+            for vertex in triangle.iter_mut() {
+                if self.point_of[*vertex as usize] == from {
+                    *vertex = replacements.get(vertex).copied().unwrap_or(fallback);
+                }
+            }
+            let [a, b, c] = self.points(triangle);
+            if a == b || b == c || c == a {
+                self.triangles[index] = None;
+                self.triangle_count -= 1;
+            } else {
+                self.triangles[index] = Some(triangle);
+                self.point_triangles[to].push(index);
+            }
+        }
+
+        let quadric = self.quadrics[from];
+        self.quadrics[to].add(&quadric);
+        self.versions[from] += 1;
+        self.versions[to] += 1;
+
+        let mut neighbours: Vec<usize> = self
+            .alive_triangles(to)
+            .iter()
+            .flat_map(|&index| self.points(self.triangles[index].unwrap()))
+            .filter(|&point| point != to)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        for neighbour in neighbours {
+            self.push_edge(to, neighbour);
+        }
+    }
+
+    fn build(self) -> Mesh {
+        let triangles: Vec<[Vertex; 3]> = self
+            .triangles
+            .iter()
+            .flatten()
+            .map(|triangle| triangle.map(|index| self.mesh.vertices[index as usize]))
+            .collect();
+        Mesh::from_triangles(&triangles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_distance_to_sphere(mesh: &Mesh, radius: f32) -> f32 {
+        mesh.vertices.iter().map(|vertex| (Vect3::from(vertex.position).norm() - radius).abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_triangle_budgets_are_met() {
+        let sphere = Mesh::icosphere(1.0, 3);
+        assert_eq!(sphere.triangle_count(), 1280);
+        for budget in [640, 300, 100, 40] {
+            let simplified = sphere.simplified(budget);
+            assert!(simplified.triangle_count() <= budget, "{} triangles for a budget of {}", simplified.triangle_count(), budget);
+            assert!(simplified.triangle_count() >= budget * 3 / 4, "{} triangles for a budget of {}", simplified.triangle_count(), budget);
+            // Vertices only move onto other vertices of the sphere.
+            assert!(max_distance_to_sphere(&simplified, 1.0) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_shape_and_winding_are_kept() {
+        let simplified = Mesh::uv_sphere(1.0, 32, 16).simplified(200);
+        assert!(simplified.triangle_count() <= 200);
+        let bounds = simplified.bounds();
+        assert!(bounds.aabb.size().x > 1.8 && bounds.aabb.size().z > 1.8);
+        for triangle in simplified.triangles() {
+            let [a, b, c] = triangle.map(|vertex| Vect3::from(vertex.position));
+            let center = (a + b + c) / 3.0;
+            assert!((c - a).cross(&(b - a)).dot(&center) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_flat_surfaces_collapse_and_keep_their_border() {
+        let plane = Mesh::plane(2.0, 2.0, 8, 8);
+        let simplified = plane.simplified(2);
+        assert_eq!(simplified.triangle_count(), 2);
+        assert_eq!(simplified.bounds().aabb, plane.bounds().aabb);
+        // Each vertex keeps the texture coordinates that belong to its position.
+        for vertex in &simplified.vertices {
+            assert_eq!(vertex.tex_coords, [vertex.position[0] / 2.0 + 0.5, vertex.position[1] / 2.0 + 0.5]);
+        }
+    }
+
+    #[test]
+    fn test_lod_chain_halves_triangles() {
+        let chain = Mesh::icosphere(1.0, 3).lod_chain(4);
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain[0], Mesh::icosphere(1.0, 3));
+        for pair in chain.windows(2) {
+            assert!(pair[1].triangle_count() <= pair[0].triangle_count() / 2);
+            assert!(pair[1].triangle_count() > 0);
+        }
+
+        // A single triangle can't get any simpler.
+        let triangle = Mesh::from_triangles(&Mesh::plane(1.0, 1.0, 1, 1).triangles().take(1).collect::<Vec<_>>());
+        assert_eq!(triangle.lod_chain(3).len(), 1);
+    }
+}
@@ -1,4 +1,15 @@
 pub mod mesh;
+pub mod mesh_shapes;
+pub mod mesh_normals;
+pub mod mesh_simplification;
+pub mod bounds;
+pub mod gpu_mesh;
 pub mod vertex;
 pub mod color;
-pub mod lights_block;
\ No newline at end of file
+pub mod material;
+pub mod lights_block;
+pub mod texture;
+pub mod texture_error;
+pub mod rect_packer;
+pub mod texture_atlas;
+pub mod atlas_error;
\ No newline at end of file
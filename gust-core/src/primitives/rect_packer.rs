@@ -0,0 +1,142 @@
+/// A rectangle placed by a `RectPacker`, in pixels from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The top edge of the filled area over a run of columns.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// Places rectangles in an area without overlap, each as low and then as far left as it fits.
+///
+/// Only the skyline of what is placed is kept, so a gap under a rectangle that overhangs a
+/// lower neighbour is never filled. Packing the tallest rectangles first keeps those gaps small.
+#[derive(Debug, Clone)]
+pub struct RectPacker {
+    width: u32,
+    height: u32,
+    padding: u32,
+    skyline: Vec<Segment>,
+}
+
+impl RectPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        RectPacker { width, height, padding: 0, skyline: vec![Segment { x: 0, y: 0, width }] }
+    }
+
+    /// Empty pixels kept between rectangles, so filtering doesn't blend in their neighbours.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Places a rectangle, or returns `None` if there is no room left for it. Empty rectangles
+    /// take up no room.
+    pub fn pack(&mut self, width: u32, height: u32) -> Option<PackedRect> {
+        if width == 0 || height == 0 {
+            return Some(PackedRect { x: 0, y: 0, width, height });
+        }
+        let (index, x, y) = (0..self.skyline.len())
+            .filter_map(|index| self.fit(index, width, height).map(|y| (index, self.skyline[index].x, y)))
+            .min_by_key(|&(_, x, y)| (y + height, x))?;
+
+        // The padding is left out where it would reach past the edges.
+        let reserved_width = (width + self.padding).min(self.width - x);
+        let reserved_height = (height + self.padding).min(self.height - y);
+        self.skyline.insert(index, Segment { x, y: y + reserved_height, width: reserved_width });
+
+        let end = x + reserved_width;
+        let mut next = index + 1;
+        while next < self.skyline.len() && self.skyline[next].x < end {
+            let segment = &mut self.skyline[next];
+            let segment_end = segment.x + segment.width;
+            if segment_end <= end {
+                self.skyline.remove(next);
+            } else {
+                segment.width = segment_end - end;
+                segment.x = end;
+                next += 1;
+            }
+        }
+        self.skyline.dedup_by(|right, left| {
+            let merge = left.y == right.y;
+            if merge {
+                left.width += right.width;
+            }
+            merge
+        });
+
+        Some(PackedRect { x, y, width, height })
+    }
+
+    /// The lowest a rectangle starting at the segment can sit, if it fits there at all.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[index].x;
+        if x + width > self.width {
+            return None;
+        }
+        let y = self.skyline[index..]
+            .iter()
+            .take_while(|segment| segment.x < x + width)
+            .map(|segment| segment.y)
+            .max()?;
+        (y + height <= self.height).then_some(y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: &PackedRect, b: &PackedRect) -> bool {
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    #[test]
+    fn test_pack() {
+        let mut packer = RectPacker::new(8, 8);
+        assert_eq!(packer.pack(4, 4), Some(PackedRect { x: 0, y: 0, width: 4, height: 4 }));
+        assert_eq!(packer.pack(4, 2), Some(PackedRect { x: 4, y: 0, width: 4, height: 2 }));
+        assert_eq!(packer.pack(4, 2), Some(PackedRect { x: 4, y: 2, width: 4, height: 2 }));
+        assert_eq!(packer.pack(8, 4), Some(PackedRect { x: 0, y: 4, width: 8, height: 4 }));
+        assert_eq!(packer.pack(1, 1), None);
+        assert_eq!(RectPacker::new(4, 4).pack(5, 1), None);
+    }
+
+    #[test]
+    fn test_padding() {
+        let mut packer = RectPacker::new(7, 3).with_padding(1);
+        assert_eq!(packer.pack(3, 3), Some(PackedRect { x: 0, y: 0, width: 3, height: 3 }));
+        assert_eq!(packer.pack(3, 3), Some(PackedRect { x: 4, y: 0, width: 3, height: 3 }));
+        assert_eq!(packer.pack(1, 1), None);
+    }
+
+    #[test]
+    fn test_no_overlap() {
+        let mut packer = RectPacker::new(64, 64).with_padding(1);
+        let mut sizes: Vec<(u32, u32)> = (0..40u32).map(|index| (1 + index * 7 % 11, 1 + index * 5 % 9)).collect();
+        sizes.sort_by_key(|&(width, height)| std::cmp::Reverse((height, width)));
+
+        let placed: Vec<PackedRect> = sizes.iter().map(|&(width, height)| packer.pack(width, height).unwrap()).collect();
+        for (index, a) in placed.iter().enumerate() {
+            assert!(a.x + a.width <= 64 && a.y + a.height <= 64);
+            assert!(placed[index + 1..].iter().all(|b| !overlaps(a, b)));
+        }
+    }
+}
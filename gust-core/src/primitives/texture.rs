@@ -0,0 +1,271 @@
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::glutin::surface::WindowSurface;
+use glium::texture::{
+    CubeLayer, Cubemap, MipmapsOption, RawImage2d, SrgbCubemap, SrgbTexture2d, SrgbTexture2dArray, Texture2dArray,
+};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction};
+use glium::{BlitTarget, Display, Surface, Texture2d};
+
+use crate::primitives::texture_error::TextureError;
+
+/// The order of the faces given to `Texture::cubemap`, the same as OpenGL's.
+pub const CUBE_LAYERS: [CubeLayer; 6] = [
+    CubeLayer::PositiveX,
+    CubeLayer::NegativeX,
+    CubeLayer::PositiveY,
+    CubeLayer::NegativeY,
+    CubeLayer::PositiveZ,
+    CubeLayer::NegativeZ,
+];
+
+/// A texture on the GPU together with how it is sampled.
+pub struct Texture {
+    pub data: TextureData,
+    pub options: TextureOptions,
+}
+
+pub enum TextureData {
+    Texture2d(Texture2d),
+    SrgbTexture2d(SrgbTexture2d),
+    Texture2dArray(Texture2dArray),
+    SrgbTexture2dArray(SrgbTexture2dArray),
+    Cubemap(Cubemap),
+    SrgbCubemap(SrgbCubemap),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Colors as they were painted, turned linear when sampled so lighting math works on them.
+    Srgb,
+    /// Data like normal maps, sampled as stored.
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureWrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFilter {
+    /// Keeps pixels sharp, for pixel art.
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureOptions {
+    pub color_space: ColorSpace,
+    /// Generates the smaller levels sampled when the texture is minified.
+    pub mipmaps: bool,
+    pub wrap: TextureWrap,
+    pub filter: TextureFilter,
+    /// The most samples taken across surfaces seen at a slant, 1 turns it off. Clamped to what
+    /// the hardware supports.
+    pub anisotropy: u16,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        TextureOptions {
+            color_space: ColorSpace::Srgb,
+            mipmaps: true,
+            wrap: TextureWrap::Repeat,
+            filter: TextureFilter::Linear,
+            anisotropy: 1,
+        }
+    }
+}
+
+impl TextureOptions {
+    /// The default options for data textures like normal maps.
+    pub fn linear() -> Self {
+        TextureOptions::default().with_color_space(ColorSpace::Linear)
+    }
+
+    pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    pub fn with_mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+
+    pub fn sampler_behavior(&self) -> SamplerBehavior {
+        let wrap = match self.wrap {
+            TextureWrap::Repeat => SamplerWrapFunction::Repeat,
+            TextureWrap::MirroredRepeat => SamplerWrapFunction::Mirror,
+            TextureWrap::ClampToEdge => SamplerWrapFunction::Clamp,
+        };
+        let (minify_filter, magnify_filter) = match (self.filter, self.mipmaps) {
+            (TextureFilter::Nearest, false) => (MinifySamplerFilter::Nearest, MagnifySamplerFilter::Nearest),
+            (TextureFilter::Nearest, true) => (MinifySamplerFilter::NearestMipmapNearest, MagnifySamplerFilter::Nearest),
+            (TextureFilter::Linear, false) => (MinifySamplerFilter::Linear, MagnifySamplerFilter::Linear),
+            (TextureFilter::Linear, true) => (MinifySamplerFilter::LinearMipmapLinear, MagnifySamplerFilter::Linear),
+        };
+        SamplerBehavior {
+            wrap_function: (wrap, wrap, wrap),
+            minify_filter,
+            magnify_filter,
+            max_anisotropy: self.anisotropy.max(1),
+            ..SamplerBehavior::default()
+        }
+    }
+
+    fn mipmaps_option(&self) -> MipmapsOption {
+        match self.mipmaps {
+            true => MipmapsOption::AutoGeneratedMipmaps,
+            false => MipmapsOption::NoMipmap,
+        }
+    }
+}
+
+/// Cube maps can't be created from images, so each face is uploaded as a 2D texture and drawn
+/// in through a framebuffer. The mipmaps are made the same way, each level by halving the one
+/// before it.
+macro_rules! fill_cubemap {
+    ($display:expr, $cubemap:expr, $faces:expr, $size:expr, $face_texture:ident) => {
+        for (layer, face) in CUBE_LAYERS.into_iter().zip($faces) {
+            let face = $face_texture::new($display, face)?;
+            let source = SimpleFrameBuffer::new($display, &face)?;
+            let target = SimpleFrameBuffer::new($display, $cubemap.main_level().image(layer))?;
+            source.blit_whole_color_to(&target, &blit_target($size), MagnifySamplerFilter::Nearest);
+
+            for level in 1..$cubemap.get_mipmap_levels() {
+                let source = SimpleFrameBuffer::new($display, $cubemap.mipmap(level - 1).unwrap().image(layer))?;
+                let target = SimpleFrameBuffer::new($display, $cubemap.mipmap(level).unwrap().image(layer))?;
+                source.blit_whole_color_to(&target, &blit_target(($size >> level).max(1)), MagnifySamplerFilter::Linear);
+            }
+        }
+    };
+}
+
+impl Texture {
+    /// Uploads an image as a 2D texture.
+    pub fn new(display: &Display<WindowSurface>, image: RawImage2d<u8>, options: TextureOptions) -> Result<Texture, TextureError> {
+        let mipmaps = options.mipmaps_option();
+        let data = match options.color_space {
+            ColorSpace::Srgb => TextureData::SrgbTexture2d(SrgbTexture2d::with_mipmaps(display, image, mipmaps)?),
+            ColorSpace::Linear => TextureData::Texture2d(Texture2d::with_mipmaps(display, image, mipmaps)?),
+        };
+        Ok(Texture { data, options })
+    }
+
+    /// Uploads images of the same size as the layers of an array texture.
+    pub fn array(display: &Display<WindowSurface>, layers: Vec<RawImage2d<u8>>, options: TextureOptions) -> Result<Texture, TextureError> {
+        check_sizes(layers.iter().map(|layer| (layer.width, layer.height)), false)?;
+        let mipmaps = options.mipmaps_option();
+        let data = match options.color_space {
+            ColorSpace::Srgb => TextureData::SrgbTexture2dArray(SrgbTexture2dArray::with_mipmaps(display, layers, mipmaps)?),
+            ColorSpace::Linear => TextureData::Texture2dArray(Texture2dArray::with_mipmaps(display, layers, mipmaps)?),
+        };
+        Ok(Texture { data, options })
+    }
+
+    /// Uploads six square images of the same size as the faces of a cube map, in the order of
+    /// `CUBE_LAYERS`.
+    pub fn cubemap(display: &Display<WindowSurface>, faces: [RawImage2d<u8>; 6], options: TextureOptions) -> Result<Texture, TextureError> {
+        let (size, _) = check_sizes(faces.iter().map(|face| (face.width, face.height)), true)?;
+        let mipmaps = match options.mipmaps {
+            true => MipmapsOption::EmptyMipmaps,
+            false => MipmapsOption::NoMipmap,
+        };
+        let data = match options.color_space {
+            ColorSpace::Srgb => {
+                let cubemap = SrgbCubemap::empty_with_mipmaps(display, mipmaps, size)?;
+                fill_cubemap!(display, cubemap, faces, size, SrgbTexture2d);
+                TextureData::SrgbCubemap(cubemap)
+            }
+            ColorSpace::Linear => {
+                let cubemap = Cubemap::empty_with_mipmaps(display, mipmaps, size)?;
+                fill_cubemap!(display, cubemap, faces, size, Texture2d);
+                TextureData::Cubemap(cubemap)
+            }
+        };
+        Ok(Texture { data, options })
+    }
+
+    /// A texture of a single pixel.
+    pub fn solid(display: &Display<WindowSurface>, color: [u8; 4], options: TextureOptions) -> Result<Texture, TextureError> {
+        Texture::new(display, RawImage2d::from_raw_rgba(color.to_vec(), (1, 1)), options)
+    }
+}
+
+fn blit_target(size: u32) -> BlitTarget {
+    BlitTarget { left: 0, bottom: 0, width: size as i32, height: size as i32 }
+}
+
+/// The size shared by all layers, which must be square if `square` is set.
+fn check_sizes<I: IntoIterator<Item = (u32, u32)>>(sizes: I, square: bool) -> Result<(u32, u32), TextureError> {
+    let mut sizes = sizes.into_iter();
+    let first = sizes.next().ok_or(TextureError::NoLayers)?;
+    let expected = match square {
+        true => (first.0, first.0),
+        false => first,
+    };
+    for (layer, found) in std::iter::once(first).chain(sizes).enumerate() {
+        if found != expected {
+            return Err(TextureError::MismatchedSize { layer, expected, found });
+        }
+    }
+    Ok(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_behavior() {
+        let behavior = TextureOptions::default().sampler_behavior();
+        assert_eq!(behavior.minify_filter, MinifySamplerFilter::LinearMipmapLinear);
+        assert_eq!(behavior.magnify_filter, MagnifySamplerFilter::Linear);
+        assert_eq!(behavior.wrap_function.0, SamplerWrapFunction::Repeat);
+        assert_eq!(behavior.max_anisotropy, 1);
+
+        let behavior = TextureOptions::linear()
+            .with_filter(TextureFilter::Nearest)
+            .with_mipmaps(false)
+            .with_wrap(TextureWrap::ClampToEdge)
+            .with_anisotropy(16)
+            .sampler_behavior();
+        assert_eq!(behavior.minify_filter, MinifySamplerFilter::Nearest);
+        assert_eq!(behavior.magnify_filter, MagnifySamplerFilter::Nearest);
+        assert_eq!(behavior.wrap_function, (SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp));
+        assert_eq!(behavior.max_anisotropy, 16);
+        assert_eq!(TextureOptions::default().with_anisotropy(0).sampler_behavior().max_anisotropy, 1);
+    }
+
+    #[test]
+    fn test_check_sizes() {
+        assert_eq!(check_sizes([(4, 2), (4, 2)], false).unwrap(), (4, 2));
+        assert!(matches!(check_sizes([], false), Err(TextureError::NoLayers)));
+        assert!(matches!(
+            check_sizes([(4, 2), (2, 4)], false),
+            Err(TextureError::MismatchedSize { layer: 1, expected: (4, 2), found: (2, 4) })
+        ));
+        assert!(matches!(
+            check_sizes([(4, 2)], true),
+            Err(TextureError::MismatchedSize { layer: 0, expected: (4, 4), found: (4, 2) })
+        ));
+    }
+}
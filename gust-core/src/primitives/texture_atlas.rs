@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+
+use glium::glutin::surface::WindowSurface;
+use glium::texture::RawImage2d;
+use glium::Display;
+use image::RgbaImage;
+
+use crate::objects::intermediaries::sprite_sheet::SpriteSheet;
+use crate::primitives::atlas_error::AtlasError;
+use crate::primitives::rect_packer::{PackedRect, RectPacker};
+use crate::primitives::texture::{Texture, TextureOptions};
+
+/// Where an image ended up in an atlas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasRegion {
+    /// In pixels from the top left of the atlas image.
+    pub rect: PackedRect,
+    /// Texture coordinates of the bottom left and top right corners, the way images are
+    /// uploaded with the first row at the top.
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+/// Many images combined into one texture, so sprites and other 2D elements can be drawn without
+/// switching textures. Looks up where each image is by the name it was added with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureAtlas {
+    pub width: u32,
+    pub height: u32,
+    regions: HashMap<String, AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// The name a frame of a sprite sheet added with `add_sprite_sheet` is stored under.
+    pub fn frame_name(sheet: &str, frame: usize) -> String {
+        format!("{}/{}", sheet, frame)
+    }
+
+    pub fn region(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.get(name)
+    }
+
+    pub fn frame(&self, sheet: &str, frame: usize) -> Option<&AtlasRegion> {
+        self.region(&TextureAtlas::frame_name(sheet, frame))
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (&str, &AtlasRegion)> {
+        self.regions.iter().map(|(name, region)| (name.as_str(), region))
+    }
+}
+
+/// Collects named images and packs them into a `TextureAtlas`.
+pub struct TextureAtlasBuilder {
+    images: Vec<(String, RgbaImage)>,
+    padding: u32,
+    max_size: u32,
+}
+
+impl TextureAtlasBuilder {
+    pub fn new() -> Self {
+        TextureAtlasBuilder { images: Vec::new(), padding: 1, max_size: 4096 }
+    }
+
+    /// Empty pixels kept between images, 1 by default. Mipmapped atlases need more for the
+    /// smaller levels not to blend neighbouring images together.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The largest width and height the atlas may grow to, 4096 by default.
+    pub fn with_max_size(mut self, max_size: u32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn add_image(&mut self, name: &str, image: RgbaImage) {
+        self.images.push((name.to_string(), image));
+    }
+
+    /// Adds every frame of a sprite sheet, named by `TextureAtlas::frame_name`.
+    pub fn add_sprite_sheet(&mut self, name: &str, sheet: &SpriteSheet) {
+        for (index, frame) in sheet.frames.iter().enumerate() {
+            self.add_image(&TextureAtlas::frame_name(name, index), frame.image.clone());
+        }
+    }
+
+    /// Packs the images into a power of two atlas, starting from the size of the largest image
+    /// and doubling the shorter side until everything fits.
+    pub fn build_image(&self) -> Result<(TextureAtlas, RgbaImage), AtlasError> {
+        let mut names = HashSet::with_capacity(self.images.len());
+        for (name, _) in &self.images {
+            if !names.insert(name.as_str()) {
+                return Err(AtlasError::DuplicateName(name.clone()));
+            }
+        }
+
+        // Tallest first leaves the fewest gaps under the skyline.
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse((self.images[index].1.height(), self.images[index].1.width())));
+
+        let area: u64 = self
+            .images
+            .iter()
+            .map(|(_, image)| (image.width() + self.padding) as u64 * (image.height() + self.padding) as u64)
+            .sum();
+        let side = |side: fn(&RgbaImage) -> u32| {
+            let largest = self.images.iter().map(|(_, image)| side(image)).max().unwrap_or(1);
+            largest.max(1).next_power_of_two().min(self.max_size)
+        };
+        let mut size = (side(RgbaImage::width), side(RgbaImage::height));
+
+        let rects = loop {
+            let at_max = size == (self.max_size, self.max_size);
+            if at_max || size.0 as u64 * size.1 as u64 >= area {
+                match self.pack(&order, size) {
+                    Ok(rects) => break rects,
+                    Err(unplaced) if at_max => {
+                        return Err(AtlasError::DoesNotFit { name: self.images[unplaced].0.clone(), max_size: self.max_size });
+                    }
+                    Err(_) => {}
+                }
+            }
+            size = match (size.0 <= size.1 && size.0 < self.max_size) || size.1 >= self.max_size {
+                true => ((size.0 * 2).min(self.max_size), size.1),
+                false => (size.0, (size.1 * 2).min(self.max_size)),
+            };
+        };
+
+        let (width, height) = size;
+        let mut image = RgbaImage::new(width, height);
+        let mut regions = HashMap::with_capacity(self.images.len());
+        for ((name, source), rect) in self.images.iter().zip(rects) {
+            image::imageops::replace(&mut image, source, rect.x as i64, rect.y as i64);
+            let uv_min = [rect.x as f32 / width as f32, 1.0 - (rect.y + rect.height) as f32 / height as f32];
+            let uv_max = [(rect.x + rect.width) as f32 / width as f32, 1.0 - rect.y as f32 / height as f32];
+            regions.insert(name.clone(), AtlasRegion { rect, uv_min, uv_max });
+        }
+
+        Ok((TextureAtlas { width, height, regions }, image))
+    }
+
+    /// Packs the images and uploads the atlas. Sprites usually want `TextureFilter::Nearest`
+    /// and no mipmaps.
+    pub fn build(&self, display: &Display<WindowSurface>, options: TextureOptions) -> Result<(TextureAtlas, Texture), AtlasError> {
+        let (atlas, image) = self.build_image()?;
+        let dimensions = image.dimensions();
+        let texture = Texture::new(display, RawImage2d::from_raw_rgba_reversed(&image.into_raw(), dimensions), options)?;
+        Ok((atlas, texture))
+    }
+
+    /// The rectangles of the images in the order they were added, or the first image that
+    /// doesn't fit.
+    fn pack(&self, order: &[usize], (width, height): (u32, u32)) -> Result<Vec<PackedRect>, usize> {
+        let mut packer = RectPacker::new(width, height).with_padding(self.padding);
+        let mut rects = vec![PackedRect { x: 0, y: 0, width: 0, height: 0 }; self.images.len()];
+        for &index in order {
+            let image = &self.images[index].1;
+            rects[index] = packer.pack(image.width(), image.height()).ok_or(index)?;
+        }
+        Ok(rects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use image::Rgba;
+
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_build_image() {
+        let mut builder = TextureAtlasBuilder::new();
+        builder.add_image("wide", solid(20, 4, 1));
+        builder.add_image("square", solid(8, 8, 2));
+        builder.add_image("small", solid(3, 3, 3));
+        let (atlas, image) = builder.build_image().unwrap();
+        assert_eq!((atlas.width, atlas.height), (32, 8));
+        assert_eq!(image.dimensions(), (32, 8));
+        assert_eq!(atlas.regions().count(), 3);
+
+        for (name, value) in [("wide", 1), ("square", 2), ("small", 3)] {
+            let region = atlas.region(name).unwrap();
+            let rect = region.rect;
+            assert!((rect.x..rect.x + rect.width).all(|x| (rect.y..rect.y + rect.height).all(|y| image.get_pixel(x, y).0[0] == value)));
+            assert_eq!(region.uv_max[0] - region.uv_min[0], rect.width as f32 / 32.0);
+            assert_eq!(region.uv_max[1], 1.0 - rect.y as f32 / 8.0);
+        }
+        assert_eq!(atlas.region("square").unwrap().rect, PackedRect { x: 0, y: 0, width: 8, height: 8 });
+    }
+
+    #[test]
+    fn test_sprite_sheet() {
+        let sheet = SpriteSheet::from_grid(&solid(48, 16, 9), 16, 16, Duration::from_millis(100));
+        let mut builder = TextureAtlasBuilder::new().with_padding(0);
+        builder.add_sprite_sheet("astro", &sheet);
+        let (atlas, _) = builder.build_image().unwrap();
+        assert_eq!((atlas.width, atlas.height), (32, 32));
+        assert!((0..3).all(|frame| atlas.frame("astro", frame).is_some()));
+        assert!(atlas.frame("astro", 3).is_none());
+        assert_eq!(atlas.frame("astro", 1).unwrap().rect, PackedRect { x: 16, y: 0, width: 16, height: 16 });
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut builder = TextureAtlasBuilder::new().with_max_size(16);
+        builder.add_image("first", solid(10, 10, 0));
+        builder.add_image("second", solid(10, 10, 0));
+        match builder.build_image() {
+            Err(AtlasError::DoesNotFit { name, max_size: 16 }) => assert_eq!(name, "second"),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        builder.add_image("first", solid(1, 1, 0));
+        match builder.build_image() {
+            Err(AtlasError::DuplicateName(name)) => assert_eq!(name, "first"),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
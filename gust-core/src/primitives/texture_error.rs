@@ -0,0 +1,53 @@
+use std::fmt;
+
+use glium::framebuffer::ValidationError;
+use glium::texture::TextureCreationError;
+
+/// Errors from creating a texture on the GPU.
+#[derive(Debug)]
+pub enum TextureError {
+    Creation(TextureCreationError),
+    /// Cube map faces are drawn in through a framebuffer, which couldn't be made.
+    Framebuffer(ValidationError),
+    /// An array without layers.
+    NoLayers,
+    /// The layers of an array differ in size, or the faces of a cube map aren't squares of one size.
+    MismatchedSize { layer: usize, expected: (u32, u32), found: (u32, u32) },
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureError::Creation(error) => write!(f, "failed to create texture: {}", error),
+            TextureError::Framebuffer(error) => write!(f, "failed to create framebuffer: {}", error),
+            TextureError::NoLayers => write!(f, "texture has no layers"),
+            TextureError::MismatchedSize { layer, expected, found } => write!(
+                f,
+                "layer {} is {}x{}, expected {}x{}",
+                layer, found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TextureError::Creation(error) => Some(error),
+            TextureError::Framebuffer(error) => Some(error),
+            TextureError::NoLayers | TextureError::MismatchedSize { .. } => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for TextureError {
+    fn from(error: TextureCreationError) -> Self {
+        TextureError::Creation(error)
+    }
+}
+
+impl From<ValidationError> for TextureError {
+    fn from(error: ValidationError) -> Self {
+        TextureError::Framebuffer(error)
+    }
+}
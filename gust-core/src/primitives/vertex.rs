@@ -1,10 +1,29 @@
 use glium::implement_vertex;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
     pub tex_coords: [f32; 2],
+    /// The direction of increasing `u` and, in `w`, the sign of the bitangent
+    /// `w * normal.cross(tangent)`. All zeros until `Mesh::compute_tangents` fills it in.
+    pub tangent: [f32; 4],
 }
 
-implement_vertex!(Vertex, position, normal, tex_coords);
\ No newline at end of file
+impl Vertex {
+    /// A vertex without a tangent.
+    pub fn new(position: [f32; 3], normal: [f32; 3], tex_coords: [f32; 2]) -> Self {
+        Vertex {
+            position,
+            normal,
+            tex_coords,
+            tangent: [0.0; 4],
+        }
+    }
+
+    pub fn has_tangent(&self) -> bool {
+        self.tangent[3] != 0.0
+    }
+}
+
+implement_vertex!(Vertex, position, normal, tex_coords, tangent);
\ No newline at end of file
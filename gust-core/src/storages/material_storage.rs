@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use crate::primitives::material::Material;
+
+pub struct MaterialStorage {
+    materials: HashMap<MaterialId, Material>,
+    material_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialId(pub u32);
+
+impl MaterialStorage {
+    pub fn new() -> Self {
+        MaterialStorage {
+            materials: HashMap::new(),
+            material_count: 0,
+        }
+    }
+
+    pub fn add_material(&mut self, material: Material) -> MaterialId {
+        self.material_count += 1;
+        let material_id = MaterialId(self.material_count as u32);
+
+        let res = self.materials.insert(material_id, material);
+
+        if res.is_some() {
+            self.material_count -= 1;
+        }
+
+        material_id
+    }
+
+    pub fn get_material(&self, material_id: MaterialId) -> Option<&Material> {
+        self.materials.get(&material_id)
+    }
+
+    pub fn get_material_mut(&mut self, material_id: MaterialId) -> Option<&mut Material> {
+        self.materials.get_mut(&material_id)
+    }
+
+    pub fn has_material(&self, material_id: MaterialId) -> bool {
+        self.materials.contains_key(&material_id)
+    }
+
+    pub fn material_count(&self) -> usize {
+        self.materials.len()
+    }
+}
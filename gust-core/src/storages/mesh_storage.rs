@@ -1,9 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use glium::Display;
+use glium::glutin::surface::WindowSurface;
+use gust_hierarchy::entity::Entity;
+use gust_hierarchy::world::World;
+
+use crate::components::mesh_component::MeshComponent;
+use crate::components::transform_component::TransformComponent;
+use crate::primitives::bounds::MeshBounds;
+use crate::primitives::gpu_mesh::GpuMesh;
 use crate::primitives::mesh::Mesh;
 
+/// Owns every mesh and its GPU buffers. A mesh is uploaded once by `upload` and only uploaded
+/// again after it is changed through `get_mesh_mut` or `replace_mesh`. The bounds of each mesh
+/// are cached the same way.
 pub struct MeshStorage {
-    pub meshes: HashMap<MeshId, Mesh>,
+    meshes: HashMap<MeshId, Mesh>,
+    gpu_meshes: HashMap<MeshId, GpuMesh>,
+    bounds: HashMap<MeshId, MeshBounds>,
+    dirty: HashSet<MeshId>,
     mesh_count: usize,
 }
 
@@ -14,6 +29,9 @@ impl MeshStorage {
     pub fn new() -> Self {
         MeshStorage {
             meshes: HashMap::new(),
+            gpu_meshes: HashMap::new(),
+            bounds: HashMap::new(),
+            dirty: HashSet::new(),
             mesh_count: 0,
         }
     }
@@ -21,21 +39,61 @@ impl MeshStorage {
     pub fn add_mesh(&mut self, mesh: Mesh) -> MeshId {
         self.mesh_count += 1;
         let mesh_id = MeshId(self.mesh_count as u32);
+        self.bounds.insert(mesh_id, mesh.bounds());
         let res = self.meshes.insert(mesh_id, mesh);
 
         if res.is_some() {
             self.mesh_count -= 1;
         }
 
+        self.dirty.insert(mesh_id);
         mesh_id
     }
 
+    /// Replaces the mesh behind `mesh_id`, returning the previous one.
+    pub fn replace_mesh(&mut self, mesh_id: MeshId, mesh: Mesh) -> Option<Mesh> {
+        self.dirty.insert(mesh_id);
+        self.bounds.insert(mesh_id, mesh.bounds());
+        self.meshes.insert(mesh_id, mesh)
+    }
+
+    /// Removes the mesh and frees its GPU buffers.
+    pub fn remove_mesh(&mut self, mesh_id: MeshId) -> Option<Mesh> {
+        self.dirty.remove(&mesh_id);
+        self.gpu_meshes.remove(&mesh_id);
+        self.bounds.remove(&mesh_id);
+        self.meshes.remove(&mesh_id)
+    }
+
     pub fn get_mesh(&self, mesh_id: MeshId) -> Option<&Mesh> {
         self.meshes.get(&mesh_id)
     }
 
+    /// Mutable access marks the mesh for upload and its bounds as outdated, since it may change.
     pub fn get_mesh_mut(&mut self, mesh_id: MeshId) -> Option<&mut Mesh> {
-        self.meshes.get_mut(&mesh_id)
+        let mesh = self.meshes.get_mut(&mesh_id);
+        if mesh.is_some() {
+            self.dirty.insert(mesh_id);
+            self.bounds.remove(&mesh_id);
+        }
+        mesh
+    }
+
+    /// The bounds of the mesh in its own space. Bounds that are outdated after `get_mesh_mut`
+    /// are computed again until the next `upload` caches them.
+    pub fn get_bounds(&self, mesh_id: MeshId) -> Option<MeshBounds> {
+        match self.bounds.get(&mesh_id) {
+            Some(&bounds) => Some(bounds),
+            None => self.meshes.get(&mesh_id).map(Mesh::bounds),
+        }
+    }
+
+    /// The bounds of an entity's mesh in world space, using its transform combined with those
+    /// of its parents. `None` if the entity has no mesh or transform.
+    pub fn world_bounds(&self, world: &World, entity: Entity) -> Option<MeshBounds> {
+        let mesh_id = world.get_component::<MeshComponent>(entity)?.0;
+        let matrix = TransformComponent::world_matrix(world, entity)?;
+        Some(self.get_bounds(mesh_id)?.transformed(&matrix))
     }
 
     pub fn has_mesh(&self, mesh_id: MeshId) -> bool {
@@ -45,4 +103,105 @@ impl MeshStorage {
     pub fn mesh_count(&self) -> usize {
         self.meshes.len()
     }
-}
\ No newline at end of file
+
+    /// Total number of unique vertices across all stored meshes.
+    pub fn vertex_count(&self) -> usize {
+        self.meshes.values().map(Mesh::vertex_count).sum()
+    }
+
+    /// Total number of indices across all stored meshes.
+    pub fn index_count(&self) -> usize {
+        self.meshes.values().map(Mesh::index_count).sum()
+    }
+
+    /// Returns `true` if the mesh has no GPU buffers yet or they are out of date.
+    pub fn needs_upload(&self, mesh_id: MeshId) -> bool {
+        self.dirty.contains(&mesh_id)
+    }
+
+    /// Uploads every new or changed mesh. Meshes that are already up to date are skipped.
+    pub fn upload(&mut self, display: &Display<WindowSurface>) {
+        for mesh_id in self.dirty.drain() {
+            if let Some(mesh) = self.meshes.get(&mesh_id) {
+                self.gpu_meshes.insert(mesh_id, GpuMesh::new(display, mesh));
+                self.bounds.entry(mesh_id).or_insert_with(|| mesh.bounds());
+            }
+        }
+    }
+
+    pub fn get_gpu_mesh(&self, mesh_id: MeshId) -> Option<&GpuMesh> {
+        self.gpu_meshes.get(&mesh_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gust_math::vectors::vect3::Vect3;
+
+    use super::*;
+
+    #[test]
+    fn test_changes_mark_mesh_for_upload() {
+        let mut storage = MeshStorage::new();
+        let mesh_id = storage.add_mesh(Mesh::new());
+        assert!(storage.needs_upload(mesh_id));
+
+        storage.dirty.clear();
+        storage.get_mesh(mesh_id);
+        assert!(!storage.needs_upload(mesh_id));
+
+        storage.get_mesh_mut(mesh_id);
+        assert!(storage.needs_upload(mesh_id));
+
+        storage.dirty.clear();
+        storage.replace_mesh(mesh_id, Mesh::new());
+        assert!(storage.needs_upload(mesh_id));
+
+        storage.remove_mesh(mesh_id);
+        assert!(!storage.needs_upload(mesh_id));
+        assert!(storage.get_gpu_mesh(mesh_id).is_none());
+    }
+
+    #[test]
+    fn test_bounds_follow_changes() {
+        let mut storage = MeshStorage::new();
+        let mesh_id = storage.add_mesh(Mesh::cube(2.0));
+        assert_eq!(storage.get_bounds(mesh_id), Some(Mesh::cube(2.0).bounds()));
+
+        *storage.get_mesh_mut(mesh_id).unwrap() = Mesh::cube(4.0);
+        assert_eq!(storage.get_bounds(mesh_id), Some(Mesh::cube(4.0).bounds()));
+        storage.replace_mesh(mesh_id, Mesh::cube(1.0));
+        assert_eq!(storage.get_bounds(mesh_id), Some(Mesh::cube(1.0).bounds()));
+
+        storage.remove_mesh(mesh_id);
+        assert_eq!(storage.get_bounds(mesh_id), None);
+    }
+
+    #[test]
+    fn test_world_bounds_use_parent_transforms() {
+        let mut storage = MeshStorage::new();
+        let mesh_id = storage.add_mesh(Mesh::cube(2.0));
+        let mut world = World::new();
+        let parent = world.spawn();
+        world.add_component(parent, TransformComponent::default().with_position([0.0, 0.0, 5.0].into()));
+        let child = world.spawn();
+        world.add_component(child, TransformComponent::default().with_scale([2.0, 2.0, 2.0].into()));
+        world.add_component(child, MeshComponent(mesh_id));
+        world.set_parent(parent, child);
+
+        let matrix = TransformComponent::world_matrix(&world, child).unwrap();
+        let bounds = storage.world_bounds(&world, child).unwrap();
+        assert_eq!(bounds, Mesh::cube(2.0).bounds().transformed(&matrix));
+        assert_eq!(bounds.aabb.size(), [4.0, 4.0, 4.0].into());
+        assert_eq!(bounds.aabb.center(), matrix.transpose() * Vect3::zeros());
+
+        // Without a mesh or with a parent lacking a transform there are no bounds.
+        assert_eq!(storage.world_bounds(&world, parent), None);
+        let bare = world.spawn();
+        let orphan = world.spawn();
+        world.add_component(orphan, TransformComponent::default());
+        world.add_component(orphan, MeshComponent(mesh_id));
+        world.set_parent(bare, orphan);
+        assert_eq!(storage.world_bounds(&world, orphan), None);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod texture_storage;
-pub mod mesh_storage;
\ No newline at end of file
+pub mod mesh_storage;
+pub mod material_storage;
\ No newline at end of file
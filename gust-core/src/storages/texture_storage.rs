@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
-use glium::Texture2d;
+use crate::primitives::texture::Texture;
 
+/// Owns every texture: 2D textures, arrays and cube maps, each with its sampler options.
 pub struct TextureStorage {
-    textures: HashMap<TextureId, Texture2d>,
+    textures: HashMap<TextureId, Texture>,
     texture_count: usize,
 }
 
@@ -18,7 +19,7 @@ impl TextureStorage {
         }
     }
 
-    pub fn add_texture(&mut self, texture: Texture2d) -> TextureId {
+    pub fn add_texture(&mut self, texture: Texture) -> TextureId {
         self.texture_count += 1;
         let texture_id = TextureId(self.texture_count as u32);
 
@@ -31,11 +32,20 @@ impl TextureStorage {
         texture_id
     }
 
-    pub fn get_texture(&self, texture_id: TextureId) -> Option<&Texture2d> {
+    /// Replaces the texture behind `texture_id`, returning the previous one.
+    pub fn replace_texture(&mut self, texture_id: TextureId, texture: Texture) -> Option<Texture> {
+        self.textures.insert(texture_id, texture)
+    }
+
+    pub fn remove_texture(&mut self, texture_id: TextureId) -> Option<Texture> {
+        self.textures.remove(&texture_id)
+    }
+
+    pub fn get_texture(&self, texture_id: TextureId) -> Option<&Texture> {
         self.textures.get(&texture_id)
     }
 
-    pub fn get_texture_mut(&mut self, texture_id: TextureId) -> Option<&mut Texture2d> {
+    pub fn get_texture_mut(&mut self, texture_id: TextureId) -> Option<&mut Texture> {
         self.textures.get_mut(&texture_id)
     }
 
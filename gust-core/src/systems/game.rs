@@ -2,21 +2,28 @@ use std::f32::consts::PI;
 use std::path::Path;
 use std::time::Instant;
 
-use glium::{Display, Texture2d};
+use glium::Display;
 use glium::glutin::surface::WindowSurface;
 
 use gust_hierarchy::world::World;
 
+use crate::assets::asset_server::AssetServer;
+use crate::assets::file_watcher::HOT_RELOAD_INTERVAL;
+use crate::assets::handle::Handle;
 use crate::components::camera_component::CameraComponent;
+use crate::components::material_component::MaterialComponent;
 use crate::components::mesh_component::MeshComponent;
 use crate::components::player_component::PlayerComponent;
-use crate::components::texture_component::TextureComponent;
 use crate::components::transform_component::TransformComponent;
 use crate::components::velocity_component::VelocityComponent;
 use crate::handlers::event_handler::EventHandler;
 use crate::handlers::input_handler::InputHandler;
-use crate::objects::intermediaries::wavefront_object::WavefrontObject;
+use crate::objects::intermediaries::wavefront_material::WavefrontMaterialLibrary;
+use crate::primitives::color::Color;
+use crate::primitives::material::Material;
 use crate::primitives::mesh::Mesh;
+use crate::primitives::texture::{Texture, TextureOptions};
+use crate::storages::material_storage::MaterialStorage;
 use crate::storages::mesh_storage::MeshStorage;
 use crate::storages::texture_storage::TextureStorage;
 use crate::systems::render_system::RenderSystem;
@@ -30,6 +37,10 @@ pub struct Game {
     pub world: World,
     pub mesh_storage: MeshStorage,
     pub texture_storage: TextureStorage,
+    pub material_storage: MaterialStorage,
+    pub asset_server: AssetServer,
+    pub mesh_handles: Vec<Handle<Mesh>>,
+    pub texture_handles: Vec<Handle<Texture>>,
 }
 
 impl Game {
@@ -42,31 +53,40 @@ impl Game {
             world: World::new(),
             mesh_storage: MeshStorage::new(),
             texture_storage: TextureStorage::new(),
+            material_storage: MaterialStorage::new(),
+            asset_server: AssetServer::new(),
+            mesh_handles: Vec::new(),
+            texture_handles: Vec::new(),
         }
     }
 
     fn construct_scene(&mut self, display: &Display<WindowSurface>) -> World {
 
-        // Load the meshes
-        let monkey_mesh = Mesh::from_wavefront(WavefrontObject::parse(Path::new("./resources/assets/objects/monkey.obj")));
-        let floor_mesh = Mesh::from_wavefront(WavefrontObject::parse(Path::new("./resources/assets/objects/floor.obj")));
+        // Load the meshes in the background, the OBJ files through their binary caches
+        let monkey_mesh = self.asset_server.load_mesh_async(Path::new("./resources/assets/objects/monkey.obj"), &mut self.mesh_storage);
+        let floor_mesh = self.asset_server.load_mesh_async(Path::new("./resources/assets/objects/floor.obj"), &mut self.mesh_storage);
+        let monkey_mesh_id = monkey_mesh.id();
+        let floor_mesh_id = floor_mesh.id();
 
-        // Add them to the mesh storage
-        let monkey_mesh_id = self.mesh_storage.add_mesh(monkey_mesh);
-        let floor_mesh_id = self.mesh_storage.add_mesh(floor_mesh);
+        // Load the textures, placeholders are drawn until they are decoded
+        let monkey_texture = self.asset_server.load_texture_async(Path::new("./resources/assets/green.png"), TextureOptions::default(), display, &mut self.texture_storage).expect("Failed to create placeholder texture");
+        let floor_texture = self.asset_server.load_texture_async(Path::new("./resources/assets/wood.jpg"), TextureOptions::default().with_anisotropy(16), display, &mut self.texture_storage).expect("Failed to create placeholder texture");
+        let monkey_texture_id = monkey_texture.id();
+        let floor_texture_id = floor_texture.id();
 
-        // Load the textures
-        let image = image::load(std::io::Cursor::new(&include_bytes!("../../../resources/assets/green.png")), image::ImageFormat::Png).unwrap().to_rgba8();
-        let image = glium::texture::RawImage2d::from_raw_rgba_reversed(&image.clone().into_raw(), image.dimensions());
-        let texture = Texture2d::new(display, image).unwrap();
+        // The entities only know the ids, so the game keeps the assets loaded
+        self.mesh_handles = vec![monkey_mesh, floor_mesh];
+        self.texture_handles = vec![monkey_texture, floor_texture];
 
-        let floor_image = image::load(std::io::Cursor::new(&include_bytes!("../../../resources/assets/wood.jpg")), image::ImageFormat::Jpeg).unwrap().to_rgba8();
-        let floor_image = glium::texture::RawImage2d::from_raw_rgba_reversed(&floor_image.clone().into_raw(), floor_image.dimensions());
-        let floor_texture = Texture2d::new(display, floor_image).unwrap();
+        // Make the materials, the floor takes its shading from floor.mtl
+        let floor_library = WavefrontMaterialLibrary::parse(Path::new("./resources/assets/objects/floor.mtl")).expect("Failed to load floor.mtl");
+        let mut floor_material = Material::from_wavefront(floor_library.get("Material.001").unwrap(), |_| None);
+        floor_material.diffuse_texture = Some(floor_texture_id);
+        // Blender always exports `Ka 1.0`, which would wash out the lighting entirely
+        floor_material.ambient = Color::BLACK;
 
-        // Add them to the texture storage
-        let monkey_texture_id = self.texture_storage.add_texture(texture);
-        let floor_texture_id = self.texture_storage.add_texture(floor_texture);
+        let monkey_material_id = self.material_storage.add_material(Material::textured(monkey_texture_id));
+        let floor_material_id = self.material_storage.add_material(floor_material);
 
         let mut world = World::new();
 
@@ -98,7 +118,7 @@ impl Game {
 
         world.add_component(monkey, monkey_transform);
         world.add_component(monkey, MeshComponent(monkey_mesh_id));
-        world.add_component(monkey, TextureComponent(monkey_texture_id));
+        world.add_component(monkey, MaterialComponent(monkey_material_id));
 
         // Make floor object
         let floor = world.spawn();
@@ -106,7 +126,7 @@ impl Game {
 
         world.add_component(floor, floor_transform);
         world.add_component(floor, MeshComponent(floor_mesh_id));
-        world.add_component(floor, TextureComponent(floor_texture_id));
+        world.add_component(floor, MaterialComponent(floor_material_id));
 
         let transform_entity = world.spawn();
         let transform = TransformComponent::default().with_scale([2.0, 1.0, 1.0].into());
@@ -125,6 +145,9 @@ impl Game {
     pub fn run(&mut self) {
         let (event_handler, display) = EventHandler::new();
 
+        // Artists can re-export assets while the game runs
+        self.asset_server.watch_for_changes(HOT_RELOAD_INTERVAL);
+
         self.world = self.construct_scene(&display);
 
         let render_system = RenderSystem::new(display);
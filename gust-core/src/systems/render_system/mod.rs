@@ -1,26 +1,37 @@
-use glium::{Display, Frame, Program, Surface, Texture2d, uniform, VertexBuffer};
+pub mod shader_error;
+
+use std::path::Path;
+
+use glium::{Display, Frame, Program, Surface, Texture2d, uniform};
 use glium::DrawParameters;
 use glium::glutin::surface::WindowSurface;
-use glium::uniforms::{UniformBuffer, Uniforms};
-
-use gust_hierarchy::entity::Entity;
-use gust_hierarchy::world::World;
-use gust_math::matrices::mat4::Mat4;
+use glium::uniforms::{AsUniformValue, Sampler, SamplerBehavior, UniformBuffer, Uniforms};
 
+use crate::assets::file_watcher::{FileWatcher, HOT_RELOAD_INTERVAL};
 use crate::components::camera_component::CameraComponent;
+use crate::components::lod_component::LodComponent;
+use crate::components::material_component::MaterialComponent;
 use crate::components::mesh_component::MeshComponent;
 use crate::components::player_component::PlayerComponent;
-use crate::components::texture_component::TextureComponent;
 use crate::components::transform_component::TransformComponent;
 use crate::primitives::lights_block::LightsBlock;
-use crate::primitives::mesh::Mesh;
-use crate::primitives::vertex::Vertex;
+use crate::primitives::gpu_mesh::GpuMesh;
+use crate::primitives::material::Material;
+use crate::primitives::texture::TextureData;
 use crate::systems::game::Game;
+use crate::systems::render_system::shader_error::ShaderError;
+
+/// Watched for changes, the shaders built into the binary are used until one happens.
+const VERTEX_SHADER_PATH: &str = "./resources/shaders/vert.glsl";
+const FRAGMENT_SHADER_PATH: &str = "./resources/shaders/frac.glsl";
 
 pub struct RenderSystem {
     pub display: Display<WindowSurface>,
     program: Program,
     params: DrawParameters<'static>,
+    /// Bound for materials without a diffuse texture, so they show their plain color.
+    white_texture: Texture2d,
+    shader_watcher: FileWatcher,
 }
 
 impl RenderSystem {
@@ -45,14 +56,38 @@ impl RenderSystem {
             ..Default::default()
         };
 
+        let white_texture = Texture2d::new(&display, vec![vec![(255u8, 255u8, 255u8, 255u8)]]).unwrap();
+
+        let mut shader_watcher = FileWatcher::new(HOT_RELOAD_INTERVAL);
+        shader_watcher.watch(Path::new(VERTEX_SHADER_PATH));
+        shader_watcher.watch(Path::new(FRAGMENT_SHADER_PATH));
+
         RenderSystem {
             display,
             program,
             params,
+            white_texture,
+            shader_watcher,
         }
     }
 
-    pub fn render(&self, game: &Game, buffer: &UniformBuffer<LightsBlock>) {
+    /// Compiles the shaders again if either file changed. Returns whether they were replaced,
+    /// on an error the previous program stays in use.
+    pub fn reload_changed_shaders(&mut self) -> Result<bool, ShaderError> {
+        if self.shader_watcher.poll().is_empty() {
+            return Ok(false);
+        }
+
+        let vert_shader_string = std::fs::read_to_string(VERTEX_SHADER_PATH)?;
+        let frac_shader_string = std::fs::read_to_string(FRAGMENT_SHADER_PATH)?;
+        self.program = Program::from_source(&self.display, &vert_shader_string, &frac_shader_string, None)?;
+        Ok(true)
+    }
+
+    pub fn render(&self, game: &mut Game, buffer: &UniformBuffer<LightsBlock>) {
+        game.asset_server.update(&self.display, &mut game.mesh_storage, &mut game.texture_storage);
+        game.asset_server.unload_unused(&mut game.mesh_storage, &mut game.texture_storage);
+        game.mesh_storage.upload(&self.display);
         self.draw_objects(game, buffer);
     }
 
@@ -71,42 +106,62 @@ impl RenderSystem {
             if game.world.has_component::<PlayerComponent>(*entity)
                 || !game.world.has_component::<TransformComponent>(*entity)
                 || !game.world.has_component::<MeshComponent>(*entity)
-                || !game.world.has_component::<TextureComponent>(*entity)
-                || !game.world.has_component::<MeshComponent>(*entity) {
+                || !game.world.has_component::<MaterialComponent>(*entity) {
                 continue;
             }
 
-            let object_transform_id = game.world.get_component::<TransformComponent>(*entity).unwrap();
             let mesh_id = game.world.get_component::<MeshComponent>(*entity).unwrap();
-            let texture_id = game.world.get_component::<TextureComponent>(*entity).unwrap();
-
-            let object_transform = object_transform_id.get_transform_matrix();
-            let object_transform = self.propagate_transform(*entity, object_transform, &game.world).to_slices();
-            let mesh = game.mesh_storage.get_mesh(mesh_id.0).unwrap();
-            let texture = game.texture_storage.get_texture(texture_id.0).unwrap();
-
-            self.draw_object(&mut target, &self.display, object_transform, player_view, player_perspective, mesh, texture, buffer);
+            let material_id = game.world.get_component::<MaterialComponent>(*entity).unwrap();
+
+            // The entity's own transform was checked above, so only a parent can be missing one.
+            let object_transform = TransformComponent::world_matrix(&game.world, *entity)
+                .expect("Parent entity does not have a transform component")
+                .to_slices();
+            let mesh_id = match game.world.get_component::<LodComponent>(*entity) {
+                Some(lod) => game
+                    .mesh_storage
+                    .world_bounds(&game.world, *entity)
+                    .and_then(|bounds| lod.select(player_camera.screen_size(player_transform.position, &bounds.sphere)))
+                    .unwrap_or(mesh_id.0),
+                None => mesh_id.0,
+            };
+            // Components keep plain ids, so an entity can outlive the handles keeping its assets
+            // loaded. Without a mesh or material it isn't drawn, without a texture it is plain.
+            let mesh = game.mesh_storage.get_gpu_mesh(mesh_id);
+            let material = game.material_storage.get_material(material_id.0);
+            let (Some(mesh), Some(material)) = (mesh, material) else {
+                continue;
+            };
+            let texture = material.diffuse_texture.and_then(|texture_id| game.texture_storage.get_texture(texture_id));
+
+            // Arrays and cube maps can't be bound as a material's texture, those show the plain color too.
+            match texture.map(|texture| (&texture.data, texture.options.sampler_behavior())) {
+                Some((TextureData::SrgbTexture2d(texture), behavior)) => {
+                    self.draw_object(&mut target, object_transform, player_view, player_perspective, mesh, material, Sampler(texture, behavior), buffer)
+                }
+                Some((TextureData::Texture2d(texture), behavior)) => {
+                    self.draw_object(&mut target, object_transform, player_view, player_perspective, mesh, material, Sampler(texture, behavior), buffer)
+                }
+                _ => {
+                    let white = Sampler(&self.white_texture, SamplerBehavior::default());
+                    self.draw_object(&mut target, object_transform, player_view, player_perspective, mesh, material, white, buffer)
+                }
+            }
         }
 
         target.finish().unwrap();
     }
 
-    pub fn draw_object(&self, target: &mut Frame, display: &Display<WindowSurface>, object_transform: [[f32; 4]; 4], player_view: [[f32; 4]; 4], player_perspective: [[f32; 4]; 4], mesh: &Mesh, texture: &Texture2d, buffer: &UniformBuffer<LightsBlock>) {
-        let uniforms = self.get_uniforms(object_transform, player_view, player_perspective, &texture, &buffer);
-
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
-
-        let flattened_triangles: Vec<Vertex> = mesh.triangles
-            .iter()
-            .flat_map(|triangle| triangle.iter().cloned())
-            .collect();
-
-        let vertex_buffer = VertexBuffer::new(display, &flattened_triangles).unwrap();
+    pub fn draw_object<'a, T>(&self, target: &mut Frame, object_transform: [[f32; 4]; 4], player_view: [[f32; 4]; 4], player_perspective: [[f32; 4]; 4], mesh: &GpuMesh, material: &Material, texture: Sampler<'a, T>, buffer: &'a UniformBuffer<LightsBlock>)
+    where
+        Sampler<'a, T>: AsUniformValue,
+    {
+        let uniforms = self.get_uniforms(object_transform, player_view, player_perspective, material, texture, buffer);
 
         target
             .draw(
-                &vertex_buffer,
-                &indices,
+                &mesh.vertex_buffer,
+                &mesh.index_buffer,
                 &self.program,
                 &uniforms,
                 &self.params,
@@ -114,7 +169,10 @@ impl RenderSystem {
             .unwrap();
     }
 
-    fn get_uniforms<'a>(&'a self, model_transform: [[f32; 4]; 4], player_view: [[f32; 4]; 4], player_perspective: [[f32; 4]; 4], texture: &'a Texture2d, buffer: &'a UniformBuffer<LightsBlock>) -> impl Uniforms + 'a {
+    fn get_uniforms<'a, T>(&self, model_transform: [[f32; 4]; 4], player_view: [[f32; 4]; 4], player_perspective: [[f32; 4]; 4], material: &Material, texture: Sampler<'a, T>, buffer: &'a UniformBuffer<LightsBlock>) -> impl Uniforms + 'a
+    where
+        Sampler<'a, T>: AsUniformValue,
+    {
         let lights_used = 5;
 
         uniform! {
@@ -122,30 +180,13 @@ impl RenderSystem {
             view : player_view,
             model: model_transform,
             u_texture: texture,
+            u_ambient_color: material.ambient.to_rgb_array(),
+            u_diffuse_color: material.diffuse.to_array(),
+            u_specular_color: material.specular.to_rgb_array(),
+            u_emissive_color: material.emissive.to_rgb_array(),
+            u_shininess: material.shininess,
             lightsBlock: &*buffer,
             u_light_count : lights_used,
         }
     }
-
-    fn propagate_transform(&self, entity: Entity, entity_transform: Mat4, world: &World) -> Mat4 {
-        // Get parent transform if it exists
-        let parent = world.get_parent(entity);
-
-        if parent.is_none() {
-            return entity_transform;
-        }
-
-        let parent = parent.unwrap();
-        if let None = world.get_component::<TransformComponent>(parent) {
-            // If the parent doesn't have a transform, it cannot exist in the scene
-            // Hence, we return an error
-            panic!("Parent entity does not have a transform component");
-        }
-        let parent_transform = world.get_component::<TransformComponent>(parent).unwrap().get_transform_matrix();
-
-        let new_transform = parent_transform * entity_transform;
-
-        // Recursively propagate the transform
-        self.propagate_transform(parent, new_transform, world)
-    }
 }
@@ -0,0 +1,40 @@
+use std::fmt;
+
+use glium::ProgramCreationError;
+
+/// Errors from reloading the shaders, the previous program is kept when one happens.
+#[derive(Debug)]
+pub enum ShaderError {
+    Io(std::io::Error),
+    Compile(ProgramCreationError),
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderError::Io(error) => write!(f, "failed to read shader: {}", error),
+            ShaderError::Compile(error) => write!(f, "failed to compile shaders: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShaderError::Io(error) => Some(error),
+            ShaderError::Compile(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for ShaderError {
+    fn from(error: std::io::Error) -> Self {
+        ShaderError::Io(error)
+    }
+}
+
+impl From<ProgramCreationError> for ShaderError {
+    fn from(error: ProgramCreationError) -> Self {
+        ShaderError::Compile(error)
+    }
+}
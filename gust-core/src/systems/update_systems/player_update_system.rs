@@ -22,7 +22,7 @@ impl PlayerUpdateSystem {
         PlayerUpdateSystem::update_position(dt, transform, game_input);
     }
 
-    fn update_direction(dt: &f32, player_transform: &mut TransformComponent, game_input: &InputHandler) {
+    fn update_direction(_dt: &f32, player_transform: &mut TransformComponent, game_input: &InputHandler) {
         let delta_x = game_input.mouse_input.mouse_delta.0;
         let delta_y = game_input.mouse_input.mouse_delta.1;
 
@@ -34,62 +34,61 @@ impl PlayerUpdateSystem {
 
         let mut new_direction = player_transform.forward.clone();
 
-        let right = player_transform.forward.cross(&player_transform.up).normalize();
+        let right = player_transform.forward.cross(&player_transform.up).normalized();
 
         let vertical_angle = player_transform.forward.dot(&player_transform.up).acos();
 
         // Clamp the vertical angle
         if vertical_angle - delta_y > Self::MAX_VERTICAL_ANGLE || vertical_angle - delta_y < Self::MIN_VERTICAL_ANGLE {
-            new_direction = rotation_matrix_side.clone() * new_direction.normalize();
+            new_direction = rotation_matrix_side * new_direction.normalized();
             player_transform.forward = new_direction;
             return;
         }
 
         let rotation_matrix_up = Mat3::rotation_matrix(&right, delta_y);
 
-        new_direction = rotation_matrix_up * rotation_matrix_side * new_direction.normalize();
+        new_direction = rotation_matrix_up * rotation_matrix_side * new_direction.normalized();
 
-        new_direction.normalize();
-
-        player_transform.forward = (dt.clone() * new_direction).normalize();
+        player_transform.forward = new_direction.normalized();
     }
 
     fn update_position(dt: &f32, player_transform: &mut TransformComponent, game_input: &InputHandler) {
         let look_direction = player_transform.forward.clone();
         let up = player_transform.up.clone();
 
-        let horizontal = (look_direction - up * look_direction.dot(&up)).normalize();
-        let right = horizontal.cross(&up).normalize();
+        let horizontal = (look_direction - up * look_direction.dot(&up)).normalized();
+        let right = horizontal.cross(&up).normalized();
 
         let mut cumulative_vector = Vect3::from_slice(&[0.0, 0.0, 0.0]);
 
         if game_input.keyboard_input.is_character_pressed('w') {
-            cumulative_vector = cumulative_vector + horizontal;
+            cumulative_vector += horizontal;
         }
 
         if game_input.keyboard_input.is_character_pressed('s') {
-            cumulative_vector = cumulative_vector - horizontal;
+            cumulative_vector -= horizontal;
         }
 
         if game_input.keyboard_input.is_character_pressed('a') {
-            cumulative_vector = cumulative_vector + right;
+            cumulative_vector += right;
         }
 
         if game_input.keyboard_input.is_character_pressed('d') {
-            cumulative_vector = cumulative_vector - right;
+            cumulative_vector -= right;
         }
 
         if game_input.keyboard_input.is_key_pressed(Key::Named(NamedKey::Space)) {
-            cumulative_vector = cumulative_vector + up;
+            cumulative_vector += up;
         }
 
         if game_input.keyboard_input.is_key_pressed(Key::Named(NamedKey::Shift)) {
-            cumulative_vector = cumulative_vector - up;
+            cumulative_vector -= up;
         }
 
         let temp_speed = 10.0;
 
-        cumulative_vector.normalize();
-        player_transform.position = player_transform.position + cumulative_vector * temp_speed * dt.clone();
+        if let Some(direction) = cumulative_vector.try_normalized() {
+            player_transform.position += direction * temp_speed * *dt;
+        }
     }
 }
\ No newline at end of file
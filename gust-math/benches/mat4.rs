@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use gust_math::matrices::mat4::Mat4;
+use gust_math::matrices::matrix::Matrix4f;
+use gust_math::vectors::vect3::Vect3;
+use gust_math::vectors::vect4::Vect4;
+use gust_math::vectors::vector::Vector4f;
+
+fn sample_matrices() -> (Mat4, Mat4) {
+    let a = Mat4::identity()
+        .translate(Vect3::new(1.0, 2.0, 3.0))
+        .rotate(0.7, Vect3::new(0.0, 0.0, 1.0))
+        .scale(Vect3::new(2.0, 2.0, 2.0));
+    let b = Mat4::identity()
+        .rotate(-1.3, Vect3::new(1.0, 0.0, 0.0))
+        .translate(Vect3::new(-4.0, 0.5, 9.0));
+    (a, b)
+}
+
+fn bench_mat4_mul(c: &mut Criterion) {
+    let (a, b) = sample_matrices();
+    let (a_generic, b_generic) = (Matrix4f::from(a), Matrix4f::from(b));
+
+    let mut group = c.benchmark_group("mat4 * mat4");
+    group.bench_function("mat4", |bencher| {
+        bencher.iter(|| black_box(a) * black_box(b))
+    });
+    group.bench_function("generic", |bencher| {
+        bencher.iter(|| black_box(a_generic) * black_box(b_generic))
+    });
+    group.finish();
+}
+
+fn bench_mat4_mul_vect4(c: &mut Criterion) {
+    let (a, _) = sample_matrices();
+    let a_generic = Matrix4f::from(a);
+    let v = Vect4::from([1.0, -2.0, 3.5, 1.0]);
+    let v_generic = Vector4f::from(v);
+
+    let mut group = c.benchmark_group("mat4 * vect4");
+    group.bench_function("mat4", |bencher| {
+        bencher.iter(|| black_box(a) * black_box(v))
+    });
+    group.bench_function("generic", |bencher| {
+        bencher.iter(|| black_box(a_generic) * black_box(v_generic))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_mat4_mul, bench_mat4_mul_vect4);
+criterion_main!(benches);
@@ -0,0 +1,289 @@
+//! Approximate floating point comparisons for all gust-math types.
+//!
+//! Exact `==` on floats breaks as soon as a value goes through a rotation or an inverse, so
+//! tests and gameplay code should compare with one of the tolerances below instead:
+//!
+//! - `abs_diff_eq`: the values differ by at most `epsilon`. Good near zero.
+//! - `relative_eq`: the difference is small relative to the larger magnitude, falling back to
+//!   `epsilon` near zero. Good for values of arbitrary scale.
+//! - `ulps_eq`: the values are at most `max_ulps` representable floats apart, falling back to
+//!   `epsilon` near zero.
+//!
+//! Composite types compare component-wise and are only equal if every component is. NaN is
+//! never approximately equal to anything.
+
+use num_traits::Float;
+
+use crate::matrices::mat3::Mat3;
+use crate::matrices::mat4::Mat4;
+use crate::matrices::matrix::Matrix;
+use crate::vectors::vect3::Vect3;
+use crate::vectors::vect4::Vect4;
+use crate::vectors::vector::Vector;
+
+pub trait ApproxEq {
+    type Epsilon: Copy;
+
+    fn default_epsilon() -> Self::Epsilon;
+
+    fn default_max_relative() -> Self::Epsilon;
+
+    fn default_max_ulps() -> u32 {
+        4
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool;
+
+    fn relative_eq(&self, other: &Self, epsilon: Self::Epsilon, max_relative: Self::Epsilon) -> bool;
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool;
+
+    /// Relative comparison with the default tolerances of the component type.
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.relative_eq(other, Self::default_epsilon(), Self::default_max_relative())
+    }
+}
+
+macro_rules! impl_approx_eq_float {
+    ($float:ty, $bits:ty, $epsilon:expr, $max_relative:expr) => {
+        impl ApproxEq for $float {
+            type Epsilon = $float;
+
+            fn default_epsilon() -> $float {
+                $epsilon
+            }
+
+            fn default_max_relative() -> $float {
+                $max_relative
+            }
+
+            fn abs_diff_eq(&self, other: &$float, epsilon: $float) -> bool {
+                (self - other).abs() <= epsilon
+            }
+
+            fn relative_eq(&self, other: &$float, epsilon: $float, max_relative: $float) -> bool {
+                if self == other {
+                    return true;
+                }
+                if self.is_infinite() || other.is_infinite() {
+                    return false;
+                }
+                let diff = (self - other).abs();
+                if diff <= epsilon {
+                    return true;
+                }
+                diff <= self.abs().max(other.abs()) * max_relative
+            }
+
+            fn ulps_eq(&self, other: &$float, epsilon: $float, max_ulps: u32) -> bool {
+                if self.abs_diff_eq(other, epsilon) {
+                    return true;
+                }
+                if self.is_nan() || other.is_nan() || self.is_sign_positive() != other.is_sign_positive() {
+                    return false;
+                }
+                let (a, b) = (self.to_bits(), other.to_bits());
+                a.abs_diff(b) <= max_ulps as $bits
+            }
+        }
+    };
+}
+
+impl_approx_eq_float!(f32, u32, 1e-6, 1e-5);
+impl_approx_eq_float!(f64, u64, 1e-12, 1e-10);
+
+/// Implements `ApproxEq` for a type by comparing the scalars yielded by `$components`.
+macro_rules! impl_approx_eq_components {
+    ($type:ty, $scalar:ty, |$value:ident| $components:expr) => {
+        impl ApproxEq for $type {
+            type Epsilon = $scalar;
+
+            fn default_epsilon() -> $scalar {
+                <$scalar as ApproxEq>::default_epsilon()
+            }
+
+            fn default_max_relative() -> $scalar {
+                <$scalar as ApproxEq>::default_max_relative()
+            }
+
+            fn abs_diff_eq(&self, other: &Self, epsilon: $scalar) -> bool {
+                let components = |$value: &Self| $components;
+                components(self).into_iter().zip(components(other)).all(|(a, b)| a.abs_diff_eq(&b, epsilon))
+            }
+
+            fn relative_eq(&self, other: &Self, epsilon: $scalar, max_relative: $scalar) -> bool {
+                let components = |$value: &Self| $components;
+                components(self).into_iter().zip(components(other)).all(|(a, b)| a.relative_eq(&b, epsilon, max_relative))
+            }
+
+            fn ulps_eq(&self, other: &Self, epsilon: $scalar, max_ulps: u32) -> bool {
+                let components = |$value: &Self| $components;
+                components(self).into_iter().zip(components(other)).all(|(a, b)| a.ulps_eq(&b, epsilon, max_ulps))
+            }
+        }
+    };
+}
+
+impl_approx_eq_components!(Vect3, f32, |v| v.as_slice());
+impl_approx_eq_components!(Vect4, f32, |v| v.to_array());
+impl_approx_eq_components!(Mat3, f32, |m| m.to_array().into_iter().flatten());
+impl_approx_eq_components!(Mat4, f32, |m| m.to_slice());
+
+impl<T: Float + ApproxEq<Epsilon = T>, const N: usize> ApproxEq for Vector<T, N> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::default_epsilon()
+    }
+
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        self.data.iter().zip(other.data.iter()).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        self.data.iter().zip(other.data.iter()).all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: T, max_ulps: u32) -> bool {
+        self.data.iter().zip(other.data.iter()).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+}
+
+impl<T: Float + ApproxEq<Epsilon = T>, const R: usize, const C: usize> ApproxEq for Matrix<T, R, C> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::default_epsilon()
+    }
+
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        (0..R).all(|i| self.row(i).abs_diff_eq(&other.row(i), epsilon))
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        (0..R).all(|i| self.row(i).relative_eq(&other.row(i), epsilon, max_relative))
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: T, max_ulps: u32) -> bool {
+        (0..R).all(|i| self.row(i).ulps_eq(&other.row(i), epsilon, max_ulps))
+    }
+}
+
+/// Used by `assert_approx_eq!` to pick the default epsilon for the type of a value.
+#[doc(hidden)]
+pub fn default_epsilon_of<T: ApproxEq>(_value: &T) -> T::Epsilon {
+    T::default_epsilon()
+}
+
+/// Asserts that two values are approximately equal, using `ApproxEq::approx_eq` by default or
+/// the given tolerance with `epsilon = ...`, `max_relative = ...` or `max_ulps = ...`.
+///
+/// ```
+/// use gust_math::assert_approx_eq;
+/// use gust_math::vectors::vect3::Vect3;
+///
+/// let v = Vect3::new(0.1, 0.2, 0.3) * 3.0;
+/// assert_approx_eq!(v, Vect3::new(0.3, 0.6, 0.9));
+/// assert_approx_eq!(v.x, 0.3, epsilon = 1e-6);
+/// ```
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if !$crate::approx::ApproxEq::approx_eq(left, right) {
+            panic!("assertion `left ≈ right` failed\n  left: {:?}\n right: {:?}", left, right);
+        }
+    }};
+    ($left:expr, $right:expr, epsilon = $epsilon:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if !$crate::approx::ApproxEq::abs_diff_eq(left, right, $epsilon) {
+            panic!("assertion `left ≈ right` failed (epsilon = {:?})\n  left: {:?}\n right: {:?}", $epsilon, left, right);
+        }
+    }};
+    ($left:expr, $right:expr, max_relative = $max_relative:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        let epsilon = $crate::approx::default_epsilon_of(left);
+        if !$crate::approx::ApproxEq::relative_eq(left, right, epsilon, $max_relative) {
+            panic!("assertion `left ≈ right` failed (max_relative = {:?})\n  left: {:?}\n right: {:?}", $max_relative, left, right);
+        }
+    }};
+    ($left:expr, $right:expr, max_ulps = $max_ulps:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        let epsilon = $crate::approx::default_epsilon_of(left);
+        if !$crate::approx::ApproxEq::ulps_eq(left, right, epsilon, $max_ulps) {
+            panic!("assertion `left ≈ right` failed (max_ulps = {:?})\n  left: {:?}\n right: {:?}", $max_ulps, left, right);
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vectors::vector::Vector3d;
+
+    #[test]
+    fn test_abs_diff_eq() {
+        assert!(1.0f32.abs_diff_eq(&1.05, 0.1));
+        assert!(!1.0f32.abs_diff_eq(&1.2, 0.1));
+    }
+
+    #[test]
+    fn test_relative_eq_scales_with_magnitude() {
+        assert!(1_000_000.0f32.relative_eq(&1_000_001.0, 1e-6, 1e-5));
+        assert!(!1.0f32.relative_eq(&1.001, 1e-6, 1e-5));
+    }
+
+    #[test]
+    fn test_ulps_eq() {
+        let a = 1.0f32;
+        let b = f32::from_bits(a.to_bits() + 3);
+        assert!(a.ulps_eq(&b, 0.0, 4));
+        assert!(!a.ulps_eq(&b, 0.0, 2));
+        assert!(!1.0f32.ulps_eq(&-1.0, 0.0, u32::MAX));
+    }
+
+    #[test]
+    fn test_nan_is_never_equal() {
+        assert!(!f32::NAN.approx_eq(&f32::NAN));
+        assert!(!f32::NAN.ulps_eq(&f32::NAN, 1.0, u32::MAX));
+        assert!(!Vect3::new(f32::NAN, 0.0, 0.0).approx_eq(&Vect3::new(f32::NAN, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_infinity() {
+        assert!(f32::INFINITY.approx_eq(&f32::INFINITY));
+        assert!(!f32::INFINITY.approx_eq(&f32::MAX));
+    }
+
+    #[test]
+    fn test_rotation_roundtrip() {
+        let axis = Vect3::new(0.0, 0.0, 1.0);
+        let rotation = Mat3::rotation_matrix(&axis, 0.3) * Mat3::rotation_matrix(&axis, -0.3);
+        assert_ne!(rotation, Mat3::identity());
+        assert_approx_eq!(rotation, Mat3::identity());
+
+        let transform = Mat4::identity().rotate(1.2, axis).rotate(-1.2, axis);
+        assert_approx_eq!(transform, Mat4::identity(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_composite_types() {
+        assert_approx_eq!(Vect4::from([1.0, 2.0, 3.0, 4.0]) * 0.1, Vect4::from([0.1, 0.2, 0.3, 0.4]), max_ulps = 4);
+        assert_approx_eq!(Vector3d::new([0.1, 0.2, 0.3]) * 3.0, Vector3d::new([0.3, 0.6, 0.9]), max_relative = 1e-12);
+        assert_approx_eq!(Matrix::<f64, 2, 2>::new([[0.1 + 0.2, 1.0], [0.0, 1.0]]), Matrix::new([[0.3, 1.0], [0.0, 1.0]]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_approx_eq_fails() {
+        assert_approx_eq!(Vect3::new(1.0, 2.0, 3.0), Vect3::new(1.0, 2.0, 3.1));
+    }
+}
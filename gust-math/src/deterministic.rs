@@ -0,0 +1,220 @@
+//! Software implementations of `sqrt`, `sin` and `cos` that give bit-identical results on every
+//! platform, for lockstep simulations.
+//!
+//! They only use integer arithmetic and basic IEEE 754 `f64` operations (which are exactly
+//! rounded everywhere), never the platform `libm`. Enable the `deterministic` feature to route
+//! `Vect3::normalize`, `Mat3::rotation_matrix` and `Mat4::rotate` through them; see `scalar`.
+
+use std::f64::consts::FRAC_2_PI;
+
+/// The first 33 bits of pi / 2, so `k * PIO2_HI` is exact for `|k| < 2^20`.
+const PIO2_HI: f64 = 1.570_796_326_734_125_6;
+/// pi / 2 - `PIO2_HI`.
+const PIO2_LO: f64 = 6.077_100_506_506_192e-11;
+
+/// Correctly rounded square root, bit-identical to IEEE 754 `sqrt`.
+pub fn sqrt(x: f32) -> f32 {
+    if x == 0.0 || x == f32::INFINITY {
+        return x;
+    }
+    if x.is_nan() || x < 0.0 {
+        return f32::NAN;
+    }
+
+    let bits = x.to_bits();
+    let mut exponent = (bits >> 23) as i32;
+    let mut mantissa = bits & 0x7F_FFFF;
+    if exponent == 0 {
+        // Subnormal: shift the mantissa up until it has an implicit leading bit.
+        exponent = 1;
+        while mantissa & 0x80_0000 == 0 {
+            mantissa <<= 1;
+            exponent -= 1;
+        }
+    } else {
+        mantissa |= 0x80_0000;
+    }
+
+    // Make the exponent even so it can be halved exactly.
+    let mut exponent = exponent - 127;
+    if exponent % 2 != 0 {
+        mantissa <<= 1;
+        exponent -= 1;
+    }
+
+    let scaled = (mantissa as u64) << 23;
+    let mut root = isqrt(scaled);
+    // Round to nearest. An integer's square root is never exactly halfway between two integers.
+    if scaled - root * root > root {
+        root += 1;
+    }
+    let mut exponent = exponent / 2;
+    if root == 1 << 24 {
+        root >>= 1;
+        exponent += 1;
+    }
+
+    f32::from_bits((((exponent + 127) as u32) << 23) | (root as u32 & 0x7F_FFFF))
+}
+
+fn isqrt(n: u64) -> u64 {
+    let mut remainder = n;
+    let mut root = 0;
+    let mut bit = 1 << 62;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+/// Returns `(sin(x), cos(x))`. Accurate to within one ulp of the exact result; precision
+/// degrades for `|x|` beyond about `10^6`, but results stay reproducible.
+pub fn sin_cos(x: f32) -> (f32, f32) {
+    if !x.is_finite() {
+        return (f32::NAN, f32::NAN);
+    }
+
+    // Reduce to r in [-pi/4, pi/4] and the quadrant k.
+    let x = x as f64;
+    let k = (x * FRAC_2_PI).round();
+    let r = x - k * PIO2_HI - k * PIO2_LO;
+    let (s, c) = (sin_kernel(r), cos_kernel(r));
+
+    let (sin, cos) = match (k as i64) & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    (sin as f32, cos as f32)
+}
+
+pub fn sin(x: f32) -> f32 {
+    sin_cos(x).0
+}
+
+pub fn cos(x: f32) -> f32 {
+    sin_cos(x).1
+}
+
+/// Taylor series of sin on [-pi/4, pi/4]; the truncation error is below 1e-13.
+fn sin_kernel(r: f64) -> f64 {
+    let r2 = r * r;
+    r * (1.0
+        + r2 * (-1.0 / 6.0
+            + r2 * (1.0 / 120.0
+                + r2 * (-1.0 / 5040.0
+                    + r2 * (1.0 / 362_880.0 + r2 * (-1.0 / 39_916_800.0 + r2 * (1.0 / 6_227_020_800.0)))))))
+}
+
+/// Taylor series of cos on [-pi/4, pi/4]; the truncation error is below 1e-13.
+fn cos_kernel(r: f64) -> f64 {
+    let r2 = r * r;
+    1.0 + r2
+        * (-0.5
+            + r2 * (1.0 / 24.0
+                + r2 * (-1.0 / 720.0
+                    + r2 * (1.0 / 40_320.0 + r2 * (-1.0 / 3_628_800.0 + r2 * (1.0 / 479_001_600.0))))))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn ulps(a: f32, b: f32) -> i64 {
+        (a.to_bits() as i32 as i64 - b.to_bits() as i32 as i64).abs()
+    }
+
+    #[test]
+    fn test_sqrt_special_values() {
+        assert_eq!(sqrt(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(sqrt(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(sqrt(f32::INFINITY), f32::INFINITY);
+        assert!(sqrt(-1.0).is_nan());
+        assert!(sqrt(f32::NAN).is_nan());
+        assert_eq!(sqrt(4.0), 2.0);
+        assert_eq!(sqrt(f32::MAX), f32::MAX.sqrt());
+        assert_eq!(sqrt(f32::from_bits(1)), f32::from_bits(1).sqrt());
+    }
+
+    #[test]
+    fn test_sin_cos_golden_values() {
+        let golden: [(f32, u32, u32); 6] = [
+            (0.0, 0x0000_0000, 0x3F80_0000),
+            (0.5, 0x3EF5_7744, 0x3F60_A940),
+            (1.0, 0x3F57_6AA4, 0x3F0A_5140),
+            (std::f32::consts::FRAC_PI_2, 0x3F80_0000, 0xB33B_BD2E),
+            (-2.5, 0xBF19_3578, 0xBF4D_17BF),
+            (100.0, 0xBF01_A12E, 0x3F5C_C0EE),
+        ];
+        for (x, sin, cos) in golden {
+            let (s, c) = sin_cos(x);
+            assert_eq!((s.to_bits(), c.to_bits()), (sin, cos), "sin_cos({})", x);
+        }
+        assert!(sin_cos(f32::INFINITY).0.is_nan());
+    }
+
+    /// The values the engine types must produce with the `deterministic` feature, on any machine.
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn test_engine_golden_values() {
+        use crate::matrices::mat3::Mat3;
+        use crate::matrices::mat4::Mat4;
+        use crate::vectors::vect3::Vect3;
+
+        let bits = |values: &[f32]| values.iter().map(|value| value.to_bits()).collect::<Vec<_>>();
+
+        let mut v = Vect3::new(3.0, -1.0, 7.5);
+        v.normalize();
+        assert_eq!(bits(&[v.x, v.y, v.z]), [0x3EBC_B623, 0xBDFB_9D84, 0x3F6B_E3AB]);
+
+        let axis = Vect3::new(1.0, 2.0, 2.0).normalized();
+        let rotation = Mat3::rotation_matrix(&axis, 0.7);
+        assert_eq!(
+            bits(rotation.data.as_flattened()),
+            [
+                0x3F4A_7D11, 0xBEC1_2322, 0x3EF6_A612,
+                0x3EF6_A612, 0x3F5E_8E2B, 0xBDE1_BD76,
+                0xBEC1_2322, 0x3EA3_753C, 0x3F5E_8E2B,
+            ]
+        );
+
+        let rotated = Mat4::identity().rotate(-2.1, axis);
+        assert_eq!(
+            bits(rotated.to_slices().as_flattened()),
+            [
+                0xBEAC_DF44, 0x3F68_EE1C, 0xBE76_D922, 0x0000_0000,
+                0xBE76_D922, 0x3E27_E8F0, 0x3F74_E0EC, 0x0000_0000,
+                0x3F68_EE1C, 0x3EC3_1D74, 0x3E27_E8F0, 0x0000_0000,
+                0x0000_0000, 0x0000_0000, 0x0000_0000, 0x3F80_0000,
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sqrt_matches_ieee(bits in any::<u32>()) {
+            let x = f32::from_bits(bits);
+            let (expected, actual) = (x.sqrt(), sqrt(x));
+            prop_assert!(expected.to_bits() == actual.to_bits() || (expected.is_nan() && actual.is_nan()));
+        }
+
+        #[test]
+        fn prop_sin_cos_close_to_std(x in -1000.0f32..1000.0) {
+            let (s, c) = sin_cos(x);
+            prop_assert!(ulps(s, x.sin()) <= 1 || (s - x.sin()).abs() < 1e-7);
+            prop_assert!(ulps(c, x.cos()) <= 1 || (c - x.cos()).abs() < 1e-7);
+        }
+    }
+}
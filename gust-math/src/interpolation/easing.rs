@@ -0,0 +1,200 @@
+//! Easing curves mapping a normalized time `t` in `[0, 1]` to an eased progress value.
+//!
+//! Every curve maps 0 to 0 and 1 to 1. Elastic and back-style curves overshoot in between,
+//! the others stay within `[0, 1]`. Inputs outside `[0, 1]` are clamped.
+
+use std::f32::consts::PI;
+
+use crate::interpolation::lerp::Lerp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => linear(t),
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => quad_out(t),
+            Easing::QuadInOut => quad_in_out(t),
+            Easing::CubicIn => cubic_in(t),
+            Easing::CubicOut => cubic_out(t),
+            Easing::CubicInOut => cubic_in_out(t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => elastic_in_out(t),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => bounce_in_out(t),
+        }
+    }
+
+    /// Interpolates between `from` and `to` following this curve.
+    pub fn ease<T: Lerp>(&self, from: &T, to: &T, t: f32) -> T {
+        from.lerp(to, self.apply(t))
+    }
+}
+
+pub fn linear(t: f32) -> f32 {
+    t.clamp(0.0, 1.0)
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t
+}
+
+pub fn quad_out(t: f32) -> f32 {
+    1.0 - quad_in(1.0 - t)
+}
+
+pub fn quad_in_out(t: f32) -> f32 {
+    in_out(t, quad_in)
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * t
+}
+
+pub fn cubic_out(t: f32) -> f32 {
+    1.0 - cubic_in(1.0 - t)
+}
+
+pub fn cubic_in_out(t: f32) -> f32 {
+    in_out(t, cubic_in)
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    1.0 - elastic_out(1.0 - t)
+}
+
+pub fn elastic_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t == 0.0 || t == 1.0 {
+        return t;
+    }
+    let period = 2.0 * PI / 3.0;
+    2.0f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * period).sin() + 1.0
+}
+
+pub fn elastic_in_out(t: f32) -> f32 {
+    in_out(t, elastic_in)
+}
+
+pub fn bounce_in(t: f32) -> f32 {
+    1.0 - bounce_out(1.0 - t)
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    let n = 7.5625;
+    let d = 2.75;
+    if t < 1.0 / d {
+        n * t * t
+    } else if t < 2.0 / d {
+        let t = t - 1.5 / d;
+        n * t * t + 0.75
+    } else if t < 2.5 / d {
+        let t = t - 2.25 / d;
+        n * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d;
+        n * t * t + 0.984375
+    }
+}
+
+pub fn bounce_in_out(t: f32) -> f32 {
+    in_out(t, bounce_in)
+}
+
+/// Builds the symmetric in-out variant of an ease-in curve.
+fn in_out(t: f32, ease_in: fn(f32) -> f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        ease_in(2.0 * t) / 2.0
+    } else {
+        1.0 - ease_in(2.0 - 2.0 * t) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::vectors::vect3::Vect3;
+
+    const ALL: [Easing; 13] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    #[test]
+    fn test_endpoints() {
+        for easing in ALL {
+            assert_approx_eq!(easing.apply(0.0), 0.0, epsilon = 1e-6);
+            assert_approx_eq!(easing.apply(1.0), 1.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_in_out_is_symmetric() {
+        for easing in [Easing::QuadInOut, Easing::CubicInOut, Easing::ElasticInOut, Easing::BounceInOut] {
+            assert_approx_eq!(easing.apply(0.5), 0.5, epsilon = 1e-6);
+            for i in 0..=10 {
+                let t = i as f32 / 10.0;
+                assert_approx_eq!(easing.apply(t) + easing.apply(1.0 - t), 1.0, epsilon = 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_elastic_curves_stay_in_range() {
+        for easing in ALL.iter().filter(|easing| !matches!(easing, Easing::ElasticIn | Easing::ElasticOut | Easing::ElasticInOut)) {
+            for i in 0..=100 {
+                let value = easing.apply(i as f32 / 100.0);
+                assert!((0.0..=1.0 + 1e-6).contains(&value), "{:?} left [0, 1]: {}", easing, value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(quad_in(0.5), 0.25);
+        assert_eq!(cubic_out(0.5), 0.875);
+        assert_approx_eq!(bounce_out(0.5), 0.765625);
+    }
+
+    #[test]
+    fn test_ease_vectors() {
+        let from = Vect3::zeros();
+        let to = Vect3::new(4.0, 0.0, 0.0);
+        assert_eq!(Easing::QuadIn.ease(&from, &to, 0.5), Vect3::new(1.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::vectors::vect3::Vect3;
+use crate::vectors::vect4::Vect4;
+
+/// Types that can be linearly interpolated, so easing curves and animations work on scalars
+/// and vectors alike.
+pub trait Lerp {
+    /// Interpolates between `self` (at `t = 0`) and `other` (at `t = 1`). `t` is not clamped.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &f32, t: f32) -> f32 {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vect3 {
+    fn lerp(&self, other: &Vect3, t: f32) -> Vect3 {
+        Vect3::lerp(self, other, t)
+    }
+}
+
+impl Lerp for Vect4 {
+    fn lerp(&self, other: &Vect4, t: f32) -> Vect4 {
+        Vect4::lerp(self, other, t)
+    }
+}
+
+/// Returns the `t` for which `lerp(a, b, t) == value`. Returns 0 when `a == b`.
+pub fn inverse_lerp(a: f32, b: f32, value: f32) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+    (value - a) / (b - a)
+}
+
+/// Maps `value` from the range `[from_a, from_b]` to the range `[to_a, to_b]`.
+pub fn remap(value: f32, from_a: f32, from_b: f32, to_a: f32, to_b: f32) -> f32 {
+    to_a.lerp(&to_b, inverse_lerp(from_a, from_b, value))
+}
+
+/// Hermite smoothing of `value` between the edges, clamped to `[0, 1]`.
+pub fn smoothstep(edge0: f32, edge1: f32, value: f32) -> f32 {
+    let t = inverse_lerp(edge0, edge1, value).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp() {
+        assert_eq!(2.0f32.lerp(&4.0, 0.25), 2.5);
+        assert_eq!(Lerp::lerp(&Vect3::zeros(), &Vect3::new(2.0, 4.0, 6.0), 0.5), Vect3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn test_inverse_lerp_and_remap() {
+        assert_eq!(inverse_lerp(2.0, 4.0, 3.0), 0.5);
+        assert_eq!(inverse_lerp(1.0, 1.0, 3.0), 0.0);
+        assert_eq!(remap(5.0, 0.0, 10.0, 100.0, 200.0), 150.0);
+    }
+
+    #[test]
+    fn test_smoothstep() {
+        assert_eq!(smoothstep(0.0, 1.0, -1.0), 0.0);
+        assert_eq!(smoothstep(0.0, 1.0, 0.5), 0.5);
+        assert_eq!(smoothstep(0.0, 1.0, 2.0), 1.0);
+    }
+}
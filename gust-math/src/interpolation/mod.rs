@@ -0,0 +1,3 @@
+pub mod lerp;
+pub mod easing;
+pub mod spline;
\ No newline at end of file
@@ -0,0 +1,337 @@
+//! Parametric curves over `Vect3` for camera paths and scripted motion.
+//!
+//! Every curve is parameterized over `t` in `[0, 1]`. That parameter does not move at constant
+//! speed along the curve; wrap a curve in an `ArcLengthCurve` when it should.
+
+use crate::vectors::vect3::Vect3;
+
+pub trait Curve {
+    /// The point on the curve at `t`, where `t` runs from 0 at the start to 1 at the end.
+    fn position(&self, t: f32) -> Vect3;
+
+    /// The derivative of `position` with respect to `t`.
+    fn tangent(&self, t: f32) -> Vect3;
+}
+
+/// A single cubic Bézier segment from `p0` to `p3`, pulled towards `p1` and `p2`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier {
+    pub p0: Vect3,
+    pub p1: Vect3,
+    pub p2: Vect3,
+    pub p3: Vect3,
+}
+
+impl CubicBezier {
+    pub fn new(p0: Vect3, p1: Vect3, p2: Vect3, p3: Vect3) -> Self {
+        CubicBezier { p0, p1, p2, p3 }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn position(&self, t: f32) -> Vect3 {
+        let s = 1.0 - t;
+        self.p0 * (s * s * s) + self.p1 * (3.0 * s * s * t) + self.p2 * (3.0 * s * t * t) + self.p3 * (t * t * t)
+    }
+
+    fn tangent(&self, t: f32) -> Vect3 {
+        let s = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * s * s) + (self.p2 - self.p1) * (6.0 * s * t) + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+}
+
+/// A cubic Hermite segment from `p0` to `p1` leaving with tangent `m0` and arriving with `m1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hermite {
+    pub p0: Vect3,
+    pub m0: Vect3,
+    pub p1: Vect3,
+    pub m1: Vect3,
+}
+
+impl Hermite {
+    pub fn new(p0: Vect3, m0: Vect3, p1: Vect3, m1: Vect3) -> Self {
+        Hermite { p0, m0, p1, m1 }
+    }
+}
+
+impl Curve for Hermite {
+    fn position(&self, t: f32) -> Vect3 {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        self.p0 * (2.0 * t3 - 3.0 * t2 + 1.0)
+            + self.m0 * (t3 - 2.0 * t2 + t)
+            + self.p1 * (-2.0 * t3 + 3.0 * t2)
+            + self.m1 * (t3 - t2)
+    }
+
+    fn tangent(&self, t: f32) -> Vect3 {
+        let t2 = t * t;
+        self.p0 * (6.0 * t2 - 6.0 * t)
+            + self.m0 * (3.0 * t2 - 4.0 * t + 1.0)
+            + self.p1 * (-6.0 * t2 + 6.0 * t)
+            + self.m1 * (3.0 * t2 - 2.0 * t)
+    }
+}
+
+/// A Catmull-Rom spline passing through every control point.
+///
+/// `alpha` selects the knot parameterization: 0 is uniform, 0.5 centripetal (no cusps or
+/// self-intersections within a segment) and 1 chordal. The end segments use mirrored
+/// phantom points, so the spline starts and ends exactly at the first and last point.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatmullRom {
+    pub points: Vec<Vect3>,
+    pub alpha: f32,
+}
+
+impl CatmullRom {
+    pub fn new(points: Vec<Vect3>, alpha: f32) -> Self {
+        assert!(points.len() >= 2, "A Catmull-Rom spline needs at least two points");
+        CatmullRom { points, alpha }
+    }
+
+    pub fn uniform(points: Vec<Vect3>) -> Self {
+        CatmullRom::new(points, 0.0)
+    }
+
+    pub fn centripetal(points: Vec<Vect3>) -> Self {
+        CatmullRom::new(points, 0.5)
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    /// The segment between `points[index]` and `points[index + 1]` as a Hermite curve.
+    pub fn segment(&self, index: usize) -> Hermite {
+        let last = self.points.len() - 1;
+        let p1 = self.points[index];
+        let p2 = self.points[index + 1];
+        let p0 = if index == 0 { p1 * 2.0 - p2 } else { self.points[index - 1] };
+        let p3 = if index + 1 == last { p2 * 2.0 - p1 } else { self.points[index + 2] };
+
+        let knot = |a: Vect3, b: Vect3| {
+            let interval = a.distance(&b).powf(self.alpha);
+            if interval < 1e-6 { 1.0 } else { interval }
+        };
+        let dt0 = knot(p0, p1);
+        let dt1 = knot(p1, p2);
+        let dt2 = knot(p2, p3);
+
+        let m1 = (p1 - p0) / dt0 - (p2 - p0) / (dt0 + dt1) + (p2 - p1) / dt1;
+        let m2 = (p2 - p1) / dt1 - (p3 - p1) / (dt1 + dt2) + (p3 - p2) / dt2;
+
+        Hermite::new(p1, m1 * dt1, p2, m2 * dt1)
+    }
+
+    /// Splits a global `t` into a segment index and the local parameter within that segment.
+    fn locate(&self, t: f32) -> (usize, f32) {
+        let scaled = t.clamp(0.0, 1.0) * self.segment_count() as f32;
+        let index = (scaled.floor() as usize).min(self.segment_count() - 1);
+        (index, scaled - index as f32)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn position(&self, t: f32) -> Vect3 {
+        let (index, local) = self.locate(t);
+        self.segment(index).position(local)
+    }
+
+    fn tangent(&self, t: f32) -> Vect3 {
+        let (index, local) = self.locate(t);
+        self.segment(index).tangent(local) * self.segment_count() as f32
+    }
+}
+
+/// A lookup table mapping distance travelled along a curve to its parameter `t`.
+///
+/// The curve is approximated by `samples` straight chords, so the accuracy of the lengths
+/// depends on the sample count relative to how sharply the curve bends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthTable {
+    ts: Vec<f32>,
+    distances: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    pub fn new<C: Curve + ?Sized>(curve: &C, samples: usize) -> Self {
+        let samples = samples.max(1);
+        let mut ts = Vec::with_capacity(samples + 1);
+        let mut distances = Vec::with_capacity(samples + 1);
+
+        let mut previous = curve.position(0.0);
+        let mut distance = 0.0;
+        ts.push(0.0);
+        distances.push(0.0);
+
+        for i in 1..=samples {
+            let t = i as f32 / samples as f32;
+            let point = curve.position(t);
+            distance += previous.distance(&point);
+            previous = point;
+            ts.push(t);
+            distances.push(distance);
+        }
+
+        ArcLengthTable { ts, distances }
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.distances.last().unwrap()
+    }
+
+    /// The parameter `t` at which the given distance along the curve is reached, clamped to the curve.
+    pub fn t_at_distance(&self, distance: f32) -> f32 {
+        if distance <= 0.0 || self.length() == 0.0 {
+            return 0.0;
+        }
+        if distance >= self.length() {
+            return 1.0;
+        }
+
+        let upper = self.distances.partition_point(|&d| d < distance);
+        let lower = upper - 1;
+        let span = self.distances[upper] - self.distances[lower];
+        let fraction = if span == 0.0 { 0.0 } else { (distance - self.distances[lower]) / span };
+        self.ts[lower] + (self.ts[upper] - self.ts[lower]) * fraction
+    }
+
+    /// The parameter `t` at which the given fraction of the total length is reached.
+    pub fn t_at_fraction(&self, fraction: f32) -> f32 {
+        self.t_at_distance(fraction * self.length())
+    }
+}
+
+/// Reparameterizes a curve by arc length, so equal steps in `t` cover equal distances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthCurve<C: Curve> {
+    pub curve: C,
+    table: ArcLengthTable,
+}
+
+impl<C: Curve> ArcLengthCurve<C> {
+    pub fn new(curve: C, samples: usize) -> Self {
+        let table = ArcLengthTable::new(&curve, samples);
+        ArcLengthCurve { curve, table }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.table.length()
+    }
+
+    pub fn position_at_distance(&self, distance: f32) -> Vect3 {
+        self.curve.position(self.table.t_at_distance(distance))
+    }
+}
+
+impl<C: Curve> Curve for ArcLengthCurve<C> {
+    fn position(&self, t: f32) -> Vect3 {
+        self.curve.position(self.table.t_at_fraction(t))
+    }
+
+    /// Approximately unit speed times the total length, as the table is piecewise linear.
+    fn tangent(&self, t: f32) -> Vect3 {
+        self.curve.tangent(self.table.t_at_fraction(t)).normalized() * self.length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    fn bezier() -> CubicBezier {
+        CubicBezier::new(
+            Vect3::new(0.0, 0.0, 0.0),
+            Vect3::new(0.0, 1.0, 0.0),
+            Vect3::new(1.0, 1.0, 0.0),
+            Vect3::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    fn numeric_tangent<C: Curve>(curve: &C, t: f32) -> Vect3 {
+        let h = 1e-3;
+        (curve.position(t + h) - curve.position(t - h)) / (2.0 * h)
+    }
+
+    #[test]
+    fn test_bezier() {
+        let curve = bezier();
+        assert_eq!(curve.position(0.0), curve.p0);
+        assert_eq!(curve.position(1.0), curve.p3);
+        assert_approx_eq!(curve.position(0.5), Vect3::new(0.5, 0.75, 0.0));
+        assert_approx_eq!(curve.tangent(0.0), (curve.p1 - curve.p0) * 3.0);
+        assert_approx_eq!(curve.tangent(0.3), numeric_tangent(&curve, 0.3), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_hermite() {
+        let curve = Hermite::new(Vect3::zeros(), Vect3::new(1.0, 0.0, 0.0), Vect3::new(1.0, 1.0, 0.0), Vect3::new(0.0, 2.0, 0.0));
+        assert_eq!(curve.position(0.0), curve.p0);
+        assert_eq!(curve.position(1.0), curve.p1);
+        assert_eq!(curve.tangent(0.0), curve.m0);
+        assert_eq!(curve.tangent(1.0), curve.m1);
+        assert_approx_eq!(curve.tangent(0.6), numeric_tangent(&curve, 0.6), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_catmull_rom_passes_through_points() {
+        let points = vec![
+            Vect3::new(0.0, 0.0, 0.0),
+            Vect3::new(1.0, 2.0, 0.0),
+            Vect3::new(3.0, 2.0, 1.0),
+            Vect3::new(4.0, 0.0, 1.0),
+        ];
+        for alpha in [0.0, 0.5, 1.0] {
+            let spline = CatmullRom::new(points.clone(), alpha);
+            for (i, point) in points.iter().enumerate() {
+                let t = i as f32 / spline.segment_count() as f32;
+                assert_approx_eq!(spline.position(t), *point, epsilon = 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniform_catmull_rom_tangent() {
+        let points = vec![Vect3::new(0.0, 0.0, 0.0), Vect3::new(1.0, 0.0, 0.0), Vect3::new(2.0, 1.0, 0.0)];
+        let spline = CatmullRom::uniform(points.clone());
+        // Interior tangents are half the chord between the neighbouring points.
+        assert_approx_eq!(spline.segment(0).m1, (points[2] - points[0]) * 0.5);
+        assert_approx_eq!(spline.tangent(0.25), numeric_tangent(&spline, 0.25), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_catmull_rom_with_repeated_points() {
+        let spline = CatmullRom::centripetal(vec![Vect3::zeros(), Vect3::zeros(), Vect3::new(1.0, 0.0, 0.0)]);
+        assert!(spline.position(0.25).is_finite());
+        assert!(spline.position(0.75).is_finite());
+    }
+
+    #[test]
+    fn test_arc_length_of_line() {
+        let line = Hermite::new(Vect3::zeros(), Vect3::new(3.0, 4.0, 0.0), Vect3::new(3.0, 4.0, 0.0), Vect3::new(3.0, 4.0, 0.0));
+        let table = ArcLengthTable::new(&line, 64);
+        assert_approx_eq!(table.length(), 5.0);
+        assert_eq!(table.t_at_distance(-1.0), 0.0);
+        assert_eq!(table.t_at_distance(10.0), 1.0);
+    }
+
+    #[test]
+    fn test_arc_length_curve_has_constant_speed() {
+        let curve = ArcLengthCurve::new(bezier(), 512);
+        let steps = 10;
+        let step_length = curve.length() / steps as f32;
+        for i in 0..steps {
+            let a = curve.position(i as f32 / steps as f32);
+            let b = curve.position((i + 1) as f32 / steps as f32);
+            // Chords are slightly shorter than the arc they span.
+            assert_approx_eq!(a.distance(&b), step_length, epsilon = 5e-3);
+        }
+        assert_approx_eq!(curve.position_at_distance(curve.length()), bezier().p3);
+    }
+}
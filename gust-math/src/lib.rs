@@ -1,2 +1,9 @@
 pub mod vectors;
-pub mod matrices;
\ No newline at end of file
+pub mod matrices;
+pub mod approx;
+pub mod interpolation;
+pub mod random;
+pub mod scalar;
+pub mod deterministic;
+#[cfg(feature = "serde")]
+mod serialization;
\ No newline at end of file
@@ -1,7 +1,9 @@
 use std::ops::{Index, IndexMut};
 
+use crate::scalar;
 use crate::vectors::vect3::Vect3;
 
+#[derive(Debug, Clone, Copy)]
 pub struct Mat3 {
     pub data: [[f32; 3]; 3],
 }
@@ -23,12 +25,12 @@ impl Mat3 {
 
     pub fn from_slice(slice: &[[f32; 3]; 3]) -> Mat3 {
         Mat3 {
-            data: slice.clone(),
+            data: *slice,
         }
     }
 
     pub fn to_array(&self) -> [[f32; 3]; 3] {
-        self.data.clone()
+        self.data
     }
 
     pub fn to_vec(&self) -> Vec<f32> {
@@ -66,9 +68,14 @@ impl Mat3 {
     }
 
     pub fn inverse(&self) -> Mat3 {
+        self.try_inverse().expect("Matrix is not invertible")
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it is singular.
+    pub fn try_inverse(&self) -> Option<Mat3> {
         let det = self.det();
-        if det == 0.0 {
-            panic!("Matrix is not invertible");
+        if det == 0.0 || !det.is_finite() {
+            return None;
         }
         let mut res = Mat3::new();
         let inv_det = 1.0 / det;
@@ -81,7 +88,7 @@ impl Mat3 {
         res.data[2][0] = (self.data[1][0] * self.data[2][1] - self.data[2][0] * self.data[1][1]) * inv_det;
         res.data[2][1] = (self.data[2][0] * self.data[0][1] - self.data[0][0] * self.data[2][1]) * inv_det;
         res.data[2][2] = (self.data[0][0] * self.data[1][1] - self.data[1][0] * self.data[0][1]) * inv_det;
-        res
+        Some(res)
     }
 
     pub fn dot(&self, other: &Mat3) -> f32 {
@@ -94,10 +101,19 @@ impl Mat3 {
         res
     }
 
+    /// Returns `true` if any element is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.data.iter().flatten().any(|value| value.is_nan())
+    }
+
+    /// Returns `true` if every element is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().flatten().all(|value| value.is_finite())
+    }
+
     pub fn rotation_matrix(axis: &Vect3, angle: f32) -> Mat3 {
         let mut res = Mat3::new();
-        let c = angle.cos();
-        let s = angle.sin();
+        let (s, c) = scalar::sin_cos(angle);
         let t = 1.0 - c;
         let x = axis[0];
         let y = axis[1];
@@ -115,11 +131,9 @@ impl Mat3 {
     }
 }
 
-impl Clone for Mat3 {
-    fn clone(&self) -> Mat3 {
-        Mat3 {
-            data: self.data.clone(),
-        }
+impl Default for Mat3 {
+    fn default() -> Self {
+        Mat3::new()
     }
 }
 
@@ -157,4 +171,174 @@ impl std::ops::Mul<Vect3> for Mat3 {
         }
         res
     }
-}
\ No newline at end of file
+}
+
+impl std::ops::MulAssign<Mat3> for Mat3 {
+    fn mul_assign(&mut self, other: Mat3) {
+        *self = *self * other;
+    }
+}
+
+impl std::ops::Mul<f32> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: f32) -> Mat3 {
+        let mut res = Mat3::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                res.data[i][j] = self.data[i][j] * other;
+            }
+        }
+        res
+    }
+}
+
+impl std::ops::Mul<Mat3> for f32 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        other * self
+    }
+}
+
+impl std::ops::MulAssign<f32> for Mat3 {
+    fn mul_assign(&mut self, other: f32) {
+        *self = *self * other;
+    }
+}
+
+impl std::ops::Add for Mat3 {
+    type Output = Mat3;
+
+    fn add(self, other: Mat3) -> Mat3 {
+        let mut res = Mat3::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                res.data[i][j] = self.data[i][j] + other.data[i][j];
+            }
+        }
+        res
+    }
+}
+
+impl std::ops::AddAssign for Mat3 {
+    fn add_assign(&mut self, other: Mat3) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::Sub for Mat3 {
+    type Output = Mat3;
+
+    fn sub(self, other: Mat3) -> Mat3 {
+        let mut res = Mat3::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                res.data[i][j] = self.data[i][j] - other.data[i][j];
+            }
+        }
+        res
+    }
+}
+
+impl std::ops::SubAssign for Mat3 {
+    fn sub_assign(&mut self, other: Mat3) {
+        *self = *self - other;
+    }
+}
+
+impl std::ops::Neg for Mat3 {
+    type Output = Mat3;
+
+    fn neg(self) -> Mat3 {
+        self * -1.0
+    }
+}
+
+impl PartialEq for Mat3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::assert_approx_eq;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn mat3_close(a: &Mat3, b: &Mat3) -> bool {
+        (0..3).all(|i| (0..3).all(|j| (a[i][j] - b[i][j]).abs() <= EPSILON * (1.0 + a[i][j].abs().max(b[i][j].abs()))))
+    }
+
+    fn mat3() -> impl Strategy<Value = Mat3> {
+        prop::array::uniform3(prop::array::uniform3(-10.0f32..10.0)).prop_map(|data| Mat3::from_slice(&data))
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let a = Mat3::from_slice(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let b = Mat3::identity();
+        assert_eq!(a + b, Mat3::from_slice(&[[2.0, 2.0, 3.0], [4.0, 6.0, 6.0], [7.0, 8.0, 10.0]]));
+        assert_eq!(a - b, Mat3::from_slice(&[[0.0, 2.0, 3.0], [4.0, 4.0, 6.0], [7.0, 8.0, 8.0]]));
+    }
+
+    #[test]
+    fn test_try_inverse() {
+        let singular = Mat3::from_slice(&[[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]]);
+        assert!(singular.try_inverse().is_none());
+
+        let rotation = Mat3::rotation_matrix(&Vect3::new(0.0, 1.0, 0.0), 0.5);
+        assert_approx_eq!(rotation.try_inverse().unwrap() * rotation, Mat3::identity());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_sub_roundtrip(a in mat3(), b in mat3()) {
+            prop_assert!(mat3_close(&(a + b - b), &a));
+        }
+
+        #[test]
+        fn prop_assign_ops_match_binary_ops(a in mat3(), b in mat3(), s in -10.0f32..10.0) {
+            let mut c = a;
+            c += b;
+            prop_assert_eq!(c, a + b);
+
+            let mut c = a;
+            c -= b;
+            prop_assert_eq!(c, a - b);
+
+            let mut c = a;
+            c *= b;
+            prop_assert_eq!(c, a * b);
+
+            let mut c = a;
+            c *= s;
+            prop_assert_eq!(c, a * s);
+        }
+
+        #[test]
+        fn prop_neg_is_additive_inverse(a in mat3()) {
+            prop_assert_eq!(a + -a, Mat3::new());
+        }
+
+        #[test]
+        fn prop_identity_is_neutral(a in mat3()) {
+            prop_assert_eq!(a * Mat3::identity(), a);
+            prop_assert_eq!(Mat3::identity() * a, a);
+        }
+
+        #[test]
+        fn prop_transpose_of_product(a in mat3(), b in mat3()) {
+            prop_assert!(mat3_close(&(a * b).transpose(), &(b.transpose() * a.transpose())));
+        }
+
+        #[test]
+        fn prop_mul_distributes_over_add(a in mat3(), b in mat3(), c in mat3()) {
+            prop_assert!(mat3_close(&(a * (b + c)), &(a * b + a * c)));
+        }
+    }
+}
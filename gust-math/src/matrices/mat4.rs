@@ -1,3 +1,4 @@
+use crate::scalar;
 use crate::vectors::vect3::Vect3;
 use crate::vectors::vect4::Vect4;
 
@@ -34,20 +35,16 @@ impl Mat4 {
     }
 
     pub fn from_slices(slices: [[f32; 4]; 4]) -> Self {
-        let mut mat = Mat4::new();
-        for i in 0..4 {
-            for j in 0..3 {
-                mat.data[i][j] = slices[i][j];
-            }
+        Mat4 {
+            data: slices,
         }
-        mat
     }
 
     pub fn from_vects(vects: [Vect4; 4]) -> Self {
         let mut mat = Mat4::new();
-        for i in 0..4 {
-            for j in 0..4 {
-                mat.data[i][j] = vects[j][i];
+        for (j, vect) in vects.iter().enumerate() {
+            for i in 0..4 {
+                mat.data[i][j] = vect[i];
             }
         }
         mat
@@ -85,6 +82,16 @@ impl Mat4 {
         self.data
     }
 
+    /// Returns `true` if any element is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.data.iter().flatten().any(|value| value.is_nan())
+    }
+
+    /// Returns `true` if every element is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().flatten().all(|value| value.is_finite())
+    }
+
     pub fn transpose(&self) -> Self {
         let mut mat = Mat4::new();
         for i in 0..4 {
@@ -100,13 +107,12 @@ impl Mat4 {
         mat.data[0][0] = scale[0];
         mat.data[1][1] = scale[1];
         mat.data[2][2] = scale[2];
-        self.clone() * mat
+        *self * mat
     }
 
     pub fn rotate(&self, angle: f32, axis: Vect3) -> Self {
         let mut mat = Mat4::identity();
-        let c = angle.cos();
-        let s = angle.sin();
+        let (s, c) = scalar::sin_cos(angle);
         let t = 1.0 - c;
         let x = axis.x;
         let y = axis.y;
@@ -120,20 +126,20 @@ impl Mat4 {
         mat.data[2][0] = t * x * z - s * y;
         mat.data[2][1] = t * y * z + s * x;
         mat.data[2][2] = t * z * z + c;
-        self.clone() * mat
+        *self * mat
     }
 
     /// Rotate the matrix to align the forward vector with the given forward vector and the up vector with the given up vector.
     /// These rotations are based on the global forward and up vectors.
     /// The global forward vector is [1.0, 0.0, 0.0] and the global up vector is [0.0, 0.0, 1.0].
     pub fn rotate_with_dir_and_up(&self, forward: Vect3, up: Vect3) -> Self {
-        let forward = forward.clone().normalize();
-        let right = up.cross(&forward).normalize();
-        let up = forward.cross(&right).normalize();
+        let forward = forward.normalized();
+        let right = up.cross(&forward).normalized();
+        let up = forward.cross(&right).normalized();
 
         let mat = Mat4::from_cols(right.into(), forward.into(), up.into(), [0.0, 0.0, 0.0, 1.0].into());
 
-        self.clone() * mat
+        *self * mat
     }
 
     pub fn from_cols(col1: Vect4, col2: Vect4, col3: Vect4, col4: Vect4) -> Self {
@@ -169,6 +175,12 @@ impl Mat4 {
     }
 }
 
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::new()
+    }
+}
+
 impl std::ops::Mul for Mat4 {
     type Output = Self;
 
@@ -177,6 +189,12 @@ impl std::ops::Mul for Mat4 {
     }
 }
 
+impl std::ops::MulAssign for Mat4 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
 impl std::ops::Mul<Vect3> for Mat4 {
     type Output = Vect3;
 
@@ -221,6 +239,12 @@ impl std::ops::Mul<f32> for Mat4 {
     }
 }
 
+impl std::ops::MulAssign<f32> for Mat4 {
+    fn mul_assign(&mut self, other: f32) {
+        *self = *self * other;
+    }
+}
+
 impl std::ops::Mul<Mat4> for f32 {
     type Output = Mat4;
 
@@ -249,6 +273,12 @@ impl std::ops::Add for Mat4 {
     }
 }
 
+impl std::ops::AddAssign for Mat4 {
+    fn add_assign(&mut self, other: Mat4) {
+        *self = *self + other;
+    }
+}
+
 impl std::ops::Sub for Mat4 {
     type Output = Mat4;
 
@@ -263,6 +293,12 @@ impl std::ops::Sub for Mat4 {
     }
 }
 
+impl std::ops::SubAssign for Mat4 {
+    fn sub_assign(&mut self, other: Mat4) {
+        *self = *self - other;
+    }
+}
+
 impl std::ops::Neg for Mat4 {
     type Output = Mat4;
 
@@ -302,4 +338,81 @@ impl PartialEq for Mat4 {
         }
         true
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn mat4_close(a: &Mat4, b: &Mat4) -> bool {
+        (0..4).all(|i| (0..4).all(|j| (a[i][j] - b[i][j]).abs() <= EPSILON * (1.0 + a[i][j].abs().max(b[i][j].abs()))))
+    }
+
+    fn mat4() -> impl Strategy<Value = Mat4> {
+        prop::array::uniform4(prop::array::uniform4(-10.0f32..10.0)).prop_map(Mat4::from_slices)
+    }
+
+    #[test]
+    fn test_from_slices_keeps_last_column() {
+        let mat = Mat4::from_slices([[1.0, 2.0, 3.0, 4.0]; 4]);
+        assert_eq!(mat[2][3], 4.0);
+    }
+
+    #[test]
+    fn test_mul_vect4() {
+        let mat = Mat4::identity() * 2.0;
+        let v = Vect4::from([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(mat * v, Vect4::from([2.0, 4.0, 6.0, 8.0]));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_sub_roundtrip(a in mat4(), b in mat4()) {
+            prop_assert!(mat4_close(&(a + b - b), &a));
+        }
+
+        #[test]
+        fn prop_assign_ops_match_binary_ops(a in mat4(), b in mat4(), s in -10.0f32..10.0) {
+            let mut c = a;
+            c += b;
+            prop_assert_eq!(c, a + b);
+
+            let mut c = a;
+            c -= b;
+            prop_assert_eq!(c, a - b);
+
+            let mut c = a;
+            c *= b;
+            prop_assert_eq!(c, a * b);
+
+            let mut c = a;
+            c *= s;
+            prop_assert_eq!(c, a * s);
+        }
+
+        #[test]
+        fn prop_scalar_mul_commutes(a in mat4(), s in -10.0f32..10.0) {
+            prop_assert_eq!(a * s, s * a);
+        }
+
+        #[test]
+        fn prop_identity_is_neutral(a in mat4()) {
+            prop_assert_eq!(a * Mat4::identity(), a);
+            prop_assert_eq!(Mat4::identity() * a, a);
+        }
+
+        #[test]
+        fn prop_transpose_of_product(a in mat4(), b in mat4()) {
+            prop_assert!(mat4_close(&(a * b).transpose(), &(b.transpose() * a.transpose())));
+        }
+
+        #[test]
+        fn prop_mul_is_associative(a in mat4(), b in mat4(), c in mat4()) {
+            prop_assert!(mat4_close(&((a * b) * c), &(a * (b * c))));
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::{Index, IndexMut};
+
+use num_traits::Float;
+
+use crate::matrices::mat3::Mat3;
+use crate::matrices::mat4::Mat4;
+use crate::vectors::vector::Vector;
+
+/// A row-major `R x C` matrix over any floating point type.
+///
+/// `Mat3` and `Mat4` remain the `f32` types used throughout the engine; this type is the
+/// generic foundation for other sizes and `f64` precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<T: Float, const R: usize, const C: usize> {
+    pub data: [[T; C]; R],
+}
+
+pub type Matrix3f = Matrix<f32, 3, 3>;
+pub type Matrix4f = Matrix<f32, 4, 4>;
+pub type Matrix3d = Matrix<f64, 3, 3>;
+pub type Matrix4d = Matrix<f64, 4, 4>;
+
+impl<T: Float, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn new(data: [[T; C]; R]) -> Self {
+        Matrix { data }
+    }
+
+    pub fn zeros() -> Self {
+        Matrix { data: [[T::zero(); C]; R] }
+    }
+
+    pub fn to_array(&self) -> [[T; C]; R] {
+        self.data
+    }
+
+    pub fn row(&self, index: usize) -> Vector<T, C> {
+        Vector::new(self.data[index])
+    }
+
+    pub fn col(&self, index: usize) -> Vector<T, R> {
+        Vector::new(self.data.map(|row| row[index]))
+    }
+
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        let mut res = Matrix::zeros();
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                res.data[j][i] = *value;
+            }
+        }
+        res
+    }
+
+    /// Returns `true` if any element is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.data.iter().flatten().any(|value| value.is_nan())
+    }
+
+    /// Returns `true` if every element is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().flatten().all(|value| value.is_finite())
+    }
+
+    /// Applies `f` to every element.
+    pub fn map<F: Fn(T) -> T>(&self, f: F) -> Self {
+        Matrix { data: self.data.map(|row| row.map(&f)) }
+    }
+
+    /// Combines the elements of two matrices pairwise with `f`.
+    pub fn zip_map<F: Fn(T, T) -> T>(&self, other: &Self, f: F) -> Self {
+        let mut data = self.data;
+        for (row, other_row) in data.iter_mut().zip(other.data.iter()) {
+            for (value, other) in row.iter_mut().zip(other_row.iter()) {
+                *value = f(*value, *other);
+            }
+        }
+        Matrix { data }
+    }
+
+    /// Converts every element to another floating point type.
+    pub fn cast<U: Float>(&self) -> Matrix<U, R, C> {
+        let mut res = Matrix::zeros();
+        for (row, own_row) in res.data.iter_mut().zip(self.data.iter()) {
+            for (value, own) in row.iter_mut().zip(own_row.iter()) {
+                *value = U::from(*own).unwrap();
+            }
+        }
+        res
+    }
+}
+
+impl<T: Float, const N: usize> Matrix<T, N, N> {
+    pub fn identity() -> Self {
+        let mut res = Matrix::zeros();
+        for i in 0..N {
+            res.data[i][i] = T::one();
+        }
+        res
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> Default for Matrix<T, R, C> {
+    fn default() -> Self {
+        Matrix::zeros()
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize, const K: usize> std::ops::Mul<Matrix<T, C, K>> for Matrix<T, R, C> {
+    type Output = Matrix<T, R, K>;
+
+    fn mul(self, other: Matrix<T, C, K>) -> Matrix<T, R, K> {
+        let mut res = Matrix::zeros();
+        for (res_row, row) in res.data.iter_mut().zip(self.data.iter()) {
+            for (j, value) in res_row.iter_mut().enumerate() {
+                *value = row
+                    .iter()
+                    .zip(other.data.iter())
+                    .fold(T::zero(), |acc, (a, other_row)| acc + *a * other_row[j]);
+            }
+        }
+        res
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> std::ops::Mul<Vector<T, C>> for Matrix<T, R, C> {
+    type Output = Vector<T, R>;
+
+    fn mul(self, other: Vector<T, C>) -> Vector<T, R> {
+        Vector::new(self.data.map(|row| Vector::new(row).dot(&other)))
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> std::ops::Mul<T> for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn mul(self, other: T) -> Self {
+        self.map(|a| a * other)
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> std::ops::Add for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_map(&other, |a, b| a + b)
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> std::ops::Sub for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_map(&other, |a, b| a - b)
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> std::ops::Neg for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> Index<usize> for Matrix<T, R, C> {
+    type Output = [T; C];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> IndexMut<usize> for Matrix<T, R, C> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(data: [[T; C]; R]) -> Self {
+        Matrix { data }
+    }
+}
+
+impl From<Mat3> for Matrix3f {
+    fn from(mat3: Mat3) -> Self {
+        Matrix::new(mat3.to_array())
+    }
+}
+
+impl From<Matrix3f> for Mat3 {
+    fn from(matrix: Matrix3f) -> Self {
+        Mat3::from_slice(&matrix.data)
+    }
+}
+
+impl From<Mat4> for Matrix4f {
+    fn from(mat4: Mat4) -> Self {
+        Matrix::new(mat4.to_slices())
+    }
+}
+
+impl From<Matrix4f> for Mat4 {
+    fn from(matrix: Matrix4f) -> Self {
+        Mat4::from_slices(matrix.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::vectors::vect4::Vect4;
+    use crate::vectors::vector::{Vector2d, Vector3d, Vector4f};
+
+    fn rows4() -> impl Strategy<Value = [[f32; 4]; 4]> {
+        prop::array::uniform4(prop::array::uniform4(-10.0f32..10.0))
+    }
+
+    #[test]
+    fn test_non_square_mul() {
+        let a = Matrix::<f64, 2, 3>::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = a.transpose();
+        assert_eq!(a * b, Matrix::new([[14.0, 32.0], [32.0, 77.0]]));
+        assert_eq!(a * Vector3d::new([1.0, 0.0, -1.0]), Vector2d::new([-2.0, -2.0]));
+    }
+
+    #[test]
+    fn test_identity() {
+        let a = Matrix3d::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        assert_eq!(a * Matrix3d::identity(), a);
+    }
+
+    #[test]
+    fn test_row_and_col() {
+        let a = Matrix::<f32, 2, 3>::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_eq!(a.row(1).data, [4.0, 5.0, 6.0]);
+        assert_eq!(a.col(2).data, [3.0, 6.0]);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_mul_matches_mat4(a in rows4(), b in rows4()) {
+            let product: Mat4 = (Matrix4f::new(a) * Matrix4f::new(b)).into();
+            prop_assert_eq!(product, Mat4::from_slices(a) * Mat4::from_slices(b));
+        }
+
+        #[test]
+        fn prop_mul_vector_matches_mat4(a in rows4(), v in prop::array::uniform4(-10.0f32..10.0)) {
+            let product: Vect4 = (Matrix4f::new(a) * Vector4f::new(v)).into();
+            prop_assert_eq!(product, Mat4::from_slices(a) * Vect4::from(v));
+        }
+
+        #[test]
+        fn prop_mul_matches_mat3(a in prop::array::uniform3(prop::array::uniform3(-10.0f32..10.0)), b in prop::array::uniform3(prop::array::uniform3(-10.0f32..10.0))) {
+            let product: Mat3 = (Matrix3f::new(a) * Matrix3f::new(b)).into();
+            prop_assert_eq!(product, Mat3::from_slice(&a) * Mat3::from_slice(&b));
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod mat3;
-pub mod mat4;
\ No newline at end of file
+pub mod mat4;
+pub mod matrix;
\ No newline at end of file
@@ -0,0 +1,139 @@
+use crate::random::noise::Noise;
+use crate::vectors::vect3::Vect3;
+
+/// Fractal Brownian motion: several octaves of a base noise summed at increasing frequency and
+/// decreasing amplitude. The result is normalized back into `[-1, 1]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fbm<N: Noise> {
+    pub noise: N,
+    pub octaves: u32,
+    pub frequency: f32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl<N: Noise> Fbm<N> {
+    /// Six octaves, doubling the frequency and halving the amplitude each octave.
+    pub fn new(noise: N) -> Self {
+        Fbm { noise, octaves: 6, frequency: 1.0, lacunarity: 2.0, gain: 0.5 }
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    fn accumulate<F: Fn(f32) -> f32>(&self, sample: F) -> f32 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut max = 0.0;
+        let mut frequency = self.frequency;
+        for _ in 0..self.octaves {
+            total += sample(frequency) * amplitude;
+            max += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+        if max > 0.0 { total / max } else { 0.0 }
+    }
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        self.accumulate(|frequency| self.noise.noise2(x * frequency, y * frequency))
+    }
+
+    fn noise3(&self, point: &Vect3) -> f32 {
+        self.accumulate(|frequency| self.noise.noise3(&(*point * frequency)))
+    }
+}
+
+/// Ridged multifractal: octaves of `1 - |noise|`, giving sharp crests suited to mountain ranges.
+/// Output is remapped to `[-1, 1]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ridged<N: Noise> {
+    pub fbm: Fbm<N>,
+}
+
+impl<N: Noise> Ridged<N> {
+    pub fn new(noise: N) -> Self {
+        Ridged { fbm: Fbm::new(noise) }
+    }
+
+    pub fn from_fbm(fbm: Fbm<N>) -> Self {
+        Ridged { fbm }
+    }
+
+    fn ridge(value: f32) -> f32 {
+        let ridge = 1.0 - value.abs();
+        ridge * ridge
+    }
+}
+
+impl<N: Noise> Noise for Ridged<N> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let value = self.fbm.accumulate(|frequency| Self::ridge(self.fbm.noise.noise2(x * frequency, y * frequency)));
+        value * 2.0 - 1.0
+    }
+
+    fn noise3(&self, point: &Vect3) -> f32 {
+        let value = self.fbm.accumulate(|frequency| Self::ridge(self.fbm.noise.noise3(&(*point * frequency))));
+        value * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::noise::{Perlin, Simplex};
+
+    #[test]
+    fn test_fbm_in_range() {
+        let fbm = Fbm::new(Simplex::new(3)).with_frequency(0.37);
+        let ridged = Ridged::new(Perlin::new(3));
+        for i in 0..1000 {
+            let point = Vect3::new(i as f32 * 0.13, i as f32 * -0.07, i as f32 * 0.29);
+            for value in [fbm.noise3(&point), fbm.noise2(point.x, point.z), ridged.noise3(&point), ridged.noise2(point.y, point.z)] {
+                assert!((-1.0..=1.0).contains(&value), "value out of range: {}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_octave_matches_base() {
+        let fbm = Fbm::new(Perlin::new(5)).with_octaves(1).with_frequency(2.0);
+        let base = Perlin::new(5);
+        let point = Vect3::new(0.3, 0.6, 0.9);
+        assert_eq!(fbm.noise3(&point), base.noise3(&(point * 2.0)));
+        assert_eq!(fbm.noise2(0.3, 0.6), base.noise2(0.6, 1.2));
+    }
+
+    #[test]
+    fn test_fbm_deterministic() {
+        let a = Fbm::new(Simplex::new(11));
+        let b = Fbm::new(Simplex::new(11));
+        let point = Vect3::new(4.2, -1.1, 0.5);
+        assert_eq!(a.noise3(&point).to_bits(), b.noise3(&point).to_bits());
+    }
+
+    #[test]
+    fn test_zero_octaves() {
+        let fbm = Fbm::new(Perlin::new(1)).with_octaves(0);
+        assert_eq!(fbm.noise2(0.5, 0.5), 0.0);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rng;
+pub mod noise;
+pub mod fractal;
\ No newline at end of file
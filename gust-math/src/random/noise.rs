@@ -0,0 +1,357 @@
+//! Seeded gradient and value noise in two and three dimensions.
+//!
+//! All generators return values in roughly `[-1, 1]` and are deterministic for a given seed.
+//! The lattice is shuffled with `Rng`, so two generators with the same seed agree exactly.
+
+use crate::random::rng::Rng;
+use crate::vectors::vect3::Vect3;
+
+pub trait Noise {
+    fn noise2(&self, x: f32, y: f32) -> f32;
+
+    fn noise3(&self, point: &Vect3) -> f32;
+
+    /// Three decorrelated noise values at `point`, e.g. for displacing or jittering positions.
+    fn noise3_vect(&self, point: &Vect3) -> Vect3 {
+        Vect3::new(
+            self.noise3(point),
+            self.noise3(&(*point + Vect3::new(31.416, 47.853, 12.793))),
+            self.noise3(&(*point + Vect3::new(-71.337, 18.271, 93.119))),
+        )
+    }
+}
+
+/// A shuffled permutation of `0..256`, repeated once so lookups never need to wrap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Permutation {
+    table: [u8; 512],
+}
+
+impl Permutation {
+    fn new(seed: u64) -> Self {
+        let mut values: [u8; 256] = std::array::from_fn(|i| i as u8);
+        Rng::new(seed).shuffle(&mut values);
+
+        let mut table = [0; 512];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = values[i & 255];
+        }
+        Permutation { table }
+    }
+
+    fn hash2(&self, x: i32, y: i32) -> u8 {
+        self.table[self.table[(x & 255) as usize] as usize + (y & 255) as usize]
+    }
+
+    fn hash3(&self, x: i32, y: i32, z: i32) -> u8 {
+        self.table[self.hash2(x, y) as usize + (z & 255) as usize]
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Dot product of the offset with one of eight 2D gradient directions.
+fn grad2(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// Dot product of the offset with one of the twelve edge directions of a cube.
+fn grad3(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    match hash & 15 {
+        0 | 12 => x + y,
+        1 | 14 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x + z,
+        5 => -x + z,
+        6 => x - z,
+        7 => -x - z,
+        8 => y + z,
+        9 | 13 => -y + z,
+        10 => y - z,
+        _ => -y - z,
+    }
+}
+
+/// Ken Perlin's improved gradient noise. It is zero at every integer lattice point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Perlin {
+    permutation: Permutation,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Perlin { permutation: Permutation::new(seed) }
+    }
+}
+
+impl Noise for Perlin {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+        let (xf, yf) = (x - x.floor(), y - y.floor());
+        let (u, v) = (fade(xf), fade(yf));
+        let p = &self.permutation;
+
+        let n00 = grad2(p.hash2(xi, yi), xf, yf);
+        let n10 = grad2(p.hash2(xi + 1, yi), xf - 1.0, yf);
+        let n01 = grad2(p.hash2(xi, yi + 1), xf, yf - 1.0);
+        let n11 = grad2(p.hash2(xi + 1, yi + 1), xf - 1.0, yf - 1.0);
+
+        lerp(lerp(n00, n10, u), lerp(n01, n11, u), v)
+    }
+
+    fn noise3(&self, point: &Vect3) -> f32 {
+        let (xi, yi, zi) = (point.x.floor() as i32, point.y.floor() as i32, point.z.floor() as i32);
+        let (x, y, z) = (point.x - point.x.floor(), point.y - point.y.floor(), point.z - point.z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let p = &self.permutation;
+
+        let corner = |dx: i32, dy: i32, dz: i32| {
+            grad3(p.hash3(xi + dx, yi + dy, zi + dz), x - dx as f32, y - dy as f32, z - dz as f32)
+        };
+
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+
+        lerp(lerp(x00, x10, v), lerp(x01, x11, v), w)
+    }
+}
+
+/// Simplex noise following Stefan Gustavson's reference implementation. Cheaper than
+/// `Perlin` in 3D and free of its axis-aligned artifacts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplex {
+    permutation: Permutation,
+}
+
+impl Simplex {
+    pub fn new(seed: u64) -> Self {
+        Simplex { permutation: Permutation::new(seed) }
+    }
+}
+
+impl Noise for Simplex {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        const F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+        const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+        let p = &self.permutation;
+
+        let s = (x + y) * F2;
+        let (i, j) = ((x + s).floor() as i32, (y + s).floor() as i32);
+        let t = (i + j) as f32 * G2;
+        let (x0, y0) = (x - (i as f32 - t), y - (j as f32 - t));
+
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 as f32 + G2, y0 - j1 as f32 + G2);
+        let (x2, y2) = (x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2);
+
+        let contribution = |hash: u8, x: f32, y: f32| {
+            let t = 0.5 - x * x - y * y;
+            if t < 0.0 { 0.0 } else { t * t * t * t * grad2(hash, x, y) }
+        };
+
+        let n0 = contribution(p.hash2(i, j), x0, y0);
+        let n1 = contribution(p.hash2(i + i1, j + j1), x1, y1);
+        let n2 = contribution(p.hash2(i + 1, j + 1), x2, y2);
+
+        40.0 * (n0 + n1 + n2)
+    }
+
+    fn noise3(&self, point: &Vect3) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+        let p = &self.permutation;
+        let (x, y, z) = (point.x, point.y, point.z);
+
+        let s = (x + y + z) * F3;
+        let (i, j, k) = ((x + s).floor() as i32, (y + s).floor() as i32, (z + s).floor() as i32);
+        let t = (i + j + k) as f32 * G3;
+        let (x0, y0, z0) = (x - (i as f32 - t), y - (j as f32 - t), z - (k as f32 - t));
+
+        // Find which of the six tetrahedra of the skewed cube the point is in.
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let offset = |di: i32, dj: i32, dk: i32, scale: f32| {
+            (x0 - di as f32 + scale * G3, y0 - dj as f32 + scale * G3, z0 - dk as f32 + scale * G3)
+        };
+        let corners = [
+            ((0, 0, 0), (x0, y0, z0)),
+            ((i1, j1, k1), offset(i1, j1, k1, 1.0)),
+            ((i2, j2, k2), offset(i2, j2, k2, 2.0)),
+            ((1, 1, 1), offset(1, 1, 1, 3.0)),
+        ];
+
+        let total: f32 = corners
+            .iter()
+            .map(|&((di, dj, dk), (x, y, z))| {
+                let t = 0.6 - x * x - y * y - z * z;
+                if t < 0.0 {
+                    0.0
+                } else {
+                    t * t * t * t * grad3(p.hash3(i + di, j + dj, k + dk), x, y, z)
+                }
+            })
+            .sum();
+
+        32.0 * total
+    }
+}
+
+/// Smoothly interpolated random values on the integer lattice. Blockier than gradient noise,
+/// but non-zero at lattice points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueNoise {
+    permutation: Permutation,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        ValueNoise { permutation: Permutation::new(seed) }
+    }
+
+    fn value(hash: u8) -> f32 {
+        hash as f32 / 127.5 - 1.0
+    }
+}
+
+impl Noise for ValueNoise {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+        let (u, v) = (fade(x - x.floor()), fade(y - y.floor()));
+        let p = &self.permutation;
+
+        let v00 = Self::value(p.hash2(xi, yi));
+        let v10 = Self::value(p.hash2(xi + 1, yi));
+        let v01 = Self::value(p.hash2(xi, yi + 1));
+        let v11 = Self::value(p.hash2(xi + 1, yi + 1));
+
+        lerp(lerp(v00, v10, u), lerp(v01, v11, u), v)
+    }
+
+    fn noise3(&self, point: &Vect3) -> f32 {
+        let (xi, yi, zi) = (point.x.floor() as i32, point.y.floor() as i32, point.z.floor() as i32);
+        let (u, v, w) = (
+            fade(point.x - point.x.floor()),
+            fade(point.y - point.y.floor()),
+            fade(point.z - point.z.floor()),
+        );
+        let p = &self.permutation;
+        let corner = |dx: i32, dy: i32, dz: i32| Self::value(p.hash3(xi + dx, yi + dy, zi + dz));
+
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+
+        lerp(lerp(x00, x10, v), lerp(x01, x11, v), w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> impl Iterator<Item = Vect3> {
+        (0..2000).map(|i| {
+            let i = i as f32;
+            Vect3::new(i * 0.173 - 150.0, i * 0.091 + 3.7, i * -0.057 + 20.0)
+        })
+    }
+
+    fn check_range<N: Noise>(noise: &N) {
+        for point in samples() {
+            let value2 = noise.noise2(point.x, point.y);
+            let value3 = noise.noise3(&point);
+            assert!((-1.0..=1.0).contains(&value2), "2D value out of range: {}", value2);
+            assert!((-1.0..=1.0).contains(&value3), "3D value out of range: {}", value3);
+        }
+    }
+
+    fn check_continuity<N: Noise>(noise: &N) {
+        let delta = Vect3::new(1e-3, 1e-3, 1e-3);
+        for point in samples().take(200) {
+            assert!((noise.noise3(&point) - noise.noise3(&(point + delta))).abs() < 0.05);
+            assert!((noise.noise2(point.x, point.y) - noise.noise2(point.x + 1e-3, point.y + 1e-3)).abs() < 0.05);
+        }
+    }
+
+    fn check_seeding<N: Noise>(a: &N, b: &N, c: &N) {
+        let point = Vect3::new(1.3, 2.7, -0.4);
+        assert_eq!(a.noise3(&point).to_bits(), b.noise3(&point).to_bits());
+        assert_ne!(a.noise3(&point), c.noise3(&point));
+    }
+
+    #[test]
+    fn test_perlin() {
+        let noise = Perlin::new(42);
+        check_range(&noise);
+        check_continuity(&noise);
+        check_seeding(&noise, &Perlin::new(42), &Perlin::new(43));
+        assert_eq!(noise.noise3(&Vect3::new(3.0, -2.0, 7.0)), 0.0);
+        assert_eq!(noise.noise2(5.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_simplex() {
+        let noise = Simplex::new(42);
+        check_range(&noise);
+        check_continuity(&noise);
+        check_seeding(&noise, &Simplex::new(42), &Simplex::new(43));
+    }
+
+    #[test]
+    fn test_value_noise() {
+        let noise = ValueNoise::new(42);
+        check_range(&noise);
+        check_continuity(&noise);
+        check_seeding(&noise, &ValueNoise::new(42), &ValueNoise::new(43));
+    }
+
+    #[test]
+    fn test_noise_is_not_constant() {
+        let noise = Simplex::new(1);
+        let values: Vec<f32> = samples().map(|point| noise.noise3(&point)).collect();
+        let min = values.iter().cloned().fold(f32::MAX, f32::min);
+        let max = values.iter().cloned().fold(f32::MIN, f32::max);
+        assert!(max - min > 1.0);
+    }
+
+    #[test]
+    fn test_noise3_vect_components_differ() {
+        let noise = Perlin::new(8);
+        let value = noise.noise3_vect(&Vect3::new(0.5, 0.25, 0.75));
+        assert_ne!(value.x, value.y);
+        assert_ne!(value.y, value.z);
+    }
+}
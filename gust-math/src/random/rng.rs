@@ -0,0 +1,213 @@
+use crate::vectors::vect3::Vect3;
+
+/// A seedable PCG32 (XSH-RR) pseudo random number generator.
+///
+/// The generator only uses integer arithmetic and every float is built from its bits, so a
+/// seed produces the same sequence on every platform and build. It is not cryptographically
+/// secure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    pub(crate) state: u64,
+    pub(crate) increment: u64,
+}
+
+impl Rng {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    pub fn new(seed: u64) -> Self {
+        Rng::with_stream(seed, 0)
+    }
+
+    /// Creates a generator on one of 2^63 independent streams, so e.g. terrain and prop
+    /// scattering can draw from the same world seed without influencing each other.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Rng {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// Derives a new, independent generator from this one.
+    pub fn fork(&mut self) -> Rng {
+        let seed = self.next_u64();
+        let stream = self.next_u64();
+        Rng::with_stream(seed, stream)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// A uniformly distributed float in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    /// A uniformly distributed integer in `[0, bound)`, without modulo bias.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "The bound must be positive");
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    /// A uniformly distributed integer in `[min, max]`.
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        assert!(min <= max, "The range must not be empty");
+        let span = max.wrapping_sub(min) as u32;
+        if span == u32::MAX {
+            return self.next_u32() as i32;
+        }
+        min.wrapping_add(self.below(span + 1) as i32)
+    }
+
+    /// A uniformly distributed float in `[min, max)`.
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.below(items.len() as u32) as usize)
+    }
+
+    /// Shuffles the items in place with a Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// A point uniformly distributed in the box spanned by `min` and `max`.
+    pub fn vect3_in_box(&mut self, min: Vect3, max: Vect3) -> Vect3 {
+        Vect3::new(
+            self.range_f32(min.x, max.x),
+            self.range_f32(min.y, max.y),
+            self.range_f32(min.z, max.z),
+        )
+    }
+
+    /// A point uniformly distributed inside the unit sphere.
+    pub fn vect3_in_unit_sphere(&mut self) -> Vect3 {
+        loop {
+            let point = self.vect3_in_box(Vect3::new(-1.0, -1.0, -1.0), Vect3::new(1.0, 1.0, 1.0));
+            if point.dot(&point) <= 1.0 {
+                return point;
+            }
+        }
+    }
+
+    /// A direction uniformly distributed over the unit sphere.
+    pub fn unit_vect3(&mut self) -> Vect3 {
+        loop {
+            if let Some(direction) = self.vect3_in_unit_sphere().try_normalized() {
+                return direction;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pcg32_reference_output() {
+        // First outputs of the reference implementation seeded with `pcg32_srandom(42, 54)`.
+        let mut rng = Rng::with_stream(42, 54);
+        let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+        for value in expected {
+            assert_eq!(rng.next_u32(), value);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(1234);
+        let mut b = Rng::new(1234);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+        assert_ne!(Rng::new(1).next_u32(), Rng::new(2).next_u32());
+        assert_ne!(Rng::with_stream(1, 0).next_u32(), Rng::with_stream(1, 1).next_u32());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let f = rng.next_f32();
+            assert!((0.0..1.0).contains(&f));
+            let i = rng.range_i32(-3, 3);
+            assert!((-3..=3).contains(&i));
+            let r = rng.range_f32(10.0, 20.0);
+            assert!((10.0..20.0).contains(&r));
+        }
+        assert_eq!(rng.range_i32(5, 5), 5);
+        let full = rng.range_i32(i32::MIN, i32::MAX);
+        assert!((i32::MIN..=i32::MAX).contains(&full));
+    }
+
+    #[test]
+    fn test_below_is_roughly_uniform() {
+        let mut rng = Rng::new(99);
+        let mut counts = [0; 6];
+        for _ in 0..60_000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+        for count in counts {
+            assert!((9_000..11_000).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut rng = Rng::new(3);
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        assert_eq!(rng.choose::<u32>(&[]), None);
+    }
+
+    #[test]
+    fn test_vect3_sampling() {
+        let mut rng = Rng::new(11);
+        for _ in 0..100 {
+            assert!(rng.vect3_in_unit_sphere().norm() <= 1.0);
+            assert!((rng.unit_vect3().norm() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_fork_is_deterministic() {
+        let mut a = Rng::new(5);
+        let mut b = Rng::new(5);
+        assert_eq!(a.fork().next_u32(), b.fork().next_u32());
+    }
+}
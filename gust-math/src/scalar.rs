@@ -0,0 +1,27 @@
+//! Scalar functions whose implementation depends on the `deterministic` feature.
+//!
+//! By default these forward to the standard library. With `deterministic` enabled they use the
+//! software implementations in `deterministic`, which are slower but bit-identical everywhere.
+
+#[cfg(feature = "deterministic")]
+pub use crate::deterministic::{cos, sin, sin_cos, sqrt};
+
+#[cfg(not(feature = "deterministic"))]
+pub fn sqrt(x: f32) -> f32 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "deterministic"))]
+pub fn sin(x: f32) -> f32 {
+    x.sin()
+}
+
+#[cfg(not(feature = "deterministic"))]
+pub fn cos(x: f32) -> f32 {
+    x.cos()
+}
+
+#[cfg(not(feature = "deterministic"))]
+pub fn sin_cos(x: f32) -> (f32, f32) {
+    x.sin_cos()
+}
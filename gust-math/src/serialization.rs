@@ -0,0 +1,238 @@
+//! `serde` support, enabled with the `serde` feature.
+//!
+//! Vectors and matrices serialize as plain arrays (`[x, y, z]`, `[[m00, m01, ..], ..]`) rather than
+//! structs, which keeps scene files and network messages compact and readable.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use num_traits::Float;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::matrices::mat3::Mat3;
+use crate::matrices::mat4::Mat4;
+use crate::matrices::matrix::Matrix;
+use crate::random::rng::Rng;
+use crate::vectors::vect3::Vect3;
+use crate::vectors::vect4::Vect4;
+use crate::vectors::vector::Vector;
+
+fn serialize_array<T: Serialize, S: Serializer>(values: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(values.len())?;
+    for value in values {
+        tuple.serialize_element(value)?;
+    }
+    tuple.end()
+}
+
+/// Deserializes exactly `N` elements. serde only implements arrays up to 32 elements and not for
+/// const generic lengths, so the generic types go through this instead.
+fn deserialize_array<'de, T: Deserialize<'de>, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[T; N], D::Error> {
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for ArrayVisitor<T, N> {
+        type Value = [T; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an array of length {}", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[T; N], A::Error> {
+            let mut values = Vec::with_capacity(N);
+            while let Some(value) = seq.next_element()? {
+                if values.len() == N {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                values.push(value);
+            }
+            let len = values.len();
+            values.try_into().map_err(|_| A::Error::invalid_length(len, &self))
+        }
+    }
+
+    deserializer.deserialize_tuple(N, ArrayVisitor::<T, N>(PhantomData))
+}
+
+impl Serialize for Vect3 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x, self.y, self.z].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vect3 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[f32; 3]>::deserialize(deserializer).map(Vect3::from)
+    }
+}
+
+impl Serialize for Vect4 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_array().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vect4 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[f32; 4]>::deserialize(deserializer).map(Vect4::from)
+    }
+}
+
+impl Serialize for Mat3 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mat3 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[[f32; 3]; 3]>::deserialize(deserializer).map(|data| Mat3::from_slice(&data))
+    }
+}
+
+impl Serialize for Mat4 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_slices().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mat4 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[[f32; 4]; 4]>::deserialize(deserializer).map(Mat4::from_slices)
+    }
+}
+
+impl<T: Float + Serialize, const N: usize> Serialize for Vector<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(&self.data, serializer)
+    }
+}
+
+impl<'de, T: Float + Deserialize<'de>, const N: usize> Deserialize<'de> for Vector<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_array(deserializer).map(Vector::new)
+    }
+}
+
+impl<T: Float + Serialize, const R: usize, const C: usize> Serialize for Matrix<T, R, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rows = self.data.map(Vector::new);
+        serialize_array(&rows, serializer)
+    }
+}
+
+impl<'de, T: Float + Deserialize<'de>, const R: usize, const C: usize> Deserialize<'de> for Matrix<T, R, C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows: [Vector<T, C>; R] = deserialize_array(deserializer)?;
+        Ok(Matrix::new(rows.map(|row| row.data)))
+    }
+}
+
+/// Serialized as `[state, increment]`, so a saved generator resumes exactly where it stopped.
+impl Serialize for Rng {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.state, self.increment].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rng {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [state, increment] = <[u64; 2]>::deserialize(deserializer)?;
+        if increment % 2 == 0 {
+            return Err(D::Error::custom("PCG increment must be odd"));
+        }
+        Ok(Rng { state, increment })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+    use crate::interpolation::easing::Easing;
+    use crate::interpolation::spline::CatmullRom;
+    use crate::matrices::matrix::Matrix4d;
+    use crate::vectors::vector::{Vector2d, Vector3f};
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> (String, T) {
+        let json = serde_json::to_string(value).unwrap();
+        let back = serde_json::from_str(&json).unwrap();
+        (json, back)
+    }
+
+    #[test]
+    fn test_vect3() {
+        let (json, back) = round_trip(&Vect3::new(1.0, -2.5, 3.0));
+        assert_eq!(json, "[1.0,-2.5,3.0]");
+        assert_eq!(back, Vect3::new(1.0, -2.5, 3.0));
+    }
+
+    #[test]
+    fn test_vect4() {
+        let v = Vect4::from([0.5, 1.0, 1.5, 2.0]);
+        let (json, back) = round_trip(&v);
+        assert_eq!(json, "[0.5,1.0,1.5,2.0]");
+        assert_eq!(back, v);
+    }
+
+    #[test]
+    fn test_mat3() {
+        let m = Mat3::from_slice(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let (json, back) = round_trip(&m);
+        assert_eq!(json, "[[1.0,2.0,3.0],[4.0,5.0,6.0],[7.0,8.0,9.0]]");
+        assert_eq!(back, m);
+    }
+
+    #[test]
+    fn test_mat4() {
+        let m = Mat4::identity().translate(Vect3::new(1.0, 2.0, 3.0));
+        let (json, back) = round_trip(&m);
+        assert!(json.ends_with(",[1.0,2.0,3.0,1.0]]"));
+        assert_eq!(back, m);
+    }
+
+    #[test]
+    fn test_generic_types() {
+        let v = Vector3f::new([1.0, 2.0, 3.0]);
+        let (json, back) = round_trip(&v);
+        assert_eq!(json, "[1.0,2.0,3.0]");
+        assert_eq!(back, v);
+
+        let m = Matrix::<f64, 2, 3>::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let (json, back) = round_trip(&m);
+        assert_eq!(json, "[[1.0,2.0,3.0],[4.0,5.0,6.0]]");
+        assert_eq!(back, m);
+
+        let identity = Matrix4d::identity();
+        assert_eq!(round_trip(&identity).1, identity);
+    }
+
+    #[test]
+    fn test_wrong_length_is_rejected() {
+        assert!(serde_json::from_str::<Vect3>("[1.0,2.0]").is_err());
+        assert!(serde_json::from_str::<Vector2d>("[1.0,2.0,3.0]").is_err());
+        assert!(serde_json::from_str::<Matrix<f32, 2, 2>>("[[1.0,2.0],[3.0]]").is_err());
+    }
+
+    #[test]
+    fn test_rng_resumes() {
+        let mut rng = Rng::new(7);
+        rng.next_u32();
+        let (_, mut back) = round_trip(&rng);
+        assert_eq!(back.next_u64(), rng.next_u64());
+        assert!(serde_json::from_str::<Rng>("[1,2]").is_err());
+    }
+
+    #[test]
+    fn test_derived_types() {
+        let spline = CatmullRom::centripetal(vec![Vect3::new(0.0, 0.0, 0.0), Vect3::new(1.0, 2.0, 0.0)]);
+        assert_eq!(round_trip(&spline).1, spline);
+        let (json, back) = round_trip(&Easing::CubicInOut);
+        assert_eq!(json, "\"CubicInOut\"");
+        assert_eq!(back, Easing::CubicInOut);
+    }
+}
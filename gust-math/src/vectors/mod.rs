@@ -1,2 +1,3 @@
 pub mod vect3;
-pub mod vect4;
\ No newline at end of file
+pub mod vect4;
+pub mod vector;
\ No newline at end of file
@@ -1,8 +1,9 @@
 use std::ops::{Index, IndexMut};
 
+use crate::scalar;
 use crate::vectors::vect4::Vect4;
 
-#[derive(Copy)]
+#[derive(Clone, Copy)]
 pub struct Vect3 {
     pub x: f32,
     pub y: f32,
@@ -35,18 +36,41 @@ impl Vect3 {
     }
 
     pub fn norm(&self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+        scalar::sqrt(self.x * self.x + self.y * self.y + self.z * self.z)
     }
 
-    pub fn normalize(&mut self) -> Vect3 {
+    /// Normalizes the vector in place. A zero vector is left untouched.
+    pub fn normalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// Returns a normalized copy of the vector. A zero vector is returned as is.
+    pub fn normalized(&self) -> Vect3 {
         let norm = self.norm();
         if norm == 0.0 {
-            return self.clone();
+            return *self;
+        }
+        *self / norm
+    }
+
+    /// Returns a normalized copy of the vector, or `None` if the vector is too short (or not
+    /// finite) to have a meaningful direction.
+    pub fn try_normalized(&self) -> Option<Vect3> {
+        let norm = self.norm();
+        if !norm.is_finite() || norm <= f32::EPSILON {
+            return None;
         }
-        self.x = self.x / norm;
-        self.y = self.y / norm;
-        self.z = self.z / norm;
-        self.clone()
+        Some(*self / norm)
+    }
+
+    /// Returns `true` if any component is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.x.is_nan() || self.y.is_nan() || self.z.is_nan()
+    }
+
+    /// Returns `true` if every component is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
     }
 
     pub fn cross(&self, other: &Vect3) -> Vect3 {
@@ -80,15 +104,81 @@ impl Vect3 {
         let cos_theta = dot / (norm_self * norm_other);
         cos_theta.acos()
     }
-}
 
-impl Clone for Vect3 {
-    fn clone(&self) -> Vect3 {
+    /// Linearly interpolates between `self` (at `t = 0`) and `other` (at `t = 1`).
+    pub fn lerp(&self, other: &Vect3, t: f32) -> Vect3 {
+        *self + (*other - *self) * t
+    }
+
+    /// Spherically interpolates the direction from `self` (at `t = 0`) to `other` (at `t = 1`)
+    /// at constant angular speed, while the length is interpolated linearly.
+    /// Falls back to `lerp` when either vector is zero.
+    pub fn slerp(&self, other: &Vect3, t: f32) -> Vect3 {
+        let (from, to) = match (self.try_normalized(), other.try_normalized()) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return self.lerp(other, t),
+        };
+        let length = self.norm() + (other.norm() - self.norm()) * t;
+
+        let theta = from.dot(&to).clamp(-1.0, 1.0).acos();
+        if theta < 1e-4 {
+            return from.lerp(&to, t).normalized() * length;
+        }
+
+        let sin_theta = theta.sin();
+        let direction = if sin_theta < 1e-4 {
+            // Opposite directions: any great circle works, so rotate about an arbitrary perpendicular axis.
+            let helper = if from.x.abs() < 0.9 { Vect3::new(1.0, 0.0, 0.0) } else { Vect3::new(0.0, 1.0, 0.0) };
+            let perpendicular = from.cross(&helper).normalized();
+            from * (theta * t).cos() + perpendicular * (theta * t).sin()
+        } else {
+            from * (((1.0 - t) * theta).sin() / sin_theta) + to * ((t * theta).sin() / sin_theta)
+        };
+        direction * length
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Vect3) -> Vect3 {
+        Vect3 {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Vect3) -> Vect3 {
+        Vect3 {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    pub fn abs(&self) -> Vect3 {
         Vect3 {
-            x: self.x,
-            y: self.y,
-            z: self.z,
+            x: self.x.abs(),
+            y: self.y.abs(),
+            z: self.z.abs(),
+        }
+    }
+
+    pub fn distance(&self, other: &Vect3) -> f32 {
+        (*other - *self).norm()
+    }
+
+    /// Reflects the vector off a surface with the given normal. The normal is expected to be normalized.
+    pub fn reflect(&self, normal: &Vect3) -> Vect3 {
+        *self - *normal * (2.0 * self.dot(normal))
+    }
+
+    /// Projects the vector onto `other`. Projecting onto a zero vector yields a zero vector.
+    pub fn project(&self, other: &Vect3) -> Vect3 {
+        let norm_squared = other.dot(other);
+        if norm_squared == 0.0 {
+            return Vect3::zeros();
         }
+        *other * (self.dot(other) / norm_squared)
     }
 }
 
@@ -104,6 +194,12 @@ impl std::ops::Add<Vect3> for Vect3 {
     }
 }
 
+impl std::ops::AddAssign<Vect3> for Vect3 {
+    fn add_assign(&mut self, other: Vect3) {
+        *self = *self + other;
+    }
+}
+
 impl Index<usize> for Vect3 {
     type Output = f32;
 
@@ -140,6 +236,12 @@ impl std::ops::Sub<Vect3> for Vect3 {
     }
 }
 
+impl std::ops::SubAssign<Vect3> for Vect3 {
+    fn sub_assign(&mut self, other: Vect3) {
+        *self = *self - other;
+    }
+}
+
 impl std::ops::Mul<f32> for Vect3 {
     type Output = Vect3;
 
@@ -164,6 +266,68 @@ impl std::ops::Mul<Vect3> for f32 {
     }
 }
 
+/// Component-wise (Hadamard) product.
+impl std::ops::Mul<Vect3> for Vect3 {
+    type Output = Vect3;
+
+    fn mul(self, rhs: Vect3) -> Vect3 {
+        Vect3 {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vect3 {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::MulAssign<Vect3> for Vect3 {
+    fn mul_assign(&mut self, rhs: Vect3) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::Div<f32> for Vect3 {
+    type Output = Vect3;
+
+    fn div(self, rhs: f32) -> Vect3 {
+        Vect3 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+        }
+    }
+}
+
+/// Component-wise division.
+impl std::ops::Div<Vect3> for Vect3 {
+    type Output = Vect3;
+
+    fn div(self, rhs: Vect3) -> Vect3 {
+        Vect3 {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+            z: self.z / rhs.z,
+        }
+    }
+}
+
+impl std::ops::DivAssign<f32> for Vect3 {
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs;
+    }
+}
+
+impl std::ops::DivAssign<Vect3> for Vect3 {
+    fn div_assign(&mut self, rhs: Vect3) {
+        *self = *self / rhs;
+    }
+}
+
 impl std::ops::Neg for Vect3 {
     type Output = Vect3;
 
@@ -222,4 +386,186 @@ impl From<Vect4> for Vect3 {
             z: vect4.z,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::assert_approx_eq;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= EPSILON * (1.0 + a.abs().max(b.abs()))
+    }
+
+    fn vect3_close(a: Vect3, b: Vect3) -> bool {
+        close(a.x, b.x) && close(a.y, b.y) && close(a.z, b.z)
+    }
+
+    fn vect3() -> impl Strategy<Value = Vect3> {
+        (-100.0f32..100.0, -100.0f32..100.0, -100.0f32..100.0).prop_map(Vect3::from)
+    }
+
+    #[test]
+    fn test_normalize_in_place() {
+        let mut v = Vect3::new(3.0, 0.0, 4.0);
+        v.normalize();
+        assert_eq!(v, Vect3::new(0.6, 0.0, 0.8));
+    }
+
+    #[test]
+    fn test_normalized_zero_vector() {
+        assert_eq!(Vect3::zeros().normalized(), Vect3::zeros());
+    }
+
+    #[test]
+    fn test_try_normalized() {
+        assert_eq!(Vect3::zeros().try_normalized(), None);
+        assert_eq!(Vect3::new(1e-30, 0.0, 0.0).try_normalized(), None);
+        assert_eq!(Vect3::new(f32::NAN, 1.0, 0.0).try_normalized(), None);
+        assert_eq!(Vect3::new(0.0, 2.0, 0.0).try_normalized(), Some(Vect3::new(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_nan_checks() {
+        assert!(Vect3::new(0.0, f32::NAN, 0.0).is_nan());
+        assert!(!Vect3::new(0.0, f32::INFINITY, 0.0).is_finite());
+        assert!(Vect3::new(1.0, 2.0, 3.0).is_finite());
+    }
+
+    #[test]
+    fn test_slerp() {
+        let a = Vect3::new(1.0, 0.0, 0.0);
+        let b = Vect3::new(0.0, 2.0, 0.0);
+        let half = std::f32::consts::FRAC_1_SQRT_2 * 1.5;
+        assert_approx_eq!(a.slerp(&b, 0.0), a);
+        assert_approx_eq!(a.slerp(&b, 1.0), b);
+        assert_approx_eq!(a.slerp(&b, 0.5), Vect3::new(half, half, 0.0));
+
+        let opposite = a.slerp(&-a, 0.5);
+        assert_approx_eq!(opposite.norm(), 1.0);
+        assert_approx_eq!(opposite.dot(&a), 0.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_reflect() {
+        let v = Vect3::new(1.0, -1.0, 0.0);
+        let normal = Vect3::new(0.0, 1.0, 0.0);
+        assert_eq!(v.reflect(&normal), Vect3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_project() {
+        let v = Vect3::new(2.0, 3.0, 4.0);
+        assert_eq!(v.project(&Vect3::new(0.0, 5.0, 0.0)), Vect3::new(0.0, 3.0, 0.0));
+        assert_eq!(v.project(&Vect3::zeros()), Vect3::zeros());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_sub_roundtrip(a in vect3(), b in vect3()) {
+            prop_assert!(vect3_close(a + b - b, a));
+        }
+
+        #[test]
+        fn prop_assign_ops_match_binary_ops(a in vect3(), b in vect3(), s in 0.1f32..10.0) {
+            let mut c = a;
+            c += b;
+            prop_assert_eq!(c, a + b);
+
+            let mut c = a;
+            c -= b;
+            prop_assert_eq!(c, a - b);
+
+            let mut c = a;
+            c *= s;
+            prop_assert_eq!(c, a * s);
+
+            let mut c = a;
+            c *= b;
+            prop_assert_eq!(c, a * b);
+
+            let mut c = a;
+            c /= s;
+            prop_assert_eq!(c, a / s);
+        }
+
+        #[test]
+        fn prop_scalar_mul_commutes(a in vect3(), s in -10.0f32..10.0) {
+            prop_assert_eq!(a * s, s * a);
+        }
+
+        #[test]
+        fn prop_component_mul_div_roundtrip(a in vect3(), b in vect3()) {
+            prop_assume!(b.x.abs() > 0.01 && b.y.abs() > 0.01 && b.z.abs() > 0.01);
+            prop_assert!(vect3_close(a * b / b, a));
+        }
+
+        #[test]
+        fn prop_normalized_has_unit_length(a in vect3()) {
+            prop_assume!(a.norm() > 1e-3);
+            prop_assert!(close(a.normalized().norm(), 1.0));
+        }
+
+        #[test]
+        fn prop_normalized_does_not_mutate(a in vect3()) {
+            let copy = a;
+            let _ = a.normalized();
+            prop_assert_eq!(a, copy);
+        }
+
+        #[test]
+        fn prop_cross_is_orthogonal(a in vect3(), b in vect3()) {
+            let c = a.cross(&b);
+            prop_assert!(close(c.dot(&a) / (1.0 + a.norm() * c.norm()), 0.0));
+            prop_assert!(close(c.dot(&b) / (1.0 + b.norm() * c.norm()), 0.0));
+        }
+
+        #[test]
+        fn prop_lerp_endpoints(a in vect3(), b in vect3()) {
+            prop_assert!(vect3_close(a.lerp(&b, 0.0), a));
+            prop_assert!(vect3_close(a.lerp(&b, 1.0), b));
+        }
+
+        #[test]
+        fn prop_min_max_bound_components(a in vect3(), b in vect3()) {
+            let min = a.min(&b);
+            let max = a.max(&b);
+            for i in 0..3 {
+                prop_assert!(min[i] <= a[i] && min[i] <= b[i]);
+                prop_assert!(max[i] >= a[i] && max[i] >= b[i]);
+            }
+        }
+
+        #[test]
+        fn prop_abs_is_non_negative(a in vect3()) {
+            let abs = a.abs();
+            prop_assert!(abs.x >= 0.0 && abs.y >= 0.0 && abs.z >= 0.0);
+            prop_assert!(close(abs.norm(), a.norm()));
+        }
+
+        #[test]
+        fn prop_distance_is_symmetric(a in vect3(), b in vect3()) {
+            prop_assert!(close(a.distance(&b), b.distance(&a)));
+            prop_assert!(close(a.distance(&b), (a - b).norm()));
+        }
+
+        #[test]
+        fn prop_reflect_preserves_length(a in vect3(), n in vect3()) {
+            prop_assume!(n.norm() > 1e-3);
+            let n = n.normalized();
+            prop_assert!(close(a.reflect(&n).norm(), a.norm()));
+        }
+
+        #[test]
+        fn prop_project_is_parallel(a in vect3(), b in vect3()) {
+            prop_assume!(b.norm() > 1e-3);
+            let p = a.project(&b);
+            prop_assert!(close(p.cross(&b).norm() / (1.0 + p.norm() * b.norm()), 0.0));
+            prop_assert!(close((a - p).dot(&b) / (1.0 + a.norm() * b.norm()), 0.0));
+        }
+    }
+}
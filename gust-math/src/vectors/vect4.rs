@@ -1,6 +1,8 @@
 use std::ops::{Index, IndexMut};
+use crate::scalar;
 use crate::vectors::vect3::Vect3;
 
+#[derive(Clone, Copy)]
 pub struct Vect4 {
     pub x: f32,
     pub y: f32,
@@ -36,19 +38,41 @@ impl Vect4 {
     }
 
     pub fn norm(&self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+        scalar::sqrt(self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w)
     }
 
-    pub fn normalize(&mut self) -> Vect4 {
+    /// Normalizes the vector in place. A zero vector is left untouched.
+    pub fn normalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// Returns a normalized copy of the vector. A zero vector is returned as is.
+    pub fn normalized(&self) -> Vect4 {
         let norm = self.norm();
         if norm == 0.0 {
-            return self.clone();
+            return *self;
         }
-        self.x = self.x / norm;
-        self.y = self.y / norm;
-        self.z = self.z / norm;
-        self.w = self.w / norm;
-        self.clone()
+        *self / norm
+    }
+
+    /// Returns a normalized copy of the vector, or `None` if the vector is too short (or not
+    /// finite) to have a meaningful direction.
+    pub fn try_normalized(&self) -> Option<Vect4> {
+        let norm = self.norm();
+        if !norm.is_finite() || norm <= f32::EPSILON {
+            return None;
+        }
+        Some(*self / norm)
+    }
+
+    /// Returns `true` if any component is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.to_array().iter().any(|value| value.is_nan())
+    }
+
+    /// Returns `true` if every component is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.to_array().iter().all(|value| value.is_finite())
     }
 
     pub fn dot(&self, other: &Vect4) -> f32 {
@@ -63,6 +87,49 @@ impl Vect4 {
             w: 0.0,
         }
     }
+
+    /// Linearly interpolates between `self` (at `t = 0`) and `other` (at `t = 1`).
+    pub fn lerp(&self, other: &Vect4, t: f32) -> Vect4 {
+        *self + (*other - *self) * t
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Vect4) -> Vect4 {
+        Vect4::from_slice(&[self.x.min(other.x), self.y.min(other.y), self.z.min(other.z), self.w.min(other.w)])
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Vect4) -> Vect4 {
+        Vect4::from_slice(&[self.x.max(other.x), self.y.max(other.y), self.z.max(other.z), self.w.max(other.w)])
+    }
+
+    pub fn abs(&self) -> Vect4 {
+        Vect4::from_slice(&[self.x.abs(), self.y.abs(), self.z.abs(), self.w.abs()])
+    }
+
+    pub fn distance(&self, other: &Vect4) -> f32 {
+        (*other - *self).norm()
+    }
+
+    /// Reflects the vector off a surface with the given normal. The normal is expected to be normalized.
+    pub fn reflect(&self, normal: &Vect4) -> Vect4 {
+        *self - *normal * (2.0 * self.dot(normal))
+    }
+
+    /// Projects the vector onto `other`. Projecting onto a zero vector yields a zero vector.
+    pub fn project(&self, other: &Vect4) -> Vect4 {
+        let norm_squared = other.dot(other);
+        if norm_squared == 0.0 {
+            return Vect4::new();
+        }
+        *other * (self.dot(other) / norm_squared)
+    }
+}
+
+impl Default for Vect4 {
+    fn default() -> Self {
+        Vect4::new()
+    }
 }
 
 impl std::ops::Add<Vect4> for Vect4 {
@@ -73,6 +140,12 @@ impl std::ops::Add<Vect4> for Vect4 {
     }
 }
 
+impl std::ops::AddAssign<Vect4> for Vect4 {
+    fn add_assign(&mut self, other: Vect4) {
+        *self = *self + other;
+    }
+}
+
 impl std::ops::Sub<Vect4> for Vect4 {
     type Output = Vect4;
 
@@ -81,6 +154,12 @@ impl std::ops::Sub<Vect4> for Vect4 {
     }
 }
 
+impl std::ops::SubAssign<Vect4> for Vect4 {
+    fn sub_assign(&mut self, other: Vect4) {
+        *self = *self - other;
+    }
+}
+
 impl std::ops::Mul<f32> for Vect4 {
     type Output = Vect4;
 
@@ -89,6 +168,64 @@ impl std::ops::Mul<f32> for Vect4 {
     }
 }
 
+impl std::ops::Mul<Vect4> for f32 {
+    type Output = Vect4;
+
+    fn mul(self, rhs: Vect4) -> Vect4 {
+        Vect4::from_slice(&[self * rhs.x, self * rhs.y, self * rhs.z, self * rhs.w])
+    }
+}
+
+/// Component-wise (Hadamard) product.
+impl std::ops::Mul<Vect4> for Vect4 {
+    type Output = Vect4;
+
+    fn mul(self, rhs: Vect4) -> Vect4 {
+        Vect4::from_slice(&[self.x * rhs.x, self.y * rhs.y, self.z * rhs.z, self.w * rhs.w])
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vect4 {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::MulAssign<Vect4> for Vect4 {
+    fn mul_assign(&mut self, rhs: Vect4) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::Div<f32> for Vect4 {
+    type Output = Vect4;
+
+    fn div(self, rhs: f32) -> Vect4 {
+        Vect4::from_slice(&[self.x / rhs, self.y / rhs, self.z / rhs, self.w / rhs])
+    }
+}
+
+/// Component-wise division.
+impl std::ops::Div<Vect4> for Vect4 {
+    type Output = Vect4;
+
+    fn div(self, rhs: Vect4) -> Vect4 {
+        Vect4::from_slice(&[self.x / rhs.x, self.y / rhs.y, self.z / rhs.z, self.w / rhs.w])
+    }
+}
+
+impl std::ops::DivAssign<f32> for Vect4 {
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs;
+    }
+}
+
+impl std::ops::DivAssign<Vect4> for Vect4 {
+    fn div_assign(&mut self, rhs: Vect4) {
+        *self = *self / rhs;
+    }
+}
+
 impl std::ops::Neg for Vect4 {
     type Output = Vect4;
 
@@ -103,9 +240,15 @@ impl std::fmt::Display for Vect4 {
     }
 }
 
-impl Clone for Vect4 {
-    fn clone(&self) -> Vect4 {
-        Vect4::from_slice(&[self.x, self.y, self.z, self.w])
+impl std::fmt::Debug for Vect4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Vect4({}, {}, {}, {})", self.x, self.y, self.z, self.w)
+    }
+}
+
+impl PartialEq for Vect4 {
+    fn eq(&self, other: &Vect4) -> bool {
+        self.x == other.x && self.y == other.y && self.z == other.z && self.w == other.w
     }
 }
 
@@ -166,4 +309,106 @@ impl From<Vect3> for Vect4 {
             w: 0.0,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= EPSILON * (1.0 + a.abs().max(b.abs()))
+    }
+
+    fn vect4_close(a: Vect4, b: Vect4) -> bool {
+        (0..4).all(|i| close(a[i], b[i]))
+    }
+
+    fn vect4() -> impl Strategy<Value = Vect4> {
+        (-100.0f32..100.0, -100.0f32..100.0, -100.0f32..100.0, -100.0f32..100.0).prop_map(Vect4::from)
+    }
+
+    #[test]
+    fn test_component_mul() {
+        let a = Vect4::from([1.0, 2.0, 3.0, 4.0]);
+        let b = Vect4::from([2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(a * b, Vect4::from([2.0, 6.0, 12.0, 20.0]));
+    }
+
+    #[test]
+    fn test_normalized_zero_vector() {
+        assert_eq!(Vect4::new().normalized(), Vect4::new());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_sub_roundtrip(a in vect4(), b in vect4()) {
+            prop_assert!(vect4_close(a + b - b, a));
+        }
+
+        #[test]
+        fn prop_assign_ops_match_binary_ops(a in vect4(), b in vect4(), s in 0.1f32..10.0) {
+            let mut c = a;
+            c += b;
+            prop_assert_eq!(c, a + b);
+
+            let mut c = a;
+            c -= b;
+            prop_assert_eq!(c, a - b);
+
+            let mut c = a;
+            c *= s;
+            prop_assert_eq!(c, a * s);
+
+            let mut c = a;
+            c *= b;
+            prop_assert_eq!(c, a * b);
+
+            let mut c = a;
+            c /= s;
+            prop_assert_eq!(c, a / s);
+        }
+
+        #[test]
+        fn prop_scalar_mul_commutes(a in vect4(), s in -10.0f32..10.0) {
+            prop_assert_eq!(a * s, s * a);
+        }
+
+        #[test]
+        fn prop_normalized_has_unit_length(a in vect4()) {
+            prop_assume!(a.norm() > 1e-3);
+            prop_assert!(close(a.normalized().norm(), 1.0));
+        }
+
+        #[test]
+        fn prop_lerp_endpoints(a in vect4(), b in vect4()) {
+            prop_assert!(vect4_close(a.lerp(&b, 0.0), a));
+            prop_assert!(vect4_close(a.lerp(&b, 1.0), b));
+        }
+
+        #[test]
+        fn prop_min_max_bound_components(a in vect4(), b in vect4()) {
+            let min = a.min(&b);
+            let max = a.max(&b);
+            for i in 0..4 {
+                prop_assert!(min[i] <= a[i] && min[i] <= b[i]);
+                prop_assert!(max[i] >= a[i] && max[i] >= b[i]);
+            }
+        }
+
+        #[test]
+        fn prop_distance_is_symmetric(a in vect4(), b in vect4()) {
+            prop_assert!(close(a.distance(&b), b.distance(&a)));
+        }
+
+        #[test]
+        fn prop_reflect_preserves_length(a in vect4(), n in vect4()) {
+            prop_assume!(n.norm() > 1e-3);
+            let n = n.normalized();
+            prop_assert!(close(a.reflect(&n).norm(), a.norm()));
+        }
+    }
+}
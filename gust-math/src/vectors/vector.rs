@@ -0,0 +1,253 @@
+use std::ops::{Index, IndexMut};
+
+use num_traits::Float;
+
+use crate::vectors::vect3::Vect3;
+use crate::vectors::vect4::Vect4;
+
+/// A fixed-size vector over any floating point type.
+///
+/// `Vect3` and `Vect4` remain the `f32` types used throughout the engine; this type is the
+/// generic foundation for code that needs other dimensions or `f64` precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector<T: Float, const N: usize> {
+    pub data: [T; N],
+}
+
+pub type Vector2f = Vector<f32, 2>;
+pub type Vector3f = Vector<f32, 3>;
+pub type Vector4f = Vector<f32, 4>;
+pub type Vector2d = Vector<f64, 2>;
+pub type Vector3d = Vector<f64, 3>;
+pub type Vector4d = Vector<f64, 4>;
+
+impl<T: Float, const N: usize> Vector<T, N> {
+    pub fn new(data: [T; N]) -> Self {
+        Vector { data }
+    }
+
+    pub fn zeros() -> Self {
+        Vector { data: [T::zero(); N] }
+    }
+
+    pub fn splat(value: T) -> Self {
+        Vector { data: [value; N] }
+    }
+
+    pub fn to_array(&self) -> [T; N] {
+        self.data
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .fold(T::zero(), |acc, (a, b)| acc + *a * *b)
+    }
+
+    pub fn norm(&self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns a normalized copy of the vector. A zero vector is returned as is.
+    pub fn normalized(&self) -> Self {
+        let norm = self.norm();
+        if norm == T::zero() {
+            return *self;
+        }
+        *self / norm
+    }
+
+    /// Returns a normalized copy of the vector, or `None` if the vector is too short (or not
+    /// finite) to have a meaningful direction.
+    pub fn try_normalized(&self) -> Option<Self> {
+        let norm = self.norm();
+        if !norm.is_finite() || norm <= T::epsilon() {
+            return None;
+        }
+        Some(*self / norm)
+    }
+
+    /// Returns `true` if any component is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.data.iter().any(|value| value.is_nan())
+    }
+
+    /// Returns `true` if every component is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().all(|value| value.is_finite())
+    }
+
+    /// Applies `f` to every component.
+    pub fn map<F: Fn(T) -> T>(&self, f: F) -> Self {
+        Vector { data: self.data.map(f) }
+    }
+
+    /// Combines the components of two vectors pairwise with `f`.
+    pub fn zip_map<F: Fn(T, T) -> T>(&self, other: &Self, f: F) -> Self {
+        let mut data = self.data;
+        for (value, other) in data.iter_mut().zip(other.data.iter()) {
+            *value = f(*value, *other);
+        }
+        Vector { data }
+    }
+
+    /// Converts every component to another floating point type.
+    pub fn cast<U: Float>(&self) -> Vector<U, N> {
+        let mut data = [U::zero(); N];
+        for (value, own) in data.iter_mut().zip(self.data.iter()) {
+            *value = U::from(*own).unwrap();
+        }
+        Vector { data }
+    }
+}
+
+impl<T: Float> Vector<T, 3> {
+    pub fn cross(&self, other: &Self) -> Self {
+        let [x, y, z] = self.data;
+        let [ox, oy, oz] = other.data;
+        Vector {
+            data: [y * oz - z * oy, z * ox - x * oz, x * oy - y * ox],
+        }
+    }
+}
+
+impl<T: Float, const N: usize> Default for Vector<T, N> {
+    fn default() -> Self {
+        Vector::zeros()
+    }
+}
+
+impl<T: Float, const N: usize> std::ops::Add for Vector<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_map(&other, |a, b| a + b)
+    }
+}
+
+impl<T: Float, const N: usize> std::ops::Sub for Vector<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_map(&other, |a, b| a - b)
+    }
+}
+
+impl<T: Float, const N: usize> std::ops::Mul<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        self.map(|a| a * rhs)
+    }
+}
+
+/// Component-wise (Hadamard) product.
+impl<T: Float, const N: usize> std::ops::Mul for Vector<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.zip_map(&rhs, |a, b| a * b)
+    }
+}
+
+impl<T: Float, const N: usize> std::ops::Div<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        self.map(|a| a / rhs)
+    }
+}
+
+impl<T: Float, const N: usize> std::ops::Neg for Vector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Float, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: Float, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+impl<T: Float, const N: usize> From<[T; N]> for Vector<T, N> {
+    fn from(data: [T; N]) -> Self {
+        Vector { data }
+    }
+}
+
+impl From<Vect3> for Vector3f {
+    fn from(vect3: Vect3) -> Self {
+        Vector::new(vect3.as_slice())
+    }
+}
+
+impl From<Vector3f> for Vect3 {
+    fn from(vector: Vector3f) -> Self {
+        Vect3::from(vector.data)
+    }
+}
+
+impl From<Vect4> for Vector4f {
+    fn from(vect4: Vect4) -> Self {
+        Vector::new(vect4.to_array())
+    }
+}
+
+impl From<Vector4f> for Vect4 {
+    fn from(vector: Vector4f) -> Self {
+        Vect4::from(vector.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_cross_matches_vect3() {
+        let a = Vect3::new(1.0, 2.0, 3.0);
+        let b = Vect3::new(-4.0, 0.5, 2.0);
+        let cross: Vect3 = Vector3f::from(a).cross(&Vector3f::from(b)).into();
+        assert_eq!(cross, a.cross(&b));
+    }
+
+    #[test]
+    fn test_f64_normalized() {
+        let v = Vector3d::new([3.0, 0.0, 4.0]).normalized();
+        assert_eq!(v, Vector3d::new([0.6, 0.0, 0.8]));
+    }
+
+    #[test]
+    fn test_cast() {
+        let v = Vector2d::new([1.5, -2.25]).cast::<f32>();
+        assert_eq!(v, Vector2f::new([1.5, -2.25]));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ops_match_vect3(a in prop::array::uniform3(-100.0f32..100.0), b in prop::array::uniform3(-100.0f32..100.0), s in -10.0f32..10.0) {
+            let (va, vb) = (Vect3::from(a), Vect3::from(b));
+            let (ga, gb) = (Vector3f::new(a), Vector3f::new(b));
+            prop_assert_eq!(Vect3::from(ga + gb), va + vb);
+            prop_assert_eq!(Vect3::from(ga - gb), va - vb);
+            prop_assert_eq!(Vect3::from(ga * gb), va * vb);
+            prop_assert_eq!(Vect3::from(ga * s), va * s);
+            prop_assert_eq!(Vect3::from(-ga), -va);
+            prop_assert_eq!(ga.dot(&gb), va.dot(&vb));
+        }
+    }
+}
@@ -13,13 +13,17 @@ in vec2 v_tex_coords;
 uniform sampler2D u_texture;
 uniform int u_light_count;
 
-out vec4 color;
+uniform vec3 u_ambient_color;
+uniform vec4 u_diffuse_color;
+uniform vec3 u_specular_color;
+uniform vec3 u_emissive_color;
+uniform float u_shininess;
 
-const vec3 ambient_color = vec3(0.2, 0.2, 0.2);
-const vec3 specular_color = vec3(1.0, 1.0, 1.0);
+out vec4 color;
 
 void main() {
-    vec3 total_light = vec3(0.0);
+    vec3 diffuse_light = u_ambient_color;
+    vec3 specular_light = vec3(0.0);
 
     vec3 normal = normalize(v_normal);
     vec3 camera_dir = normalize(-v_position);
@@ -29,13 +33,15 @@ void main() {
 
         float diffuse = max(dot(normal, light_dir), 0.0);
         vec3 half_direction = normalize(light_dir + camera_dir);
-        float specular = pow(max(dot(half_direction, normal), 0.0), 16.0);
+        float specular = pow(max(dot(half_direction, normal), 0.0), u_shininess);
 
         vec3 light_color = light_colors[i];
 
-        total_light += light_color * (diffuse + specular);
+        diffuse_light += light_color * diffuse;
+        specular_light += light_color * specular;
     }
 
-    vec4 tex_color = texture(u_texture, v_tex_coords);
-    color = vec4(total_light, 1.0) * tex_color;
+    vec4 surface_color = texture(u_texture, v_tex_coords) * u_diffuse_color;
+    vec3 lit_color = diffuse_light * surface_color.rgb + specular_light * u_specular_color + u_emissive_color;
+    color = vec4(lit_color, surface_color.a);
 }